@@ -1,9 +1,11 @@
 use crate::commands::{
-    ActivateArgs, AliasArgs, BinArgs, CleanArgs, CompletionsArgs, DiagnoseArgs, ExecArgs,
+    ActivateArgs, AliasArgs, BinArgs, CleanArgs, CompletionsArgs, DiagnoseArgs, DuArgs, ExecArgs,
     InstallArgs, McpArgs, MigrateArgs, OutdatedArgs, PinArgs, RegenArgs, RunArgs, SetupArgs,
-    ShellArgs, StatusArgs, UnaliasArgs, UninstallArgs, UnpinArgs, UpgradeArgs, VersionsArgs,
+    ShellArgs, StatusArgs, UnaliasArgs, UninstallArgs, UnpinArgs, UpgradeArgs, VerifyArgs,
+    VersionsArgs,
     debug::{DebugConfigArgs, DebugEnvArgs},
     plugin::{PluginAddArgs, PluginInfoArgs, PluginListArgs, PluginRemoveArgs, PluginSearchArgs},
+    store::StoreMoveArgs,
 };
 use clap::builder::styling::{Color, Style, Styles};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -325,6 +327,13 @@ pub enum Commands {
     )]
     Diagnose(DiagnoseArgs),
 
+    #[command(
+        name = "du",
+        about = "Display the disk usage of the proto store.",
+        long_about = "Display the disk usage of the proto store, for each tool and version, and the plugins, cache, and temporary directories."
+    )]
+    Du(DuArgs),
+
     #[command(
         alias = "x",
         name = "exec",
@@ -407,6 +416,12 @@ pub enum Commands {
     )]
     Status(StatusArgs),
 
+    #[command(name = "store", about = "Operations for managing the proto store.")]
+    Store {
+        #[command(subcommand)]
+        command: StoreCommands,
+    },
+
     #[command(alias = "ua", name = "unalias", about = "Remove an alias from a tool.")]
     Unalias(UnaliasArgs),
 
@@ -432,6 +447,13 @@ pub enum Commands {
     )]
     Upgrade(UpgradeArgs),
 
+    #[command(
+        name = "verify",
+        about = "Verify the integrity of installed tools, shims, and bins.",
+        long_about = "Verify the integrity of installed tools. Checks that each installed version has a manifest entry, an install directory, and executables, that its files have not changed since it was installed, and that shims and bins point to valid targets."
+    )]
+    Verify(VerifyArgs),
+
     #[command(
         alias = "vs",
         name = "versions",
@@ -487,3 +509,13 @@ pub enum PluginCommands {
     )]
     Search(PluginSearchArgs),
 }
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum StoreCommands {
+    #[command(
+        name = "move",
+        about = "Move the store to a new location.",
+        long_about = "Move the store (PROTO_HOME) to a new location, and rewrite the absolute paths in the shims registry, bins, and shell profile."
+    )]
+    Move(StoreMoveArgs),
+}
@@ -2,7 +2,7 @@ use crate::error::ProtoCliError;
 use crate::session::{ProtoSession, SessionResult};
 use clap::Args;
 use proto_core::{
-    PinLocation, ProtoConfig, ToolContext, ToolSpec, UnresolvedVersionSpec, cfg,
+    AliasExpression, PinLocation, ProtoConfig, ToolContext, ToolSpec, UnresolvedVersionSpec, cfg,
     version_spec::parse_alias,
 };
 use starbase_console::ui::*;
@@ -24,6 +24,49 @@ pub struct AliasArgs {
     to: PinLocation,
 }
 
+fn validate_pin_expression(
+    session: &ProtoSession,
+    context: &ToolContext,
+    expression: &AliasExpression,
+) -> miette::Result<()> {
+    let AliasExpression::Pin { tool, part } = expression else {
+        return Ok(());
+    };
+
+    if tool == &context.id {
+        return Err(ProtoCliError::AliasPinSelf {
+            expression: expression.to_string(),
+        }
+        .into());
+    }
+
+    let config = session.load_config()?;
+
+    let Some(pinned) = config
+        .versions
+        .iter()
+        .find(|(pinned_context, _)| &pinned_context.id == tool)
+        .map(|(_, spec)| &spec.req)
+    else {
+        return Err(ProtoCliError::AliasPinMissing {
+            expression: expression.to_string(),
+            tool: tool.to_string(),
+        }
+        .into());
+    };
+
+    if AliasExpression::apply_pin(*part, pinned).is_none() {
+        return Err(ProtoCliError::AliasPinUnsupported {
+            expression: expression.to_string(),
+            tool: tool.to_string(),
+            version: pinned.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
 #[instrument(skip(session))]
 pub async fn alias(session: ProtoSession, args: AliasArgs) -> SessionResult {
     if let UnresolvedVersionSpec::Alias(inner_alias) = &args.spec.req
@@ -39,6 +82,16 @@ pub async fn alias(session: ProtoSession, args: AliasArgs) -> SessionResult {
         .into());
     }
 
+    match AliasExpression::from_spec(&args.spec.req) {
+        Some(AliasExpression::Previous { alias, .. }) if alias == args.alias => {
+            return Err(ProtoCliError::AliasNoMatchingToVersion.into());
+        }
+        Some(expression @ AliasExpression::Pin { .. }) => {
+            validate_pin_expression(&session, &args.context, &expression)?;
+        }
+        _ => {}
+    }
+
     let tool = session.load_tool(&args.context).await?;
 
     let config_path = ProtoConfig::update_document(tool.proto.get_config_dir(args.to), |doc| {
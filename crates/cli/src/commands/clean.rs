@@ -1,22 +1,29 @@
 use crate::helpers::join_list;
 use crate::session::{ProtoSession, SessionResult};
+use crate::utils::tool_record::ToolRecord;
 use clap::{Args, ValueEnum};
 use iocraft::prelude::element;
 use proto_core::ToolSpec;
 use proto_core::flow::manage::Manager;
+use proto_core::layout::{ContentIndex, DedupeStats};
 use proto_core::reporter::NoticeOutput;
-use proto_core::{PROTO_PLUGIN_KEY, Tool, VersionSpec, flow::resolve::ProtoResolveError};
+use proto_core::{
+    DedupeMode, PROTO_CONFIG_NAME, PROTO_LOCK_NAME, PROTO_PLUGIN_KEY, PinLocation, ProtoConfig,
+    ProtoLock, Tool, ToolContext, ToolManifest, UnresolvedVersionSpec, VersionSpec,
+    flow::resolve::ProtoResolveError, resolve_version, set_read_only_recursive,
+};
 use proto_shim::get_exe_file_name;
-use rustc_hash::FxHashSet;
+use rustc_hash::FxHashMap;
 use serde::Serialize;
 use starbase_console::ui::*;
 use starbase_console::utils::formats::format_bytes_binary;
 use starbase_styles::color;
-use starbase_utils::fs;
+use starbase_utils::{fs, path};
+use std::collections::BTreeMap;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 #[derive(Clone, Debug, Default, ValueEnum)]
 pub enum CleanTarget {
@@ -39,11 +46,22 @@ pub struct CleanArgs {
         help = "Clean tools and plugins older than the specified number of days"
     )]
     pub days: u8,
+
+    #[arg(
+        long,
+        help = "Link identical files across installed tool versions to save space"
+    )]
+    pub dedupe: bool,
+
+    #[arg(long, help = "Explain why each tool version was kept or removed")]
+    pub explain: bool,
 }
 
 #[derive(Default, Serialize)]
 pub struct CleanResult {
     cache: Vec<StaleFile>,
+    deduped: Option<DedupeStats>,
+    kept_tools: Vec<StaleTool>,
     plugins: Vec<StaleFile>,
     temp: Vec<StaleFile>,
     tools: Vec<StaleTool>,
@@ -53,9 +71,19 @@ pub struct CleanResult {
 pub struct StaleTool {
     dir: PathBuf,
     id: String,
+    reason: String,
     version: VersionSpec,
 }
 
+/// A version specification for a tool, that was pinned in a project's
+/// `.prototools`, or recorded in a project's `.protolock`.
+pub struct ToolReference {
+    file: PathBuf,
+    spec: UnresolvedVersionSpec,
+}
+
+pub type ToolReferences = FxHashMap<ToolContext, Vec<ToolReference>>;
+
 #[derive(Serialize)]
 pub struct StaleFile {
     file: PathBuf,
@@ -66,21 +94,175 @@ fn is_older_than_days(now: u128, other: u128, days: u64) -> bool {
     (now - other) > ((days as u128) * 24 * 60 * 60 * 1000)
 }
 
+/// Load the versions referenced by all registered projects, and the global
+/// and user configs. Projects that no longer exist are removed from the registry.
+#[instrument(skip(session))]
+pub fn load_tool_references(session: &ProtoSession) -> miette::Result<ToolReferences> {
+    let mut registry = session.env.store.load_project_registry()?;
+
+    registry.prune();
+    registry.save()?;
+
+    let global_dir = &session.env.store.dir;
+    let user_dir = session.env.get_config_dir(PinLocation::User);
+    let mut dirs = registry.projects.into_keys().collect::<Vec<_>>();
+    dirs.push(global_dir.to_owned());
+
+    if !dirs.iter().any(|dir| dir == user_dir) {
+        dirs.push(user_dir.to_owned());
+    }
+
+    let mut references = ToolReferences::default();
+
+    for dir in dirs {
+        let config_path = dir.join(PROTO_CONFIG_NAME);
+
+        if config_path.exists() {
+            match ProtoConfig::load_from(&dir, &dir == global_dir) {
+                Ok(config) => {
+                    for (context, spec) in config.versions.into_iter().flatten() {
+                        references.entry(context).or_default().push(ToolReference {
+                            file: config_path.clone(),
+                            spec: spec.req,
+                        });
+                    }
+                }
+                Err(error) => {
+                    warn!(
+                        file = ?config_path,
+                        "Failed to load project config, its versions may be cleaned: {error}"
+                    );
+                }
+            };
+        }
+
+        let lock_path = dir.join(PROTO_LOCK_NAME);
+
+        if lock_path.exists() {
+            match ProtoLock::load(&lock_path) {
+                Ok(lock) => {
+                    for (id, records) in lock.tools {
+                        for record in records {
+                            let Some(version) = record.version else {
+                                continue;
+                            };
+
+                            let context = match record.backend {
+                                Some(backend) => ToolContext::with_backend(id.clone(), backend),
+                                None => ToolContext::new(id.clone()),
+                            };
+
+                            references.entry(context).or_default().push(ToolReference {
+                                file: lock_path.clone(),
+                                spec: version.to_unresolved_spec(),
+                            });
+                        }
+                    }
+                }
+                Err(error) => {
+                    warn!(
+                        file = ?lock_path,
+                        "Failed to load project lockfile, its versions may be cleaned: {error}"
+                    );
+                }
+            };
+        }
+    }
+
+    Ok(references)
+}
+
+/// Resolve the references against the installed versions, and return the
+/// versions that are still reachable, with the reason they're reachable.
+pub fn find_referenced_versions(
+    record: &ToolRecord,
+    references: &[ToolReference],
+) -> FxHashMap<VersionSpec, String> {
+    let installed = record
+        .tool
+        .inventory
+        .manifest
+        .versions
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    let local_aliases = record
+        .local_aliases
+        .iter()
+        .map(|(alias, spec)| (alias.to_owned(), spec.req.clone()))
+        .collect::<BTreeMap<_, _>>();
+    let mut referenced = FxHashMap::default();
+
+    for reference in references {
+        // Remote aliases (lts, stable, etc) are only known by the plugin,
+        // so resolve them through the last cached versions response
+        let mut aliases = match record
+            .tool
+            .inventory
+            .load_cached_remote_versions(reference.spec.get_scope())
+        {
+            Ok(Some(remote)) => remote.aliases.into_iter().collect::<BTreeMap<_, _>>(),
+            Ok(None) => BTreeMap::default(),
+            Err(error) => {
+                warn!("Failed to load cached remote versions, unable to resolve aliases: {error}");
+
+                BTreeMap::default()
+            }
+        };
+
+        aliases.extend(local_aliases.clone());
+
+        let versions = match resolve_version(&reference.spec, &installed, &aliases, None, None) {
+            Ok(Some(version)) => vec![version],
+            // Aliases that can't be resolved offline may point to any installed
+            // stable version, so keep them all, except for `latest`, which can
+            // only be the highest
+            Ok(None) | Err(_) if matches!(reference.spec, UnresolvedVersionSpec::Alias(_)) => {
+                let stable = installed.iter().filter(|spec| {
+                    spec.as_version()
+                        .is_some_and(|version| version.prerelease.is_none())
+                });
+
+                if reference.spec.is_latest() {
+                    stable.max().cloned().into_iter().collect()
+                } else {
+                    stable.cloned().collect()
+                }
+            }
+            Ok(None) | Err(_) => vec![],
+        };
+
+        for version in versions {
+            referenced.entry(version).or_insert_with(|| {
+                format!(
+                    "referenced as {} in {}",
+                    reference.spec,
+                    reference.file.display()
+                )
+            });
+        }
+    }
+
+    referenced
+}
+
 #[instrument(skip(session))]
 pub async fn clean_tool(
     session: &ProtoSession,
     mut tool: Tool,
+    referenced: FxHashMap<VersionSpec, String>,
     now: SystemTime,
     days: u64,
-) -> miette::Result<Vec<StaleTool>> {
+) -> miette::Result<(Vec<StaleTool>, Vec<StaleTool>)> {
     let mut cleaned = vec![];
+    let mut kept = vec![];
 
     debug!("Checking {}", tool.get_name());
 
     if tool.metadata.inventory_options.override_dir.is_some() {
         debug!("Using an external inventory, skipping");
 
-        return Ok(cleaned);
+        return Ok((cleaned, kept));
     }
 
     let inventory_dir = tool.get_inventory_dir().to_path_buf();
@@ -88,10 +270,10 @@ pub async fn clean_tool(
     if !inventory_dir.exists() {
         debug!("Not being used, skipping");
 
-        return Ok(cleaned);
+        return Ok((cleaned, kept));
     }
 
-    let mut versions_to_clean = FxHashSet::<VersionSpec>::default();
+    let mut versions_to_clean = FxHashMap::<VersionSpec, String>::default();
     let now_millis = now
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
@@ -127,15 +309,25 @@ pub async fn clean_tool(
                     color::hash(version.to_string())
                 );
 
-                versions_to_clean.insert(version);
+                versions_to_clean.insert(version, "not tracked in the manifest".into());
             }
         }
     }
 
     debug!("Comparing last used timestamps from manifest");
 
+    let tool_id = tool.get_id().to_string();
+    let mut keep = |version: &VersionSpec, reason: String| {
+        kept.push(StaleTool {
+            dir: inventory_dir.join(version.to_string()),
+            id: tool_id.clone(),
+            reason,
+            version: version.to_owned(),
+        });
+    };
+
     for (version, metadata) in &tool.inventory.manifest.versions {
-        if versions_to_clean.contains(version) {
+        if versions_to_clean.contains_key(version) {
             continue;
         }
 
@@ -145,30 +337,53 @@ pub async fn clean_tool(
                 color::hash(version.to_string())
             );
 
+            keep(version, "marked as not to clean".into());
+
             continue;
         }
 
-        // None may mean a few things:
-        // - It was recently installed but not used yet
-        // - It was installed before we started tracking last used timestamps
-        // - The tools run via external commands (e.g. moon)
-        if let Ok(Some(last_used)) = tool.inventory.create_product(version).load_used_at()
-            && is_older_than_days(now_millis, last_used, days)
-        {
+        if let Some(reason) = referenced.get(version) {
             debug!(
-                "Version {} hasn't been used in over {} days, removing",
+                "Version {} is {}, skipping",
                 color::hash(version.to_string()),
-                days
+                reason
             );
 
-            versions_to_clean.insert(version.to_owned());
+            keep(version, reason.to_owned());
+
+            continue;
         }
+
+        // None may mean a few things:
+        // - It was recently installed but not used yet
+        // - It was installed before we started tracking last used timestamps
+        // - The tools run via external commands (e.g. moon)
+        match tool.inventory.create_product(version).load_used_at() {
+            Ok(Some(last_used)) if is_older_than_days(now_millis, last_used, days) => {
+                debug!(
+                    "Version {} hasn't been used in over {} days, removing",
+                    color::hash(version.to_string()),
+                    days
+                );
+
+                versions_to_clean.insert(
+                    version.to_owned(),
+                    format!("not used in over {days} days, and not referenced by any project"),
+                );
+            }
+            Ok(Some(_)) => {
+                keep(version, format!("used within the last {days} days"));
+            }
+            _ => {
+                keep(version, "not used yet".into());
+            }
+        };
     }
 
     if versions_to_clean.is_empty() {
         debug!("No versions to remove, continuing to next tool");
 
-        return Ok(cleaned);
+        return Ok((cleaned, kept));
     }
 
     let skip_prompts = session.should_skip_prompts();
@@ -185,7 +400,7 @@ pub async fn clean_tool(
                         tool.get_name(),
                         join_list(
                             versions_to_clean
-                                .iter()
+                                .keys()
                                 .map(|v| format!("<version>{v}</version>"))
                                 .collect::<Vec<_>>()
                         )
@@ -197,13 +412,13 @@ pub async fn clean_tool(
     }
 
     if skip_prompts || confirmed {
-        let tool_id = tool.get_id().to_string();
         let mut manager = Manager::new(&mut tool);
 
-        for version in versions_to_clean {
+        for (version, reason) in versions_to_clean {
             cleaned.push(StaleTool {
                 dir: inventory_dir.join(version.to_string()),
                 id: tool_id.clone(),
+                reason,
                 version: version.clone(),
             });
 
@@ -219,9 +434,13 @@ pub async fn clean_tool(
         manager.sync_manifest().await?;
     } else {
         debug!("Skipping remove, continuing to next tool");
+
+        for version in versions_to_clean.into_keys() {
+            keep(&version, "removal was declined".into());
+        }
     }
 
-    Ok(cleaned)
+    Ok((cleaned, kept))
 }
 
 #[instrument(skip(session))]
@@ -265,6 +484,7 @@ pub async fn clean_proto_tool(session: &ProtoSession, days: u64) -> miette::Resu
             cleaned.push(StaleTool {
                 dir: tool_dir,
                 id: PROTO_PLUGIN_KEY.to_owned(),
+                reason: format!("not used in over {days} days"),
                 version,
             });
         }
@@ -335,6 +555,62 @@ pub fn clean_dir(dir: &Path, days: u64, recurse: bool) -> miette::Result<Vec<Sta
     Ok(cleaned)
 }
 
+/// Link identical files across all installed tool versions through the
+/// content index. Uses reflinks unless hard links are configured, and files
+/// are left as-is on file systems that don't support reflinks.
+#[instrument(skip(session))]
+pub fn dedupe_tools(session: &ProtoSession) -> miette::Result<DedupeStats> {
+    let store = &session.env.store;
+    let settings = &session.env.load_config()?.settings;
+    let mode = match settings.dedupe {
+        DedupeMode::Off => DedupeMode::Reflink,
+        mode => mode,
+    };
+    let index = ContentIndex::new(&store.dedupe_dir);
+    let mut stats = DedupeStats::default();
+
+    if !store.inventory_dir.exists() {
+        return Ok(stats);
+    }
+
+    for entry in fs::read_dir(&store.inventory_dir)? {
+        let tool_dir = entry.path();
+
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+
+        let manifest = ToolManifest::load_from(&tool_dir)?;
+
+        // Only dedupe versions that have finished installing
+        for (version, meta) in &manifest.versions {
+            let dir = tool_dir.join(path::encode_component(format!(
+                "{version}{}",
+                meta.suffix.as_deref().unwrap_or_default()
+            )));
+
+            if !dir.exists() {
+                continue;
+            }
+
+            // Files can't be replaced within a read-only directory
+            if settings.read_only_tools {
+                set_read_only_recursive(&dir, false)?;
+            }
+
+            let result = index.dedupe_dir(&dir, mode);
+
+            if settings.read_only_tools {
+                set_read_only_recursive(&dir, true)?;
+            }
+
+            stats.merge(result?);
+        }
+    }
+
+    Ok(stats)
+}
+
 #[instrument(skip(session))]
 pub async fn internal_clean(
     session: &ProtoSession,
@@ -347,18 +623,28 @@ pub async fn internal_clean(
     if matches!(args.target, CleanTarget::All | CleanTarget::Tools) {
         debug!("Cleaning installed tools...");
 
+        let references = load_tool_references(session)?;
+
         for tool in session.load_all_tools().await? {
             if tool.get_id() == PROTO_PLUGIN_KEY {
                 continue;
             }
 
-            result
-                .tools
-                .extend(clean_tool(session, tool.tool, now, days).await?);
+            let referenced = references
+                .get(&tool.context)
+                .map(|list| find_referenced_versions(&tool, list))
+                .unwrap_or_default();
+            let (cleaned, kept) = clean_tool(session, tool.tool, referenced, now, days).await?;
+
+            result.tools.extend(cleaned);
+            result.kept_tools.extend(kept);
         }
 
         // proto has special handling
         result.tools.extend(clean_proto_tool(session, days).await?);
+
+        // Entries are no longer needed once all of their linked files have been removed
+        ContentIndex::new(&session.env.store.dedupe_dir).prune()?;
     }
 
     if matches!(args.target, CleanTarget::All | CleanTarget::Plugins) {
@@ -384,6 +670,12 @@ pub async fn internal_clean(
         result.cache = clean_dir(&session.env.store.cache_dir, days, true)?;
     }
 
+    if args.dedupe {
+        debug!("Deduplicating installed tools...");
+
+        result.deduped = Some(dedupe_tools(session)?);
+    }
+
     Ok(result)
 }
 
@@ -397,6 +689,43 @@ pub async fn clean(session: ProtoSession, args: CleanArgs) -> SessionResult {
         return Ok(None);
     }
 
+    if args.explain && (!result.tools.is_empty() || !result.kept_tools.is_empty()) {
+        let mut decisions = result
+            .tools
+            .iter()
+            .map(|item| (item, "removed"))
+            .chain(result.kept_tools.iter().map(|item| (item, "kept")))
+            .collect::<Vec<_>>();
+
+        decisions.sort_by(|(a, _), (b, _)| a.id.cmp(&b.id).then(a.version.cmp(&b.version)));
+
+        session.console.notice_with(NoticeOutput {
+            variant: Variant::Info,
+            title: Some("Tool versions".into()),
+            items: decisions
+                .into_iter()
+                .map(|(item, action)| {
+                    format!(
+                        "<id>{}</id> <version>{}</version> {action}, {}",
+                        item.id, item.version, item.reason
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        })?;
+    }
+
+    if let Some(deduped) = &result.deduped {
+        session.console.notice(
+            Variant::Success,
+            format!(
+                "Deduplicated {} files across installed tool versions ({})",
+                deduped.files,
+                format_bytes_binary(deduped.size)
+            ),
+        )?;
+    }
+
     let remove_count =
         result.cache.len() + result.plugins.len() + result.temp.len() + result.tools.len();
 
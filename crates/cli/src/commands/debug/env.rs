@@ -83,7 +83,7 @@ pub async fn env(session: ProtoSession, args: DebugEnvArgs) -> SessionResult {
         return Ok(None);
     }
 
-    let store_paths = vec![
+    let mut store_paths = vec![
         ("Root", &env.store.dir),
         ("Bins", &env.store.bin_dir),
         ("Shims", &env.store.shims_dir),
@@ -92,6 +92,10 @@ pub async fn env(session: ProtoSession, args: DebugEnvArgs) -> SessionResult {
         ("Temp", &env.store.temp_dir),
     ];
 
+    if let Some(system_dir) = &env.store.system_dir {
+        store_paths.push(("System", system_dir));
+    }
+
     session.console.render(element! {
         Container {
             Section(title: "Store") {
@@ -4,12 +4,13 @@ use crate::helpers::fetch_latest_version;
 use crate::session::{ProtoSession, SessionResult};
 use clap::Args;
 use iocraft::prelude::{FlexDirection, View, element};
-use proto_core::{Id, ToolContext, UnresolvedVersionSpec};
+use proto_core::checksum::stamp_dir;
+use proto_core::{Id, ToolContext, ToolManifest, UnresolvedVersionSpec};
 use rustc_hash::FxHashMap;
 use serde::Serialize;
 use starbase_console::ui::*;
 use starbase_shell::ShellType;
-use starbase_utils::envx;
+use starbase_utils::{envx, fs, path};
 use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::path::PathBuf;
@@ -285,7 +286,9 @@ async fn gather_warnings(
         });
     }
 
+    warnings.extend(gather_version_conflict_warnings(session)?);
     warnings.extend(gather_lockfile_warnings(session)?);
+    warnings.extend(gather_fingerprint_warnings(session)?);
 
     if !warnings.is_empty() {
         tips.push("Run <shell>proto setup</shell> to resolve some of these issues!".into());
@@ -294,6 +297,34 @@ async fn gather_warnings(
     Ok(warnings)
 }
 
+fn gather_version_conflict_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoCliError> {
+    let manager = session.env.load_file_manager()?;
+
+    Ok(manager
+        .get_version_conflicts()
+        .into_iter()
+        .map(|conflict| Issue {
+            issue: format!(
+                "Configured versions for <id>{}</id> cannot be satisfied together: {}",
+                conflict.context,
+                conflict
+                    .specs
+                    .iter()
+                    .map(|(path, spec)| format!(
+                        "<version>{spec}</version> in <path>{}</path>",
+                        path.display()
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            resolution: Some(
+                "Align the versions across these configs, or with the allowed range".into(),
+            ),
+            comment: None,
+        })
+        .collect())
+}
+
 fn gather_lockfile_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoCliError> {
     let mut warnings = vec![];
     let manager = session.env.load_file_manager()?;
@@ -312,12 +343,24 @@ fn gather_lockfile_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoC
         let mut config_specs: BTreeMap<&ToolContext, BTreeSet<&UnresolvedVersionSpec>> =
             BTreeMap::default();
 
+        // Tools with platform specific versions resolve differently per
+        // machine, so records for other platforms can't be verified
+        let mut platform_specific: BTreeSet<&ToolContext> = BTreeSet::default();
+
         for file in &entry.configs {
             if let Some(versions) = &file.config.versions {
                 for (context, spec) in versions {
                     config_specs.entry(context).or_default().insert(&spec.req);
                 }
             }
+
+            if let Some(tools) = &file.config.tools {
+                for (context, tool) in tools {
+                    if tool.platform.as_ref().is_some_and(|map| !map.is_empty()) {
+                        platform_specific.insert(context);
+                    }
+                }
+            }
         }
 
         for (id, records) in &lock.tools {
@@ -365,6 +408,9 @@ fn gather_lockfile_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoC
                 if let Some((context, specs)) = config_context
                     && let Some(spec) = &record.spec
                     && !specs.contains(spec)
+                    && !(platform_specific.contains(context)
+                        && (record.os.is_some_and(|os| os != session.env.os)
+                            || record.arch.is_some_and(|arch| arch != session.env.arch)))
                 {
                     warnings.push(Issue {
                         issue: format!(
@@ -399,3 +445,57 @@ fn gather_lockfile_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoC
 
     Ok(warnings)
 }
+
+fn gather_fingerprint_warnings(session: &ProtoSession) -> Result<Vec<Issue>, ProtoCliError> {
+    let mut warnings = vec![];
+    let inventory_dir = &session.env.store.inventory_dir;
+
+    if !inventory_dir.exists() {
+        return Ok(warnings);
+    }
+
+    for entry in fs::read_dir(inventory_dir)? {
+        let tool_dir = entry.path();
+
+        if !tool_dir.is_dir() {
+            continue;
+        }
+
+        let Ok(manifest) = ToolManifest::load_from(&tool_dir) else {
+            continue;
+        };
+
+        for (version, meta) in &manifest.versions {
+            // Only compare metadata, as hashing every file is too slow for
+            // a health check, and `proto verify` compares the fingerprint
+            let Some(stamp) = &meta.stamp else {
+                continue;
+            };
+
+            let product_dir = tool_dir.join(path::encode_component(format!(
+                "{version}{}",
+                meta.suffix.as_deref().unwrap_or_default()
+            )));
+
+            if product_dir.exists() && stamp_dir(&product_dir).is_ok_and(|actual| actual != *stamp)
+            {
+                warnings.push(Issue {
+                    issue: format!(
+                        "Files in install directory <path>{}</path> have changed since it was installed",
+                        product_dir.display()
+                    ),
+                    resolution: Some(format!(
+                        "Run <shell>proto verify {}</shell> for more information",
+                        fs::file_name(&tool_dir)
+                    )),
+                    comment: Some(
+                        "This is expected if packages were installed into the tool's directory"
+                            .into(),
+                    ),
+                });
+            }
+        }
+    }
+
+    Ok(warnings)
+}
@@ -0,0 +1,342 @@
+use crate::session::{ProtoSession, SessionResult};
+use clap::Args;
+use iocraft::prelude::Size;
+use proto_core::{PROTO_PLUGIN_KEY, ToolManifest, VersionSpec, set_read_only_recursive};
+use serde::Serialize;
+use starbase_console::ui::*;
+use starbase_console::utils::formats::format_bytes_binary;
+use starbase_styles::color;
+use starbase_utils::{fs, hash, path};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct DuArgs {
+    #[arg(
+        long,
+        help = "Remove tool directories that are not tracked in the tool's manifest"
+    )]
+    pub prune_orphans: bool,
+}
+
+#[derive(Default, Serialize)]
+pub struct DiskUsageResult {
+    backends: u64,
+    cache: u64,
+    dedupe: u64,
+    plugins: u64,
+    temp: u64,
+    tools: Vec<ToolUsage>,
+    total: u64,
+}
+
+#[derive(Serialize)]
+pub struct ToolUsage {
+    dir: PathBuf,
+    id: String,
+    products: Vec<ProductUsage>,
+    size: u64,
+}
+
+#[derive(Serialize)]
+pub struct ProductUsage {
+    dir: PathBuf,
+    name: String,
+    orphan: bool,
+    pruned: bool,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<VersionSpec>,
+}
+
+/// Measures the size of files and directories, while counting files that
+/// are hard linked to each other only once.
+#[derive(Default)]
+struct DiskUsage {
+    #[cfg(unix)]
+    inodes: rustc_hash::FxHashSet<(u64, u64)>,
+}
+
+impl DiskUsage {
+    /// Return the size of a file or directory. Symlinks are not followed,
+    /// and entries that can't be read (removed during the walk) are ignored.
+    fn get_size(&mut self, path: &Path) -> u64 {
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            return 0;
+        };
+
+        if !metadata.is_dir() {
+            return if self.is_counted(&metadata) {
+                0
+            } else {
+                metadata.len()
+            };
+        }
+
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return 0;
+        };
+
+        let mut size = 0;
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            size += self.get_size(&entry.path());
+        }
+
+        size
+    }
+
+    /// Return true if the file is a hard link to a file that was already counted.
+    #[cfg(unix)]
+    fn is_counted(&mut self, metadata: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        metadata.nlink() > 1 && !self.inodes.insert((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    fn is_counted(&mut self, _metadata: &std::fs::Metadata) -> bool {
+        false
+    }
+}
+
+#[instrument(skip(disk))]
+fn load_tool_usage(
+    disk: &mut DiskUsage,
+    tool_dir: &Path,
+    temp_dir: &Path,
+) -> miette::Result<ToolUsage> {
+    let id = fs::file_name(tool_dir);
+    let is_proto = id == PROTO_PLUGIN_KEY;
+    let manifest = ToolManifest::load_from(tool_dir)?;
+
+    // Product directories are named after the version, with an optional suffix
+    let tracked = manifest
+        .versions
+        .iter()
+        .map(|(version, meta)| {
+            (
+                path::encode_component(format!(
+                    "{version}{}",
+                    meta.suffix.as_deref().unwrap_or_default()
+                )),
+                version,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut usage = ToolUsage {
+        dir: tool_dir.to_path_buf(),
+        id,
+        products: vec![],
+        size: 0,
+    };
+
+    for entry in fs::read_dir(tool_dir)? {
+        let entry_path = entry.path();
+        let size = disk.get_size(&entry_path);
+
+        usage.size += size;
+
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+
+        let name = fs::file_name(&entry_path);
+
+        // Node.js compat, and hidden directories
+        if name == "globals" || name.starts_with('.') {
+            continue;
+        }
+
+        let version = tracked
+            .iter()
+            .find(|(dir_name, _)| *dir_name == name)
+            .map(|(_, version)| (*version).to_owned());
+
+        // The proto inventory is not tracked in a manifest
+        let orphan = version.is_none() && !is_proto;
+
+        let version = version.or_else(|| VersionSpec::parse(&name).ok());
+
+        // The version-keyed temp directory is locked while the version is
+        // being installed, so it's not an orphan (yet). Failed installs
+        // leave the directory behind, so its existence is not enough
+        let installing = version.as_ref().is_some_and(|version| {
+            fs::is_dir_locked(temp_dir.join(hash::base64::from_bytes(version.to_string())))
+        });
+
+        usage.products.push(ProductUsage {
+            dir: entry_path,
+            orphan: orphan && !installing,
+            pruned: false,
+            size,
+            version,
+            name,
+        });
+    }
+
+    usage.products.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(usage)
+}
+
+#[instrument(skip(session))]
+pub async fn du(session: ProtoSession, args: DuArgs) -> SessionResult {
+    let store = &session.env.store;
+    let mut disk = DiskUsage::default();
+    let mut result = DiskUsageResult::default();
+
+    // Tools are measured first, so that files linked into the
+    // dedupe store are attributed to the versions using them
+    if store.inventory_dir.exists() {
+        for entry in fs::read_dir(&store.inventory_dir)? {
+            let tool_dir = entry.path();
+
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
+            }
+
+            let temp_dir = store.temp_dir.join(entry.file_name());
+
+            result
+                .tools
+                .push(load_tool_usage(&mut disk, &tool_dir, &temp_dir)?);
+        }
+    }
+
+    result.backends = disk.get_size(&store.backends_dir);
+    result.cache = disk.get_size(&store.cache_dir);
+    result.dedupe = disk.get_size(&store.dedupe_dir);
+    result.plugins = disk.get_size(&store.plugins_dir);
+    result.temp = disk.get_size(&store.temp_dir);
+
+    result.tools.sort_by(|a, b| a.id.cmp(&b.id));
+
+    if args.prune_orphans {
+        for tool in &mut result.tools {
+            for product in &mut tool.products {
+                if !product.orphan {
+                    continue;
+                }
+
+                debug!(
+                    "Directory {} is not tracked in the manifest, removing",
+                    color::path(&product.dir)
+                );
+
+                // Orphans may have been made read-only before being untracked
+                let result = set_read_only_recursive(&product.dir, false)
+                    .and_then(|_| fs::remove_dir_all(&product.dir));
+
+                match result {
+                    Ok(_) => {
+                        product.pruned = true;
+                        tool.size -= product.size;
+                    }
+                    Err(error) => {
+                        warn!(dir = ?product.dir, "Failed to remove orphaned directory: {error}");
+                    }
+                };
+            }
+        }
+    }
+
+    let tools_size = result.tools.iter().map(|tool| tool.size).sum::<u64>();
+
+    result.total =
+        result.backends + result.cache + result.dedupe + result.plugins + result.temp + tools_size;
+
+    if session.is_json_format() {
+        session.console.write_json_for_format(result)?;
+
+        return Ok(None);
+    }
+
+    let mut rows = vec![];
+
+    for tool in &result.tools {
+        rows.push(vec![
+            format!("<id>{}</id>", tool.id),
+            String::new(),
+            format_bytes_binary(tool.size).to_string(),
+            String::new(),
+        ]);
+
+        for product in &tool.products {
+            rows.push(vec![
+                String::new(),
+                format!("<hash>{}</hash>", product.name),
+                format_bytes_binary(product.size).to_string(),
+                if product.pruned {
+                    "<success>orphan, pruned</success>".into()
+                } else if product.orphan {
+                    "<caution>orphan</caution>".into()
+                } else {
+                    String::new()
+                },
+            ]);
+        }
+    }
+
+    if !rows.is_empty() {
+        let id_width = result
+            .tools
+            .iter()
+            .fold(0, |acc, tool| acc.max(tool.id.len()));
+        let name_width = result
+            .tools
+            .iter()
+            .flat_map(|tool| &tool.products)
+            .fold(0, |acc, product| acc.max(product.name.len()));
+
+        session.console.table(
+            vec![
+                TableHeader::new("Tool", Size::Length((id_width + 3).max(8) as u32)),
+                TableHeader::new("Version", Size::Length((name_width + 3).max(10) as u32)),
+                TableHeader::new("Size", Size::Length(12)),
+                TableHeader::new("Status", Size::Auto),
+            ],
+            rows,
+        )?;
+    }
+
+    session.console.table(
+        vec![
+            TableHeader::new("Directory", Size::Length(12)),
+            TableHeader::new("Size", Size::Auto),
+        ],
+        [
+            ("Tools", tools_size),
+            ("Backends", result.backends),
+            ("Plugins", result.plugins),
+            ("Cache", result.cache),
+            ("Dedupe", result.dedupe),
+            ("Temp", result.temp),
+            ("Total", result.total),
+        ]
+        .into_iter()
+        .map(|(label, size)| vec![label.to_owned(), format_bytes_binary(size).to_string()])
+        .collect(),
+    )?;
+
+    let orphans = result
+        .tools
+        .iter()
+        .flat_map(|tool| &tool.products)
+        .filter(|product| product.orphan && !product.pruned)
+        .collect::<Vec<_>>();
+
+    if !orphans.is_empty() {
+        session.console.notice(
+            Variant::Caution,
+            format!(
+                "Found {} directories that are not tracked in a tool manifest ({}), likely from interrupted installs. Run with <shell>--prune-orphans</shell> to remove them.",
+                orphans.len(),
+                format_bytes_binary(orphans.iter().map(|product| product.size).sum::<u64>()),
+            ),
+        )?;
+    }
+
+    Ok(None)
+}
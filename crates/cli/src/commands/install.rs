@@ -4,8 +4,10 @@ use crate::session::{LoadToolOptions, ProtoSession, SessionResult};
 use crate::utils::install_graph::*;
 use crate::utils::tool_record::ToolRecord;
 use crate::workflows::{InstallOutcome, InstallWorkflowManager, InstallWorkflowParams};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use proto_core::flow::detect::Detector;
+use proto_core::flow::install::Installer;
+use proto_core::flow::resolve::Resolver;
 use proto_core::{
     ConfigMode, Id, PinLocation, Tool, ToolContext, ToolSpec, reporter::NoticeOutput,
 };
@@ -15,10 +17,23 @@ use starbase_console::utils::formats::format_duration;
 use starbase_styles::color;
 use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
+use system_env::SystemPackageManager;
 use tokio::task::JoinSet;
 use tokio::time::sleep;
 use tracing::{debug, info, instrument, trace};
 
+#[derive(Clone, Debug, Default, ValueEnum)]
+pub enum PrintDepsFormat {
+    #[default]
+    Shell,
+    Dockerfile,
+}
+
+fn parse_package_manager(value: &str) -> Result<SystemPackageManager, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_owned()))
+        .map_err(|_| format!("unknown package manager {value}"))
+}
+
 #[derive(Args, Clone, Debug, Default)]
 pub struct InstallArgs {
     #[arg(help = "Single tool to install")]
@@ -50,6 +65,30 @@ pub struct InstallArgs {
     #[arg(long, help = "Pin the resolved version to .prototools")]
     pub pin: Option<Option<PinLocation>>,
 
+    #[arg(
+        long,
+        requires_all = ["context", "build"],
+        help = "Print the commands to install system dependencies, without installing"
+    )]
+    pub print_deps: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        requires = "print_deps",
+        help = "Format to print system dependency commands in"
+    )]
+    pub print_deps_format: PrintDepsFormat,
+
+    #[arg(
+        long,
+        requires = "print_deps",
+        value_parser = parse_package_manager,
+        help = "Package manager to print system dependency commands for"
+    )]
+    pub package_manager: Option<SystemPackageManager>,
+
     // Passthrough args (after --)
     #[arg(
         last = true,
@@ -69,6 +108,10 @@ pub struct InstallArgs {
     // Used internally by other commands to trigger conditional logic
     #[arg(hide = true, long)]
     pub internal: bool,
+
+    // Used internally by `proto upgrade` to require a verified archive
+    #[arg(skip)]
+    pub require_verified: bool,
 }
 
 impl InstallArgs {
@@ -203,6 +246,7 @@ pub async fn install_one(
                 multiple: false,
                 passthrough_args: args.passthrough,
                 quiet: args.quiet,
+                require_verified: args.require_verified,
                 skip_prompts: session.should_skip_prompts(),
             },
         )
@@ -239,7 +283,7 @@ pub async fn install_one(
                     "{} <version>{}</version> has already been installed at <path>{}</path>!",
                     tool.get_name(),
                     spec.get_resolved_version(),
-                    tool.get_product_dir(&spec).display(),
+                    tool.locate_product_dir(&spec).display(),
                 ),
             )?;
         }
@@ -365,6 +409,7 @@ async fn install_all(session: ProtoSession, args: InstallArgs) -> SessionResult
                         multiple: true,
                         passthrough_args: vec![],
                         pin_to,
+                        require_verified: false,
                         skip_prompts,
                         strategy,
                         quiet: args.quiet,
@@ -466,9 +511,47 @@ async fn install_all(session: ProtoSession, args: InstallArgs) -> SessionResult
     Ok(Some(failed_count as u8))
 }
 
+#[instrument(skip(session))]
+async fn print_deps(
+    session: ProtoSession,
+    args: InstallArgs,
+    context: ToolContext,
+) -> SessionResult {
+    let tool = session.load_tool(&context).await?;
+    let mut spec = args.spec.clone().unwrap_or_default();
+
+    Resolver::resolve(&tool, &mut spec, false).await?;
+
+    let commands = Installer::new(&tool, &spec)
+        .get_system_dependency_commands(args.package_manager)
+        .await?;
+
+    if commands.is_empty() {
+        session.console.err.write_line(format!(
+            "No system dependencies are required to build {}",
+            tool.get_name()
+        ))?;
+
+        return Ok(None);
+    }
+
+    let commands = commands.iter().map(shell_words::join).collect::<Vec<_>>();
+
+    session
+        .console
+        .out
+        .write_line(match args.print_deps_format {
+            PrintDepsFormat::Shell => commands.join("\n"),
+            PrintDepsFormat::Dockerfile => format!("RUN {}", commands.join(" \\\n    && ")),
+        })?;
+
+    Ok(None)
+}
+
 #[instrument(skip(session))]
 pub async fn install(session: ProtoSession, args: InstallArgs) -> SessionResult {
     match args.context.clone() {
+        Some(context) if args.print_deps => print_deps(session, args, context).await,
         Some(context) => install_one(session, args, context).await,
         None => install_all(session, args).await,
     }
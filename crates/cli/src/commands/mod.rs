@@ -5,6 +5,7 @@ pub(crate) mod clean;
 mod completions;
 pub(crate) mod debug;
 mod diagnose;
+mod du;
 mod exec;
 mod install;
 mod mcp;
@@ -17,10 +18,12 @@ mod run;
 mod setup;
 mod shell;
 mod status;
+pub(crate) mod store;
 mod unalias;
 mod uninstall;
 mod unpin;
 mod upgrade;
+mod verify;
 mod versions;
 
 pub use activate::*;
@@ -29,6 +32,7 @@ pub use bin::*;
 pub use clean::*;
 pub use completions::*;
 pub use diagnose::*;
+pub use du::*;
 pub use exec::*;
 pub use install::*;
 pub use mcp::*;
@@ -44,4 +48,5 @@ pub use unalias::*;
 pub use uninstall::*;
 pub use unpin::*;
 pub use upgrade::*;
+pub use verify::*;
 pub use versions::*;
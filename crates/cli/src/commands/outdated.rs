@@ -1,4 +1,5 @@
 use crate::error::ProtoCliError;
+use crate::helpers::get_today_date;
 use crate::session::{LoadToolOptions, ProtoSession, SessionResult};
 use clap::Args;
 use iocraft::prelude::{Size, element};
@@ -7,13 +8,13 @@ use proto_core::flow::lock::Locker;
 use proto_core::flow::resolve::{ProtoResolveError, Resolver};
 use proto_core::{
     PROTO_CONFIG_NAME, ProtoConfig, Requirement, ToolContext, ToolSpec, UnresolvedVersionSpec,
-    VersionSpec, cfg,
+    VersionSpec, cfg, write_file_atomic,
 };
 use serde::Serialize;
 use starbase_console::ui::*;
 use starbase_styles::color;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::task::JoinSet;
 use tracing::{debug, instrument, warn};
 
@@ -30,18 +31,72 @@ pub struct OutdatedArgs {
         help = "Update and write the versions to their respective configuration"
     )]
     update: bool,
+
+    #[arg(
+        long,
+        requires = "update",
+        help = "When updating versions, write a Markdown summary of the changes to this file"
+    )]
+    summary: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct OutdatedItem {
+    is_eol: bool,
+    is_excluded: bool,
     is_latest: bool,
     is_outdated: bool,
     config_source: Option<PathBuf>,
     config_version: ToolSpec,
     current_version: VersionSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eol_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    held_back_version: Option<VersionSpec>,
     locked_version: Option<VersionSpec>,
     newest_version: VersionSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    newest_release_notes_url: Option<String>,
     latest_version: VersionSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_release_notes_url: Option<String>,
+}
+
+fn render_summary(
+    updates: &[(&ToolContext, &OutdatedItem, &VersionSpec, &Path)],
+    working_dir: &Path,
+) -> String {
+    let mut lines = vec!["## Tool updates".to_owned(), String::new()];
+
+    if updates.is_empty() {
+        lines.push("No tools were updated.".into());
+    } else {
+        lines.push("| Tool | From | To | Config | Release notes |".into());
+        lines.push("| :--- | :--- | :--- | :--- | :--- |".into());
+
+        for (context, item, new_version, config_path) in updates {
+            let release_notes_url = if *new_version == &item.latest_version {
+                item.latest_release_notes_url.as_ref()
+            } else {
+                item.newest_release_notes_url.as_ref()
+            };
+
+            lines.push(format!(
+                "| `{context}` | `{}` | `{new_version}` | `{}` | {} |",
+                item.current_version,
+                config_path
+                    .strip_prefix(working_dir)
+                    .unwrap_or(config_path)
+                    .display(),
+                release_notes_url
+                    .map(|url| format!("[{new_version}]({url})"))
+                    .unwrap_or_else(|| "N/A".into()),
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
 }
 
 fn get_in_major_range(spec: &UnresolvedVersionSpec) -> UnresolvedVersionSpec {
@@ -95,16 +150,41 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
                 "Resolving newest version"
             );
 
-            let newest_version = Resolver::new(&tool)
+            let mut newest_resolver = Resolver::new(&tool);
+            let newest_version = newest_resolver
                 .resolve_version_candidate(&newest_range, false, true)
                 .await?;
 
+            // Runtimes that have reached end-of-life should be flagged,
+            // regardless of whether a newer version is available
+            let current_metadata = newest_resolver.data.get_metadata(&current_version);
+            let is_eol = current_metadata.is_some_and(|meta| meta.is_eol_at(&get_today_date()));
+            let eol_date = current_metadata.and_then(|meta| meta.eol_date.clone());
+            let newest_release_notes_url = newest_resolver
+                .data
+                .get_metadata(&newest_version)
+                .and_then(|meta| meta.release_notes_url.clone());
+
+            // The current version may be excluded by the tool's allow/exclude policy
+            let is_excluded = newest_resolver
+                .validate_version_policy(&current_version)
+                .is_err();
+
             debug!(tool = tool.context.as_str(), "Resolving latest version");
 
-            let latest_version = Resolver::new(&tool)
+            let mut latest_resolver = Resolver::new(&tool);
+            let latest_version = latest_resolver
                 .resolve_version_candidate(&UnresolvedVersionSpec::default(), true, true)
                 .await?;
 
+            let latest_release_notes_url = latest_resolver
+                .data
+                .get_metadata(&latest_version)
+                .and_then(|meta| meta.release_notes_url.clone());
+
+            // A newer version may have been held back by the minimum release age
+            let held_back_version = latest_resolver.held_back.take();
+
             // If a lockfile record exists for the configured spec, then the
             // version is locked, and installs will use it instead
             let locked_version = if let Some(record) = &tool.spec.version_locked {
@@ -118,14 +198,20 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
             };
 
             let item = OutdatedItem {
+                is_eol,
+                is_excluded,
                 is_latest: current_version == latest_version,
                 is_outdated: newest_version > current_version || latest_version > current_version,
                 config_source: tool.detected_source.clone(),
                 config_version: config_version.to_owned(),
                 current_version,
+                eol_date,
+                held_back_version,
                 locked_version,
                 newest_version,
+                newest_release_notes_url,
                 latest_version,
+                latest_release_notes_url,
             };
 
             Result::<_, ProtoResolveError>::Ok((tool, item))
@@ -174,15 +260,32 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
     headers.extend([
         TableHeader::new("Newest", Size::Length(10)),
         TableHeader::new("Latest", Size::Length(10)),
-        TableHeader::new("Config", Size::Auto),
     ]);
 
+    // Only show the held back column if the minimum release age applied
+    let show_held_back = items.values().any(|item| item.held_back_version.is_some());
+
+    if show_held_back {
+        headers.push(TableHeader::new("Held back", Size::Length(11)));
+    }
+
+    headers.push(TableHeader::new("Config", Size::Auto));
+
     session.console.table(
         headers,
         items
             .iter()
             .map(|(ctx, item)| {
-                let mut row = vec![format!("<id>{ctx}</id>"), item.current_version.to_string()];
+                let mut row = vec![
+                    format!("<id>{ctx}</id>"),
+                    if item.is_excluded {
+                        format!("<failure>{}</failure>", item.current_version)
+                    } else if item.is_eol {
+                        format!("<caution>{}</caution>", item.current_version)
+                    } else {
+                        item.current_version.to_string()
+                    },
+                ];
 
                 if show_locked {
                     row.push(if let Some(version) = &item.locked_version {
@@ -205,18 +308,53 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
                     } else {
                         format!("<failure>{}</failure>", item.latest_version)
                     },
-                    if let Some(src) = &item.config_source {
-                        format!("<path>{}</path>", src.to_string_lossy())
+                ]);
+
+                if show_held_back {
+                    row.push(if let Some(version) = &item.held_back_version {
+                        format!("<caution>{version}</caution>")
                     } else {
                         "<mutedlight>N/A</mutedlight>".into()
-                    },
-                ]);
+                    });
+                }
+
+                row.push(if let Some(src) = &item.config_source {
+                    format!("<path>{}</path>", src.to_string_lossy())
+                } else {
+                    "<mutedlight>N/A</mutedlight>".into()
+                });
 
                 row
             })
             .collect(),
     )?;
 
+    for (ctx, item) in &items {
+        if item.is_excluded {
+            session.console.notice(
+                Variant::Failure,
+                format!(
+                    "<id>{ctx}</id> <version>{}</version> is not allowed by the <property>allow</property> or <property>exclude</property> settings, consider upgrading to an allowed version.",
+                    item.current_version,
+                ),
+            )?;
+        }
+
+        if item.is_eol {
+            session.console.notice(
+                Variant::Caution,
+                format!(
+                    "<id>{ctx}</id> <version>{}</version> has reached end-of-life{}, consider upgrading to a supported version.",
+                    item.current_version,
+                    item.eol_date
+                        .as_ref()
+                        .map(|date| format!(" on {date}"))
+                        .unwrap_or_default(),
+                ),
+            )?;
+        }
+    }
+
     // If updating versions, batch the changes based on config paths
     if !args.update {
         return Ok(None);
@@ -279,6 +417,8 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
             );
         }
 
+        let config_files = session.env.load_config_files()?;
+
         for (config_path, updated_versions) in &updates {
             debug!(
                 config = ?config_path,
@@ -289,10 +429,26 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
                 "Updating config with versions",
             );
 
+            // Versions that were overridden for the current platform
+            // must be written back to their platform table
+            let platform_pins = config_files
+                .iter()
+                .find(|file| &file.path == config_path)
+                .map(|file| &file.platform_pins);
+
             ProtoConfig::update_document(config_path, |doc| {
                 for (context, updated_version) in updated_versions {
-                    doc[context.as_str()] =
-                        cfg::value(ToolSpec::new(updated_version.to_owned()).to_string());
+                    let value = cfg::value(ToolSpec::new(updated_version.to_owned()).to_string());
+
+                    if let Some(platform) = platform_pins.and_then(|pins| pins.get(context)) {
+                        let tools = doc["tools"].or_insert(cfg::implicit_table());
+                        let record = tools[context.as_str()].or_insert(cfg::implicit_table());
+                        let platforms = record["platform"].or_insert(cfg::implicit_table());
+
+                        platforms[platform]["version"] = value;
+                    } else {
+                        doc[context.as_str()] = value;
+                    }
                 }
             })?;
         }
@@ -324,6 +480,38 @@ pub async fn outdated(session: ProtoSession, args: OutdatedArgs) -> SessionResul
             )?;
         }
 
+        if let Some(summary_path) = &args.summary {
+            let mut summary = vec![];
+
+            for (config_path, updated_versions) in &updates {
+                for context in updated_versions.keys() {
+                    let item = &items[context];
+
+                    summary.push((
+                        context,
+                        item,
+                        if args.latest {
+                            &item.latest_version
+                        } else {
+                            &item.newest_version
+                        },
+                        config_path.as_path(),
+                    ));
+                }
+            }
+
+            summary.sort_by_key(|(context, ..)| *context);
+
+            let summary_path = session.env.working_dir.join(summary_path);
+
+            debug!(file = ?summary_path, "Writing update summary");
+
+            write_file_atomic(
+                &summary_path,
+                render_summary(&summary, &session.env.working_dir),
+            )?;
+        }
+
         session.console.notice(
             Variant::Success,
             "Update complete! Run <shell>proto install</shell> to install these new versions.",
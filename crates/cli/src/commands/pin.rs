@@ -5,7 +5,7 @@ use proto_core::flow::resolve::Resolver;
 use proto_core::{
     PinLocation, ProtoConfig, Tool, ToolContext, ToolSpec, cfg, reporter::NoticeOutput,
 };
-use proto_pdk_api::{PinVersionInput, PinVersionOutput, PluginFunction};
+use proto_pdk_api::{PinVersionInput, PinVersionOutput, PluginFunction, PolicyOperation};
 use starbase_console::ui::*;
 use starbase_styles::encode_style_tags;
 use std::path::PathBuf;
@@ -105,8 +105,14 @@ pub async fn pin(session: ProtoSession, args: PinArgs) -> SessionResult {
 
     if args.resolve {
         Resolver::resolve(&tool, &mut spec, false).await?;
+    } else if spec.req.is_fully_qualified() {
+        Resolver::new(&tool).validate_version_policy(&spec.req.to_resolved_spec())?;
     }
 
+    Resolver::new(&tool)
+        .check_policies(PolicyOperation::Pin, &spec)
+        .await?;
+
     let config_path;
 
     if args.tool_native {
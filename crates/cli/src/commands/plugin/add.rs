@@ -22,16 +22,18 @@ pub struct PluginAddArgs {
 #[instrument(skip(session))]
 pub async fn add(session: ProtoSession, args: PluginAddArgs) -> SessionResult {
     let config_path = ProtoConfig::update_document(session.env.get_config_dir(args.to), |doc| {
-        let key = if args.ty == PluginType::Backend {
-            "backends"
-        } else {
-            "tools"
+        let key = match args.ty {
+            PluginType::Backend => "backends",
+            PluginType::Policy => "policies",
+            PluginType::Tool => "tools",
         };
 
         // Convert legacy [plugins] to [plugins.tools]
         if doc.contains_key("plugins")
             && doc["plugins"].as_table().is_some_and(|table| {
-                !table.contains_key("backends") && !table.contains_key("tools")
+                !table.contains_key("backends")
+                    && !table.contains_key("policies")
+                    && !table.contains_key("tools")
             })
         {
             let existing = doc["plugins"].clone();
@@ -42,8 +44,8 @@ pub async fn add(session: ProtoSession, args: PluginAddArgs) -> SessionResult {
             plugins["tools"] = existing;
         }
 
-        // Add plugin to nested tables
-        if doc.contains_key("plugins") {
+        // Add plugin to nested tables (policies can only be configured here)
+        if doc.contains_key("plugins") || args.ty == PluginType::Policy {
             let plugins = doc["plugins"].or_insert(cfg::implicit_table());
             let table = plugins[key].or_insert(cfg::table());
             table[args.id.as_str()] = cfg::value(args.plugin.to_string());
@@ -58,7 +60,9 @@ pub async fn add(session: ProtoSession, args: PluginAddArgs) -> SessionResult {
     // session as the config has already been cached, and doesn't reflect
     // the recent addition!
     #[cfg(not(debug_assertions))]
-    {
+    if args.ty == PluginType::Policy {
+        proto_core::load_policy_plugin(&args.id, &session.env, &args.plugin).await?;
+    } else {
         use proto_core::ToolContext;
         use proto_core::reporter::NoticeOutput;
 
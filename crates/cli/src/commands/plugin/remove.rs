@@ -27,14 +27,20 @@ pub async fn remove(session: ProtoSession, args: PluginRemoveArgs) -> SessionRes
     }
 
     let config_path = ProtoConfig::update_document(config_dir, |doc| {
-        let key = if args.ty == PluginType::Backend {
-            "backends"
-        } else {
-            "tools"
+        let key = match args.ty {
+            PluginType::Backend => "backends",
+            PluginType::Policy => "policies",
+            PluginType::Tool => "tools",
         };
 
+        // Policies are only configured in [plugins.policies], so don't
+        // remove a tool with the same identifier
+        let is_policy = args.ty == PluginType::Policy;
+
         if let Some(plugins) = doc.get_mut("plugins").and_then(|item| item.as_table_mut()) {
-            plugins.remove(&args.id);
+            if !is_policy {
+                plugins.remove(&args.id);
+            }
 
             if let Some(table) = plugins.get_mut(key).and_then(|item| item.as_table_mut()) {
                 table.remove(&args.id);
@@ -49,6 +55,10 @@ pub async fn remove(session: ProtoSession, args: PluginRemoveArgs) -> SessionRes
             }
         }
 
+        if is_policy {
+            return;
+        }
+
         if let Some(tools) = doc.get_mut("tools").and_then(|item| item.as_table_mut()) {
             tools.remove(&args.id);
 
@@ -1,6 +1,7 @@
 use crate::session::{ProtoSession, SessionResult};
 use clap::Args;
-use proto_core::flow::link::Linker;
+use proto_core::ConfigMode;
+use proto_core::flow::link::{Linker, link_config_shims};
 use proto_core::flow::resolve::Resolver;
 use starbase_console::ui::*;
 use starbase_utils::fs;
@@ -77,6 +78,24 @@ pub async fn regen(session: ProtoSession, args: RegenArgs) -> SessionResult {
         }
     }
 
+    // Custom shims - Create for all, even if the tool has no configured version,
+    // and remove those that are no longer configured
+    progress.set_message("Regenerating custom shims");
+
+    debug!("Regenerating custom shims");
+
+    let mut shim_registry = store.load_shims_registry()?;
+
+    link_config_shims(
+        &session.env,
+        session.env.load_config_with_mode(ConfigMode::Global)?,
+        &mut shim_registry,
+        None,
+        true,
+    )?;
+
+    shim_registry.save()?;
+
     progress.stop().await?;
 
     session.console.notice(
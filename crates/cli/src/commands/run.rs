@@ -1,15 +1,17 @@
 use crate::commands::install::{InstallArgs, install_one};
 use crate::error::ProtoCliError;
 use crate::session::{ProtoSession, SessionResult};
+use crate::utils::tool_record::ToolRecord;
 use crate::workflows::{ExecCommandOptions, ExecWorkflow, ExecWorkflowParams};
 use clap::Args;
 use miette::IntoDiagnostic;
 use proto_core::flow::detect::{Detector, ProtoDetectError};
 use proto_core::flow::locate::{Locator, ProtoLocateError};
 use proto_core::flow::resolve::Resolver;
-use proto_core::layout::ShimRegistry;
+use proto_core::layout::{ShimCacheEntry, ShimRegistry};
 use proto_core::{
-    Id, PROTO_PLUGIN_KEY, ProtoEnvironment, ProtoLoaderError, Tool, ToolContext, ToolSpec,
+    Id, MANIFEST_NAME, PROTO_LOCK_NAME, PROTO_PLUGIN_KEY, ProtoConfigEnvOptions, ProtoEnvironment,
+    ProtoLoaderError, Tool, ToolContext, ToolSpec,
 };
 use proto_pdk_api::ExecutableConfig;
 use proto_shim::{exec_command_and_replace, locate_proto_exe};
@@ -410,6 +412,7 @@ pub async fn run(session: ProtoSession, mut args: RunArgs) -> SessionResult {
                         req: resolved_version.to_unresolved_spec(),
                         version: Some(resolved_version.clone()),
                         version_locked: None,
+                        version_held_back: None,
                         resolve_from_manifest: false,
                         resolve_from_lockfile: false,
                         update_lockfile: false,
@@ -474,6 +477,7 @@ pub async fn run(session: ProtoSession, mut args: RunArgs) -> SessionResult {
     tool.detected_version = Some(spec);
 
     let tool_name = tool.get_name().to_string();
+    let self_upgrade_commands = tool.metadata.self_upgrade_commands.clone();
     let tools = session.load_tool_dependencies(tool).await?;
     let specs = tools
         .iter()
@@ -484,6 +488,16 @@ pub async fn run(session: ProtoSession, mut args: RunArgs) -> SessionResult {
         })
         .collect::<FxHashMap<_, _>>();
 
+    // Track what the resolution depends on, so that the shim can reuse it
+    let shim_cache_entry = match get_cacheable_shim_name(&args, use_global_proto) {
+        Some(shim_name) => Some((
+            shim_name,
+            create_shim_cache_entry(&session, &tools, &specs, &self_upgrade_commands).await?,
+        )),
+        None => None,
+    };
+    let multiple = tools.len() > 1;
+
     // Prepare environment
     let config = session.load_config()?;
     let mut workflow = ExecWorkflow::new(tools, config);
@@ -502,6 +516,12 @@ pub async fn run(session: ProtoSession, mut args: RunArgs) -> SessionResult {
         )
         .await?;
 
+    if let Some((shim_name, entry)) = shim_cache_entry
+        && !workflow.pre_run_hooked
+    {
+        cache_shim_resolution(&session, shim_name, entry, &workflow, &exe_config, multiple);
+    }
+
     // Create and run command
     let command = create_command(workflow, tool_name, exe_config, args.passthrough)?;
 
@@ -511,6 +531,135 @@ pub async fn run(session: ProtoSession, mut args: RunArgs) -> SessionResult {
         .map(|_| None)
 }
 
+fn get_cacheable_shim_name(args: &RunArgs, use_global_proto: bool) -> Option<String> {
+    if use_global_proto
+        || args.spec.is_some()
+        || env::var("PROTO_SHIM_CACHE").is_ok_and(|var| var == "0" || var == "false")
+    {
+        return None;
+    }
+
+    let shim_name = env::var("PROTO_SHIM_NAME").ok()?;
+
+    // The shim name is inherited by child processes, so only cache
+    // when this process was spawned by the shim itself
+    let spawned_by_shim = match &args.exe {
+        Some(exe) => exe == &shim_name,
+        None => args.context.id.as_str() == shim_name,
+    };
+
+    spawned_by_shim.then_some(shim_name)
+}
+
+async fn create_shim_cache_entry(
+    session: &ProtoSession,
+    tools: &[ToolRecord],
+    specs: &FxHashMap<ToolContext, ToolSpec>,
+    self_upgrade_commands: &[String],
+) -> miette::Result<ShimCacheEntry> {
+    let manager = session.env.load_file_manager()?;
+    let config = session.load_config()?;
+    let mut entry = ShimCacheEntry::default();
+
+    entry.track_var("PROTO_ENV");
+
+    for dir_entry in &manager.entries {
+        entry.track_file(dir_entry.path.join(PROTO_LOCK_NAME));
+
+        for file in &dir_entry.configs {
+            entry.track_file(file.path.clone());
+        }
+    }
+
+    for tool in tools {
+        entry.track_var(format!("{}_VERSION", tool.get_env_var_prefix()));
+        entry.track_file(tool.get_inventory_dir().join(MANIFEST_NAME));
+
+        let version_files = Detector::new(tool).get_version_files().await?;
+
+        for dir_entry in &manager.entries {
+            for file in &version_files {
+                entry.track_file(dir_entry.path.join(file));
+            }
+        }
+
+        for env_file in config.get_env_files(&ProtoConfigEnvOptions {
+            context: Some(&tool.context),
+            include_shared: true,
+            ..Default::default()
+        }) {
+            entry.track_file(env_file.to_owned());
+        }
+
+        if let Some(version) = specs
+            .get(&tool.context)
+            .and_then(|spec| spec.version.as_ref())
+        {
+            entry
+                .products
+                .push(tool.inventory.locate_product_dir(version));
+        }
+    }
+
+    for command in self_upgrade_commands {
+        if let Ok(args) = shell_words::split(command) {
+            entry.self_upgrade_args.push(args);
+        }
+    }
+
+    Ok(entry)
+}
+
+fn cache_shim_resolution(
+    session: &ProtoSession,
+    shim_name: String,
+    mut entry: ShimCacheEntry,
+    workflow: &ExecWorkflow<'_>,
+    exe_config: &ExecutableConfig,
+    multiple: bool,
+) {
+    let Some(exe_path) = &exe_config.exe_path else {
+        return;
+    };
+
+    if let Some(parent_exe_name) = &exe_config.parent_exe_name {
+        entry.exe = PathBuf::from(parent_exe_name);
+        entry.before_args.extend(exe_config.parent_exe_args.clone());
+        entry
+            .before_args
+            .push(exe_path.to_string_lossy().to_string());
+    } else {
+        entry.exe = exe_path.to_owned();
+    }
+
+    if !multiple {
+        entry.after_args.extend(workflow.args.clone());
+    }
+
+    // Config variables are not set when inherited from the process,
+    // so the process values must also be tracked
+    for (key, value) in &workflow.env {
+        entry.track_var(key);
+        entry.env.insert(key.to_owned(), value.to_owned());
+    }
+
+    entry.paths.extend(workflow.paths.iter().cloned());
+
+    debug!(
+        shim = &shim_name,
+        exe = ?entry.exe,
+        "Caching shim resolution for the current directory"
+    );
+
+    let mut cache = session.env.store.load_shim_cache(&session.env.working_dir);
+
+    cache.insert(shim_name, entry);
+
+    if let Err(error) = cache.save() {
+        debug!("Failed to save shim cache: {error}");
+    }
+}
+
 fn create_command(
     workflow: ExecWorkflow<'_>,
     tool_name: String,
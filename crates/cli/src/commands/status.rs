@@ -52,7 +52,7 @@ pub async fn status(session: ProtoSession, _args: StatusArgs) -> SessionResult {
         {
             if tool.is_installed(&spec) {
                 item.is_installed = true;
-                item.product_dir = Some(tool.get_product_dir(&spec));
+                item.product_dir = Some(tool.locate_product_dir(&spec));
             }
 
             item.resolved_version = Some(version);
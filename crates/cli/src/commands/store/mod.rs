@@ -0,0 +1,3 @@
+mod move_store;
+
+pub use move_store::*;
@@ -0,0 +1,389 @@
+use crate::error::ProtoCliError;
+use crate::session::{ProtoSession, SessionResult};
+use clap::Args;
+use proto_core::layout::Store;
+use proto_core::replace_path_in_string;
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use starbase_console::ui::*;
+use starbase_utils::{envx, fs, path};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+#[derive(Args, Clone, Debug)]
+pub struct StoreMoveArgs {
+    #[arg(required = true, help = "New location of the store")]
+    path: PathBuf,
+
+    #[arg(
+        long,
+        help = "Don't update the shell profile",
+        alias = "no-profile",
+        env = "PROTO_NO_MODIFY_PROFILE"
+    )]
+    no_modify_profile: bool,
+}
+
+#[derive(Default, Serialize)]
+pub struct StoreMoveResult {
+    bins: Vec<PathBuf>,
+    from: PathBuf,
+    profile: Option<PathBuf>,
+    shims: Vec<String>,
+    to: PathBuf,
+}
+
+fn resolve_destination(session: &ProtoSession, dir: &Path) -> PathBuf {
+    let dir = match dir.strip_prefix("~") {
+        Ok(rel_dir) => session.env.home_dir.join(rel_dir),
+        Err(_) => session.env.working_dir.join(dir),
+    };
+
+    path::clean(dir)
+}
+
+fn move_dir(from: &Path, to: &Path) -> Result<(), ProtoCliError> {
+    if let Err(error) = fs::rename(from, to) {
+        // Renaming fails across file systems/devices,
+        // so fallback to a copy and remove
+        debug!("Failed to rename store, copying instead: {error}");
+
+        if let Err(error) = copy_dir_with_links(from, to, &mut FxHashMap::default())
+            .and_then(|_| verify_copied_dir(from, to))
+        {
+            // Keep the original store intact and discard the partial copy
+            let _ = fs::remove_dir_all(to);
+
+            return Err(error);
+        }
+
+        fs::remove_dir_all(from)?;
+    }
+
+    Ok(())
+}
+
+// Files that share an inode (device, inode), mapped to their first copy
+type HardLinks = FxHashMap<(u64, u64), PathBuf>;
+
+fn copy_dir_with_links(from: &Path, to: &Path, links: &mut HardLinks) -> Result<(), ProtoCliError> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let from_path = entry.path();
+        let to_path = to.join(entry.file_name());
+
+        if from_path.is_symlink() {
+            // Bins are relinked after moving
+            copy_symlink(&from_path, &to_path)?;
+        } else if from_path.is_dir() {
+            copy_dir_with_links(&from_path, &to_path, links)?;
+        } else {
+            copy_file_with_links(&from_path, &to_path, links)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_file_with_links(
+    from: &Path,
+    to: &Path,
+    links: &mut HardLinks,
+) -> Result<(), ProtoCliError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let meta = fs::metadata(from)?;
+
+    if meta.nlink() <= 1 {
+        fs::copy_file(from, to)?;
+
+        return Ok(());
+    }
+
+    // Recreate hard links instead of duplicating the file contents
+    if let Some(first_path) = links.get(&(meta.dev(), meta.ino())) {
+        std::fs::hard_link(first_path, to).map_err(|error| fs::FsError::Create {
+            path: to.to_path_buf(),
+            error: Box::new(error),
+        })?;
+    } else {
+        fs::copy_file(from, to)?;
+
+        links.insert((meta.dev(), meta.ino()), to.to_path_buf());
+    }
+
+    Ok(())
+}
+
+// Hard links can't be detected on stable Rust for Windows
+#[cfg(windows)]
+fn copy_file_with_links(
+    from: &Path,
+    to: &Path,
+    _links: &mut HardLinks,
+) -> Result<(), ProtoCliError> {
+    fs::copy_file(from, to)?;
+
+    Ok(())
+}
+
+fn copy_symlink(from: &Path, to: &Path) -> Result<(), ProtoCliError> {
+    let target = std::fs::read_link(from).map_err(|error| fs::FsError::Read {
+        path: from.to_path_buf(),
+        error: Box::new(error),
+    })?;
+
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(&target, to);
+
+    // Windows requires the kind of the target to be known
+    #[cfg(windows)]
+    let result = if from.is_dir() {
+        std::os::windows::fs::symlink_dir(&target, to)
+    } else {
+        std::os::windows::fs::symlink_file(&target, to)
+    };
+
+    result.map_err(|error| fs::FsError::Create {
+        path: to.to_path_buf(),
+        error: Box::new(error),
+    })?;
+
+    Ok(())
+}
+
+fn verify_copied_dir(from: &Path, to: &Path) -> Result<(), ProtoCliError> {
+    for entry in fs::read_dir(from)? {
+        let from_path = entry.path();
+        let to_path = to.join(entry.file_name());
+
+        let (Ok(from_meta), Ok(to_meta)) = (
+            std::fs::symlink_metadata(&from_path),
+            std::fs::symlink_metadata(&to_path),
+        ) else {
+            return Err(ProtoCliError::StoreMoveCopyMismatch { path: from_path });
+        };
+
+        let copied = if from_meta.is_symlink() {
+            to_meta.is_symlink()
+                && std::fs::read_link(&from_path).ok() == std::fs::read_link(&to_path).ok()
+        } else if from_meta.is_dir() {
+            to_meta.is_dir()
+        } else {
+            to_meta.is_file() && from_meta.len() == to_meta.len()
+        };
+
+        if !copied {
+            return Err(ProtoCliError::StoreMoveCopyMismatch { path: from_path });
+        }
+
+        if from_meta.is_dir() {
+            verify_copied_dir(&from_path, &to_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn relink_bins(store: &Store, from: &Path) -> Result<Vec<PathBuf>, ProtoCliError> {
+    let mut bins = vec![];
+
+    if !store.bin_dir.exists() {
+        return Ok(bins);
+    }
+
+    for entry in fs::read_dir(&store.bin_dir)? {
+        let bin_path = entry.path();
+
+        if !bin_path.is_symlink() {
+            continue;
+        }
+
+        let Ok(target) = std::fs::read_link(&bin_path) else {
+            continue;
+        };
+
+        let Ok(rel_target) = target.strip_prefix(from) else {
+            continue;
+        };
+
+        let new_target = store.dir.join(rel_target);
+
+        debug!(bin = ?bin_path, target = ?new_target, "Relinking bin");
+
+        store.unlink_bin(&bin_path)?;
+        store.link_bin(&bin_path, &new_target)?;
+
+        bins.push(bin_path);
+    }
+
+    Ok(bins)
+}
+
+// Windows bins are copies of the executable, not symlinks
+#[cfg(windows)]
+fn relink_bins(_store: &Store, _from: &Path) -> Result<Vec<PathBuf>, ProtoCliError> {
+    Ok(vec![])
+}
+
+fn rewrite_profile(
+    session: &ProtoSession,
+    profile: &Path,
+    from: &Path,
+    to: &Path,
+) -> Result<bool, ProtoCliError> {
+    let mut content = fs::read_file(profile)?;
+    let mut changed = false;
+
+    // Profiles may reference the store through an environment variable,
+    // like `$HOME/.proto`, instead of an absolute path
+    let mut vars = vec![
+        ("$HOME", session.env.home_dir.clone()),
+        ("${HOME}", session.env.home_dir.clone()),
+        ("~", session.env.home_dir.clone()),
+    ];
+
+    if let Some(xdg_dir) = envx::path_var("XDG_DATA_HOME") {
+        vars.push(("$XDG_DATA_HOME", xdg_dir.clone()));
+        vars.push(("${XDG_DATA_HOME}", xdg_dir));
+    }
+
+    let mut replacements = vec![(
+        from.to_string_lossy().to_string(),
+        to.to_string_lossy().to_string(),
+    )];
+
+    for (var, dir) in vars {
+        let Ok(rel_from) = from.strip_prefix(&dir) else {
+            continue;
+        };
+
+        replacements.push((
+            format!("{var}/{}", path::standardize_separators(rel_from)),
+            match to.strip_prefix(&dir) {
+                Ok(rel_to) => format!("{var}/{}", path::standardize_separators(rel_to)),
+                Err(_) => to.to_string_lossy().to_string(),
+            },
+        ));
+    }
+
+    for (from_value, to_value) in replacements {
+        if let Some(new_content) = replace_path_in_string(&content, &from_value, &to_value) {
+            content = new_content;
+            changed = true;
+        }
+    }
+
+    if changed {
+        debug!(profile = ?profile, "Updating store location in shell profile");
+
+        fs::write_file(profile, content)?;
+    }
+
+    Ok(changed)
+}
+
+#[instrument(skip(session))]
+pub async fn move_store(session: ProtoSession, args: StoreMoveArgs) -> SessionResult {
+    let from = session.env.store.dir.clone();
+    let to = resolve_destination(&session, &args.path);
+
+    if !from.exists() {
+        return Err(ProtoCliError::StoreMoveMissing { path: from }.into());
+    }
+
+    if path::are_equal(&from, &to) {
+        return Err(ProtoCliError::StoreMoveSame { path: from }.into());
+    }
+
+    if to.starts_with(&from) {
+        return Err(ProtoCliError::StoreMoveNested { from, to }.into());
+    }
+
+    if to.exists() {
+        if !to.is_dir() || !fs::read_dir(&to)?.is_empty() {
+            return Err(ProtoCliError::StoreMoveNotEmpty { path: to }.into());
+        }
+
+        fs::remove_dir_all(&to)?;
+    }
+
+    // Move the entire store
+    debug!(from = ?from, to = ?to, "Moving store");
+
+    move_dir(&from, &to)?;
+
+    let store = Store::new(&to, None);
+    let mut result = StoreMoveResult {
+        from: from.clone(),
+        to: to.clone(),
+        ..Default::default()
+    };
+
+    // Shims are copies of the proto-shim binary, and locate proto through
+    // `PROTO_HOME`, so only the paths in their registry must be rewritten
+    let mut registry = store.load_shims_registry()?;
+
+    result.shims = registry.replace_path(&from, &to)?;
+
+    registry.save()?;
+
+    // Bins are symlinks to an executable within the store
+    result.bins = relink_bins(&store, &from)?;
+
+    // And lastly the `PROTO_HOME` export in the shell profile
+    if !args.no_modify_profile
+        && let Some(profile) = store.load_preferred_profile()?
+    {
+        if profile.exists() {
+            if rewrite_profile(&session, &profile, &from, &to)? {
+                result.profile = Some(profile);
+            }
+        } else {
+            warn!(profile = ?profile, "Shell profile does not exist, unable to update");
+        }
+    }
+
+    if session.is_json_format() {
+        session.console.write_json_for_format(result)?;
+
+        return Ok(None);
+    }
+
+    session.console.notice(
+        Variant::Success,
+        format!(
+            "Moved store from <path>{}</path> to <path>{}</path>, and updated {} shims and {} bins!",
+            from.display(),
+            to.display(),
+            result.shims.len(),
+            result.bins.len(),
+        ),
+    )?;
+
+    match &result.profile {
+        Some(profile) => {
+            session.console.notice(
+                Variant::Info,
+                format!(
+                    "Updated the shell profile at <path>{}</path>. Launch a new terminal to use the new store.",
+                    profile.display()
+                ),
+            )?;
+        }
+        None => {
+            session.console.notice(
+                Variant::Caution,
+                format!(
+                    "Update the <property>PROTO_HOME</property> environment variable to <path>{}</path>, and launch a new terminal to use the new store.",
+                    to.display()
+                ),
+            )?;
+        }
+    };
+
+    Ok(None)
+}
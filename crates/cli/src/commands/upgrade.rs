@@ -1,18 +1,22 @@
 use crate::commands::install::{InstallArgs, install_one};
 use crate::error::ProtoCliError;
-use crate::helpers::fetch_latest_version;
+use crate::helpers::fetch_latest_version_for_channel;
 use crate::session::{ProtoSession, SessionResult};
 use crate::telemetry::{Metric, track_usage};
 use clap::Args;
 use iocraft::prelude::element;
-use proto_core::{Id, PROTO_PLUGIN_KEY, ToolContext, UnresolvedVersionSpec, Version, is_offline};
+use proto_core::{
+    Id, PROTO_PLUGIN_KEY, ProtoConfigError, ToolContext, UnresolvedVersionSpec, UpgradeChannel,
+    Version, is_offline,
+};
 use serde::Serialize;
 use starbase_console::ui::*;
 use starbase_styles::color;
 use starbase_utils::{fs, fs::FsError, path};
 use std::env;
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tracing::{debug, instrument};
 
 #[derive(Args, Clone, Debug)]
@@ -20,25 +24,218 @@ pub struct UpgradeArgs {
     #[arg(help = "Explicit version to upgrade or downgrade to")]
     target: Option<Version>,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "Release channel to fetch the latest version from"
+    )]
+    channel: Option<UpgradeChannel>,
+
     #[arg(long, help = "Check versions only and avoid upgrading")]
     check: bool,
+
+    #[arg(
+        long,
+        help = "Don't verify the downloaded archive against a checksum or signature"
+    )]
+    no_verify: bool,
+
+    #[arg(
+        long,
+        help = "Rollback to the version that was replaced by the last upgrade",
+        conflicts_with_all = ["target", "channel", "check"]
+    )]
+    rollback: bool,
 }
 
 #[derive(Serialize)]
 struct UpgradeOutput {
     available: bool,
+    channel: UpgradeChannel,
     current_version: String,
     latest_version: String,
     target_version: String,
 }
 
+/// Verify that the version satisfies the `proto-version` requirement of every
+/// loaded config. This is not checked when loading configs while upgrading,
+/// so it's checked here against the target version instead.
+fn check_supported_version(session: &ProtoSession, version: &Version) -> Result<(), ProtoCliError> {
+    for file in session.env.load_config_files()? {
+        if let Err(ProtoConfigError::UnsupportedProtoVersion {
+            path, requirement, ..
+        }) = file.check_proto_version(version)
+        {
+            return Err(ProtoCliError::UpgradeUnsupportedVersion {
+                path,
+                requirement,
+                version: version.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Return all backed up versions, ordered from the most recent backup.
+fn load_backups(backups_dir: &Path) -> Result<Vec<(Version, PathBuf)>, ProtoCliError> {
+    let mut backups = vec![];
+
+    if !backups_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    for entry in fs::read_dir(backups_dir)? {
+        let dir = entry.path();
+
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let Ok(version) = Version::parse(fs::file_name(&dir)) else {
+            continue;
+        };
+
+        let modified = fs::metadata(&dir)?
+            .modified()
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        backups.push((modified, version, dir));
+    }
+
+    backups.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+
+    Ok(backups
+        .into_iter()
+        .map(|(_, version, dir)| (version, dir))
+        .collect())
+}
+
+/// Copy the binaries that are about to be replaced into the backups directory,
+/// so that they can be restored with a rollback.
+#[instrument]
+fn backup_binaries(
+    bin_dir: &Path,
+    backups_dir: &Path,
+    version: &Version,
+) -> Result<bool, ProtoCliError> {
+    let backup_dir = backups_dir.join(version.to_string());
+    let mut backed_up = false;
+
+    // Recreate so that it's the most recent backup
+    fs::remove_dir_all(&backup_dir)?;
+
+    for bin_name in [path::exe_name("proto"), path::exe_name("proto-shim")] {
+        let bin_path = bin_dir.join(&bin_name);
+
+        if !bin_path.exists() {
+            continue;
+        }
+
+        let backup_path = backup_dir.join(&bin_name);
+
+        fs::copy_file(&bin_path, &backup_path)?;
+        fs::update_perms(&backup_path, None)?;
+
+        backed_up = true;
+    }
+
+    if backed_up {
+        debug!(
+            backup_dir = ?backup_dir,
+            "Backed up proto {} binaries",
+            color::hash(version.to_string())
+        );
+    }
+
+    Ok(backed_up)
+}
+
+/// Remove the oldest backups that exceed the configured limit.
+fn prune_backups(backups_dir: &Path, keep: usize) -> Result<(), ProtoCliError> {
+    for (version, dir) in load_backups(backups_dir)?.into_iter().skip(keep) {
+        debug!(
+            "Removing backup of proto {}, as it exceeds the limit of {keep}",
+            color::hash(version.to_string())
+        );
+
+        fs::remove_dir_all(dir)?;
+    }
+
+    Ok(())
+}
+
+fn should_relocate_current(session: &ProtoSession) -> bool {
+    let tool_dir = session.env.store.inventory_dir.join(PROTO_PLUGIN_KEY);
+    let is_current_exe_managed = env::current_exe().is_ok_and(|exe| exe.starts_with(&tool_dir));
+
+    // Don't relocate within our CI pipeline as it causes issues,
+    // but do relocate for other user's CI and local development
+    !is_current_exe_managed && env::var("PROTO_TEST").is_err()
+}
+
+#[instrument(skip(session))]
+async fn rollback(session: ProtoSession) -> SessionResult {
+    let store = &session.env.store;
+    let backups_dir = store.backups_dir.join(PROTO_PLUGIN_KEY);
+    let current_version = session.cli_version.clone();
+
+    let Some((target_version, backup_dir)) = load_backups(&backups_dir)?
+        .into_iter()
+        .find(|(version, _)| *version != current_version)
+    else {
+        return Err(ProtoCliError::UpgradeNoRollback { dir: backups_dir }.into());
+    };
+
+    check_supported_version(&session, &target_version)?;
+
+    // Backup the current binaries, so that the rollback can be undone
+    let settings = &session.env.load_config()?.settings;
+
+    if settings.upgrade.keep_previous > 0 {
+        backup_binaries(&store.bin_dir, &backups_dir, &current_version)?;
+    }
+
+    let restored = replace_binaries(
+        &backup_dir,
+        store.bin_dir.clone(),
+        should_relocate_current(&session),
+    )?;
+
+    if !restored {
+        return Err(ProtoCliError::UpgradeFailed {
+            bin: "proto".into(),
+        }
+        .into());
+    }
+
+    // The backup is now the current version
+    fs::remove_dir_all(&backup_dir)?;
+
+    prune_backups(&backups_dir, settings.upgrade.keep_previous)?;
+
+    session.console.notice(
+        Variant::Success,
+        format!("Rolled back proto to <version>{target_version}</version>!"),
+    )?;
+
+    Ok(None)
+}
+
 #[instrument(skip(session))]
 pub async fn upgrade(session: ProtoSession, args: UpgradeArgs) -> SessionResult {
+    if args.rollback {
+        return rollback(session).await;
+    }
+
     if is_offline() {
         return Err(ProtoCliError::UpgradeRequiresInternet.into());
     }
 
-    let latest_version = fetch_latest_version().await?;
+    let settings = &session.env.load_config()?.settings;
+    let channel = args.channel.unwrap_or(settings.upgrade.channel);
+
+    let latest_version = fetch_latest_version_for_channel(channel).await?;
     let latest = latest_version.to_string();
 
     let current_version = session.cli_version.clone();
@@ -61,6 +258,7 @@ pub async fn upgrade(session: ProtoSession, args: UpgradeArgs) -> SessionResult
     if session.is_json_format() {
         session.console.write_json_for_format(UpgradeOutput {
             available: !not_available,
+            channel,
             current_version: current,
             latest_version: latest,
             target_version: target,
@@ -132,11 +330,16 @@ pub async fn upgrade(session: ProtoSession, args: UpgradeArgs) -> SessionResult
         }
     }
 
+    // Don't upgrade (or downgrade) to a version that the project doesn't support
+    check_supported_version(&session, &target_version)?;
+
     // Load the tool and install the new version
     install_one(
         session.clone(),
         InstallArgs {
             internal: true,
+            // Refuse to unpack an archive that can't be verified
+            require_verified: !args.no_verify,
             spec: Some(UnresolvedVersionSpec::Version(target_version.clone()).into()),
             ..Default::default()
         },
@@ -144,18 +347,27 @@ pub async fn upgrade(session: ProtoSession, args: UpgradeArgs) -> SessionResult
     )
     .await?;
 
-    // Replace the global binaries
-    let tool_dir = session.env.store.inventory_dir.join(PROTO_PLUGIN_KEY);
-    let is_current_exe_managed = env::current_exe().is_ok_and(|exe| exe.starts_with(&tool_dir));
+    // Backup the current binaries, so that the upgrade can be rolled back
+    let backups_dir = session.env.store.backups_dir.join(PROTO_PLUGIN_KEY);
+
+    if settings.upgrade.keep_previous > 0 {
+        backup_binaries(&session.env.store.bin_dir, &backups_dir, &current_version)?;
+    }
 
+    // Replace the global binaries
     let upgraded = replace_binaries(
-        tool_dir.join(target_version.to_string()),
+        session
+            .env
+            .store
+            .inventory_dir
+            .join(PROTO_PLUGIN_KEY)
+            .join(target_version.to_string()),
         session.env.store.bin_dir.clone(),
-        // Don't relocate within our CI pipeline as it causes issues,
-        // but do relocate for other user's CI and local development
-        !is_current_exe_managed && env::var("PROTO_TEST").is_err(),
+        should_relocate_current(&session),
     )?;
 
+    prune_backups(&backups_dir, settings.upgrade.keep_previous)?;
+
     // Track usage metrics
     track_usage(
         &session.env,
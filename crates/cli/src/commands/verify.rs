@@ -0,0 +1,638 @@
+use crate::commands::install::{InstallArgs, install_one};
+use crate::components::{Issue, IssuesList};
+use crate::session::{ProtoSession, SessionResult};
+use clap::Args;
+use iocraft::prelude::{FlexDirection, View, element};
+use proto_core::checksum::fingerprint_dir;
+use proto_core::flow::link::Linker;
+use proto_core::flow::locate::Locator;
+use proto_core::{PROTO_PLUGIN_KEY, SCHEMA_PLUGIN_KEY, Tool, ToolContext, ToolSpec, VersionSpec};
+use proto_pdk_api::PluginFunction;
+use proto_shim::get_shim_file_name;
+use serde::Serialize;
+use starbase_console::ui::*;
+use starbase_utils::{fs, path};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct VerifyArgs {
+    #[arg(help = "Tool to verify")]
+    context: Option<ToolContext>,
+
+    #[arg(
+        long,
+        help = "Reinstall broken versions, and relink broken shims and bins"
+    )]
+    repair: bool,
+}
+
+#[derive(Serialize)]
+pub struct VerifyVersionResult {
+    issues: Vec<Issue>,
+    modified: bool,
+    version: VersionSpec,
+
+    // Changes that are reported, but are not counted as issues
+    warnings: Vec<Issue>,
+
+    // Modified versions are not broken, as packages
+    // may have been installed into the tool's directory
+    #[serde(skip)]
+    broken: bool,
+}
+
+#[derive(Serialize)]
+pub struct VerifyToolResult {
+    issues: Vec<Issue>,
+    tool: ToolContext,
+    versions: Vec<VerifyVersionResult>,
+
+    #[serde(skip)]
+    broken_bins: Vec<PathBuf>,
+}
+
+impl VerifyToolResult {
+    fn has_issues(&self) -> bool {
+        !self.issues.is_empty() || self.versions.iter().any(|item| !item.issues.is_empty())
+    }
+}
+
+#[derive(Serialize)]
+pub struct VerifyRepairedItem {
+    tool: ToolContext,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<VersionSpec>,
+}
+
+#[derive(Default, Serialize)]
+pub struct VerifyResult {
+    bins: Vec<Issue>,
+    repaired: Vec<VerifyRepairedItem>,
+    tools: Vec<VerifyToolResult>,
+
+    #[serde(skip)]
+    broken_bins: Vec<PathBuf>,
+}
+
+impl VerifyResult {
+    fn issue_count(&self) -> usize {
+        self.bins.len()
+            + self
+                .tools
+                .iter()
+                .map(|tool| {
+                    tool.issues.len()
+                        + tool
+                            .versions
+                            .iter()
+                            .map(|item| item.issues.len())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+}
+
+fn create_repair_issue(issue: String, context: &ToolContext) -> Issue {
+    Issue {
+        issue,
+        resolution: Some(format!(
+            "Run <shell>proto verify {context} --repair</shell> to reinstall the version"
+        )),
+        comment: None,
+    }
+}
+
+fn create_relink_issue(issue: String, context: &ToolContext) -> Issue {
+    Issue {
+        issue,
+        resolution: Some(format!(
+            "Run <shell>proto verify {context} --repair</shell> to relink shims and bins"
+        )),
+        comment: None,
+    }
+}
+
+/// Find all symlinks in the bin directory whose target no longer exists.
+fn find_broken_bins(bin_dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(bin_dir) else {
+        return vec![];
+    };
+
+    let mut bins = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_symlink() && !path.exists())
+        .filter_map(|path| std::fs::read_link(&path).ok().map(|target| (path, target)))
+        .collect::<Vec<_>>();
+
+    bins.sort();
+    bins
+}
+
+async fn verify_version(tool: &Tool, version: &VersionSpec) -> miette::Result<VerifyVersionResult> {
+    let manifest = &tool.inventory.manifest;
+    let context = &tool.context;
+    let mut issues = vec![];
+
+    if !manifest.versions.contains_key(version) {
+        issues.push(create_repair_issue(
+            format!(
+                "Version <version>{version}</version> is installed but has no entry in manifest <path>{}</path>",
+                manifest.path.display()
+            ),
+            context,
+        ));
+    }
+
+    if !manifest.installed_versions.contains(version) {
+        issues.push(create_repair_issue(
+            format!(
+                "Version <version>{version}</version> has an entry in manifest <path>{}</path> but is not marked as installed",
+                manifest.path.display()
+            ),
+            context,
+        ));
+    }
+
+    let spec = ToolSpec::new_resolved(version.to_owned());
+    let locator = Locator::new(tool, &spec);
+
+    if !locator.product_dir.exists() {
+        issues.push(create_repair_issue(
+            format!(
+                "Install directory <path>{}</path> for version <version>{version}</version> does not exist",
+                locator.product_dir.display()
+            ),
+            context,
+        ));
+
+        return Ok(VerifyVersionResult {
+            broken: true,
+            issues,
+            modified: false,
+            version: version.to_owned(),
+            warnings: vec![],
+        });
+    }
+
+    if tool
+        .plugin
+        .has_func(PluginFunction::LocateExecutables)
+        .await
+    {
+        for exe in locator.locate_exes().await? {
+            if !exe.path.exists() {
+                issues.push(create_repair_issue(
+                    format!(
+                        "Executable <file>{}</file> for version <version>{version}</version> does not exist at <path>{}</path>",
+                        exe.name,
+                        exe.path.display()
+                    ),
+                    context,
+                ));
+            }
+            // Permissions are updated when the executable is located
+            else if !exe.config.update_perms && !fs::is_executable(&exe.path) {
+                issues.push(create_repair_issue(
+                    format!(
+                        "Executable <file>{}</file> for version <version>{version}</version> at <path>{}</path> is not executable",
+                        exe.name,
+                        exe.path.display()
+                    ),
+                    context,
+                ));
+            }
+        }
+    }
+
+    let broken = !issues.is_empty();
+    let mut modified = false;
+    let mut warnings = vec![];
+
+    if let Some(fingerprint) = manifest
+        .versions
+        .get(version)
+        .and_then(|meta| meta.fingerprint.as_ref())
+        && fingerprint_dir(&locator.product_dir)? != *fingerprint
+    {
+        modified = true;
+
+        warnings.push(Issue {
+            issue: format!(
+                "Files in install directory <path>{}</path> for version <version>{version}</version> have changed since it was installed",
+                locator.product_dir.display()
+            ),
+            resolution: Some(format!(
+                "Run <shell>proto install {context} {version} --force</shell> to reinstall the version"
+            )),
+            comment: Some(
+                "This is expected if packages were installed into the tool's directory".into(),
+            ),
+        });
+    }
+
+    Ok(VerifyVersionResult {
+        broken,
+        issues,
+        modified,
+        version: version.to_owned(),
+        warnings,
+    })
+}
+
+#[instrument(skip_all)]
+async fn verify_tool(
+    session: &ProtoSession,
+    tool: &Tool,
+    broken_bins: &mut Vec<(PathBuf, PathBuf)>,
+) -> miette::Result<VerifyToolResult> {
+    let manifest = &tool.inventory.manifest;
+    let context = &tool.context;
+
+    debug!(tool = context.as_str(), "Verifying tool");
+
+    let mut result = VerifyToolResult {
+        issues: vec![],
+        tool: context.to_owned(),
+        versions: vec![],
+        broken_bins: vec![],
+    };
+
+    let versions = manifest
+        .installed_versions
+        .iter()
+        .chain(manifest.versions.keys())
+        .collect::<BTreeSet<_>>();
+
+    for version in &versions {
+        result.versions.push(verify_version(tool, version).await?);
+    }
+
+    // Shims are registered when linked, so verify that
+    // every registered shim for this tool still exists
+    if let Some(latest) = manifest.installed_versions.last() {
+        let spec = ToolSpec::new_resolved(latest.to_owned());
+        let registry = session.env.store.load_shims_registry()?;
+
+        for shim in Locator::new(tool, &spec).locate_shims().await? {
+            let Some(entry) = registry.get(&shim.name) else {
+                continue;
+            };
+
+            let owned_by_this = match &entry.context {
+                Some(owner) => owner == context,
+                None => context.id.as_str() == shim.name,
+            };
+
+            if owned_by_this && !shim.path.exists() {
+                result.issues.push(create_relink_issue(
+                    format!(
+                        "Shim <file>{}</file> is registered but does not exist at <path>{}</path>",
+                        shim.name,
+                        session
+                            .env
+                            .store
+                            .shims_dir
+                            .join(get_shim_file_name(&shim.name))
+                            .display()
+                    ),
+                    context,
+                ));
+            }
+        }
+    }
+
+    // Bins are symlinks into the tool's inventory
+    let inventory_dir = tool.get_inventory_dir();
+
+    broken_bins.retain(|(bin, target)| {
+        if !target.starts_with(inventory_dir) {
+            return true;
+        }
+
+        result.issues.push(create_relink_issue(
+            format!(
+                "Binary <file>{}</file> points to a target that does not exist <path>{}</path>",
+                fs::file_name(bin),
+                target.display()
+            ),
+            context,
+        ));
+        result.broken_bins.push(bin.to_owned());
+
+        false
+    });
+
+    Ok(result)
+}
+
+async fn load_tools_to_verify(
+    session: &ProtoSession,
+    args: &VerifyArgs,
+) -> miette::Result<Vec<Tool>> {
+    let contexts = match &args.context {
+        Some(context) => vec![context.to_owned()],
+        None => {
+            let config = session.load_config()?;
+            let store = &session.env.store;
+
+            // Only load tools that have been installed, as loading
+            // every plugin is expensive. Backend tools may use a
+            // scoped inventory directory, so always load them.
+            config
+                .plugins
+                .tools
+                .keys()
+                .map(|id| ToolContext::new(id.to_owned()))
+                .chain(config.versions.keys().cloned())
+                .filter(|context| {
+                    context.id != SCHEMA_PLUGIN_KEY
+                        && context.id != PROTO_PLUGIN_KEY
+                        && (context.backend.is_some()
+                            || store
+                                .inventory_dir
+                                .join(path::encode_component(&context.id))
+                                .exists())
+                })
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        }
+    };
+
+    let mut tools = vec![];
+
+    for context in contexts {
+        let tool = session.load_tool(&context).await?.tool;
+
+        if tool.inventory.manifest.installed_versions.is_empty()
+            && tool.inventory.manifest.versions.is_empty()
+        {
+            continue;
+        }
+
+        tools.push(tool);
+    }
+
+    Ok(tools)
+}
+
+async fn verify_all(session: &ProtoSession, args: &VerifyArgs) -> miette::Result<VerifyResult> {
+    let mut result = VerifyResult::default();
+    let mut broken_bins = find_broken_bins(&session.env.store.bin_dir);
+
+    for tool in load_tools_to_verify(session, args).await? {
+        result
+            .tools
+            .push(verify_tool(session, &tool, &mut broken_bins).await?);
+    }
+
+    // Bins that don't belong to a verified tool
+    if args.context.is_none() {
+        for (bin, target) in broken_bins {
+            result.bins.push(Issue {
+                issue: format!(
+                    "Binary <file>{}</file> points to a target that does not exist <path>{}</path>",
+                    fs::file_name(&bin),
+                    target.display()
+                ),
+                resolution: Some(
+                    "Run <shell>proto verify --repair</shell> to remove the binary".into(),
+                ),
+                comment: None,
+            });
+            result.broken_bins.push(bin);
+        }
+    }
+
+    Ok(result)
+}
+
+#[instrument(skip_all)]
+async fn repair(
+    session: &ProtoSession,
+    result: &VerifyResult,
+) -> miette::Result<Vec<VerifyRepairedItem>> {
+    let store = &session.env.store;
+    let mut repaired = vec![];
+
+    for bin in &result.broken_bins {
+        store.unlink_bin(bin)?;
+    }
+
+    for tool_result in &result.tools {
+        if !tool_result.has_issues() {
+            continue;
+        }
+
+        let context = &tool_result.tool;
+
+        // Reinstalling a version will also relink its shims and bins
+        let mut reinstalled = false;
+
+        for item in &tool_result.versions {
+            if !item.broken {
+                continue;
+            }
+
+            debug!(
+                tool = context.as_str(),
+                version = item.version.to_string(),
+                "Reinstalling broken version"
+            );
+
+            let version = item.version.to_owned();
+
+            let install_result = install_one(
+                session.clone(),
+                InstallArgs {
+                    force: true,
+                    internal: true,
+                    quiet: true,
+                    spec: Some(ToolSpec {
+                        req: version.to_unresolved_spec(),
+                        version: Some(version.clone()),
+                        version_locked: None,
+                        version_held_back: None,
+                        resolve_from_manifest: false,
+                        resolve_from_lockfile: false,
+                        update_lockfile: false,
+                    }),
+                    ..Default::default()
+                },
+                context.to_owned(),
+            )
+            .await;
+
+            match install_result {
+                Ok(_) => {
+                    reinstalled = true;
+                    repaired.push(VerifyRepairedItem {
+                        tool: context.to_owned(),
+                        version: Some(version),
+                    });
+                }
+                Err(error) => {
+                    warn!(
+                        tool = context.as_str(),
+                        version = version.to_string(),
+                        "Failed to reinstall version: {error}"
+                    );
+                }
+            };
+        }
+
+        if tool_result.issues.is_empty() || reinstalled {
+            continue;
+        }
+
+        for bin in &tool_result.broken_bins {
+            store.unlink_bin(bin)?;
+        }
+
+        let tool = session.load_tool(context).await?.tool;
+
+        if let Some(latest) = tool.inventory.manifest.installed_versions.last() {
+            let spec = ToolSpec::new_resolved(latest.to_owned());
+
+            debug!(tool = context.as_str(), "Relinking shims and bins");
+
+            Linker::link(&tool, &spec, true).await?;
+
+            repaired.push(VerifyRepairedItem {
+                tool: context.to_owned(),
+                version: None,
+            });
+        }
+    }
+
+    Ok(repaired)
+}
+
+#[instrument(skip(session))]
+pub async fn verify(session: ProtoSession, args: VerifyArgs) -> SessionResult {
+    let mut result = verify_all(&session, &args).await?;
+
+    if args.repair && result.issue_count() > 0 {
+        let repaired = repair(&session, &result).await?;
+
+        // Verify again to find issues that could not be repaired
+        result = verify_all(&session, &args).await?;
+        result.repaired = repaired;
+    }
+
+    let issue_count = result.issue_count();
+    let exit_code = if issue_count > 0 { Some(1) } else { None };
+
+    if session.is_json_format() {
+        session.console.write_json_for_format(result)?;
+
+        return Ok(exit_code);
+    }
+
+    let version_count = result
+        .tools
+        .iter()
+        .map(|tool| tool.versions.len())
+        .sum::<usize>();
+    let tool_count = result.tools.len();
+    let repaired = result
+        .repaired
+        .iter()
+        .map(|item| match &item.version {
+            Some(version) => format!(
+                "Reinstalled <id>{}</id> <version>{version}</version>",
+                item.tool
+            ),
+            None => format!("Relinked shims and bins for <id>{}</id>", item.tool),
+        })
+        .collect::<Vec<_>>();
+
+    let mut sections = vec![];
+    let mut warnings = vec![];
+
+    for tool in result.tools {
+        let mut issues = tool.issues;
+
+        for item in tool.versions {
+            issues.extend(item.issues);
+            warnings.extend(item.warnings);
+        }
+
+        if !issues.is_empty() {
+            sections.push((tool.tool.to_string(), issues));
+        }
+    }
+
+    if !result.bins.is_empty() {
+        sections.push(("Bins".into(), result.bins));
+    }
+
+    if !repaired.is_empty() || !sections.is_empty() || !warnings.is_empty() {
+        session.console.render(element! {
+            Container {
+                #(if repaired.is_empty() {
+                    None
+                } else {
+                    Some(element! {
+                        Section(title: "Repaired", variant: Variant::Success) {
+                            List {
+                                #(repaired.into_iter().map(|item| {
+                                    element! {
+                                        ListItem {
+                                            StyledText(content: item)
+                                        }
+                                    }
+                                }))
+                            }
+                        }
+                    })
+                })
+                View(flex_direction: FlexDirection::Column) {
+                    #(sections.into_iter().map(|(title, issues)| {
+                        element! {
+                            Section(title, variant: Variant::Failure) {
+                                IssuesList(issues)
+                            }
+                        }
+                    }))
+                }
+                #(if warnings.is_empty() {
+                    None
+                } else {
+                    Some(element! {
+                        Section(title: "Modified", variant: Variant::Caution) {
+                            IssuesList(issues: warnings)
+                        }
+                    })
+                })
+            }
+        })?;
+    }
+
+    if issue_count == 0 {
+        session.console.notice(
+            Variant::Success,
+            format!(
+                "Verified {version_count} installed versions across {tool_count} tools, no issues found!"
+            ),
+        )?;
+    } else if args.repair {
+        session.console.notice(
+            Variant::Failure,
+            format!("Unable to repair {issue_count} issues!"),
+        )?;
+    } else {
+        session.console.notice(
+            Variant::Caution,
+            format!(
+                "Found {issue_count} issues! Run <shell>proto verify --repair</shell> to reinstall broken versions, and relink shims and bins."
+            ),
+        )?;
+    }
+
+    Ok(exit_code)
+}
@@ -1,17 +1,36 @@
 use crate::components::create_datetime;
+use crate::helpers::get_today_date;
 use crate::session::{LoadToolOptions, ProtoSession, SessionResult};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use indexmap::IndexMap;
-use iocraft::prelude::{View, element};
+use iocraft::prelude::{AnyElement, FlexDirection, Size, View, element};
 use proto_core::flow::lock::Locker;
 use proto_core::{
     MatchesVersion, Requirement, ToolContext, ToolSpec, UnresolvedVersionSpec, VersionSpec,
 };
+use proto_pdk_api::VersionMetadata;
 use serde::Serialize;
 use starbase_console::ui::*;
 use std::collections::BTreeMap;
 use tracing::{debug, instrument};
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum VersionsGroupBy {
+    Major,
+    Minor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum VersionsFormat {
+    Table,
+    Json,
+    Ndjson,
+}
+
+fn parse_since(value: &str) -> Result<Requirement, String> {
+    Requirement::parse(format!(">={value}")).map_err(|error| error.to_string())
+}
+
 #[derive(Args, Clone, Debug)]
 pub struct VersionsArgs {
     #[arg(required = true, help = "Tool to list for")]
@@ -23,15 +42,39 @@ pub struct VersionsArgs {
     #[arg(long, help = "Include aliases in the output")]
     aliases: bool,
 
-    #[arg(long, help = "Only display installed versions")]
+    #[arg(long, help = "Print output in a specific format")]
+    format: Option<VersionsFormat>,
+
+    #[arg(long, help = "Group versions by their major or minor release line")]
+    group_by: Option<VersionsGroupBy>,
+
+    #[arg(
+        long,
+        alias = "installed-only",
+        help = "Only display installed versions"
+    )]
     installed: bool,
+
+    #[arg(
+        long,
+        value_parser = parse_since,
+        help = "Only display versions greater than or equal to the provided version"
+    )]
+    since: Option<Requirement>,
 }
 
 #[derive(Serialize)]
 pub struct VersionItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    installed: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     installed_at: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_at: Option<u128>,
     locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<VersionMetadata>,
     version: VersionSpec,
 }
 
@@ -42,6 +85,159 @@ pub struct VersionsOutput {
     remote_aliases: BTreeMap<String, ToolSpec>,
 }
 
+const OTHER_GROUP: &str = "other";
+
+fn get_group(version: &VersionSpec, group_by: VersionsGroupBy) -> String {
+    match (version.as_version(), group_by) {
+        (Some(version), VersionsGroupBy::Major) => version.major.to_string(),
+        (Some(version), VersionsGroupBy::Minor) => format!("{}.{}", version.major, version.minor),
+        (None, _) => OTHER_GROUP.into(),
+    }
+}
+
+fn get_group_title(group: &str) -> String {
+    if group == OTHER_GROUP {
+        group.into()
+    } else {
+        format!("{group}.x")
+    }
+}
+
+fn format_date(timestamp: u128) -> String {
+    create_datetime(timestamp)
+        .map(|at| at.format("%x").to_string())
+        .unwrap_or_default()
+}
+
+fn create_metadata_labels(meta: &VersionMetadata, today: &str) -> Vec<String> {
+    let mut labels = vec![];
+
+    if let Some(date) = &meta.release_date {
+        labels.push(format!("released {date}"));
+    }
+
+    if let Some(lts) = &meta.lts {
+        labels.push(format!("LTS {lts}"));
+    }
+
+    if let Some(date) = &meta.eol_date {
+        labels.push(if meta.is_eol_at(today) {
+            format!("end-of-life since {date}")
+        } else {
+            format!("end-of-life on {date}")
+        });
+    }
+
+    if meta.yanked {
+        labels.push("yanked".into());
+    }
+
+    if let Some(url) = &meta.advisory_url {
+        labels.push(format!("advisory {url}"));
+    }
+
+    labels
+}
+
+fn render_item(item: &VersionItem, today: &str) -> AnyElement<'static> {
+    let mut labels = vec![];
+
+    if let Some(timestamp) = item.installed_at {
+        labels.push(format!("installed {}", format_date(timestamp)));
+    }
+
+    if let Some(timestamp) = item.last_used_at {
+        labels.push(format!("last used {}", format_date(timestamp)));
+    }
+
+    if item.locked {
+        labels.push("locked".into());
+    }
+
+    if let Some(meta) = &item.metadata {
+        labels.extend(create_metadata_labels(meta, today));
+    }
+
+    let content = if labels.is_empty() {
+        item.version.to_string()
+    } else {
+        format!(
+            "<shell>{}</shell> <muted>-</muted> <mutedlight>{}</mutedlight>",
+            item.version,
+            labels.join(", ")
+        )
+    };
+
+    element! {
+        View {
+            StyledText(content)
+        }
+    }
+    .into_any()
+}
+
+fn render_table(
+    session: &ProtoSession,
+    versions: &[VersionItem],
+    group_by: Option<VersionsGroupBy>,
+    today: &str,
+) -> miette::Result<()> {
+    let mut headers = vec![];
+
+    if let Some(group_by) = group_by {
+        headers.push(TableHeader::new(
+            match group_by {
+                VersionsGroupBy::Major => "Major",
+                VersionsGroupBy::Minor => "Minor",
+            },
+            Size::Length(8),
+        ));
+    }
+
+    headers.extend([
+        TableHeader::new("Version", Size::Length(16)),
+        TableHeader::new("Installed", Size::Length(12)),
+        TableHeader::new("Last used", Size::Length(12)),
+        TableHeader::new("Locked", Size::Length(8)),
+        TableHeader::new("Notes", Size::Auto),
+    ]);
+
+    let na = || "<mutedlight>N/A</mutedlight>".to_owned();
+
+    session.console.table(
+        headers,
+        versions
+            .iter()
+            .map(|item| {
+                let mut row = vec![];
+
+                if let Some(group) = &item.group {
+                    row.push(format!("<label>{group}</label>"));
+                }
+
+                row.extend([
+                    format!("<hash>{}</hash>", item.version),
+                    item.installed_at.map(format_date).unwrap_or_else(na),
+                    item.last_used_at.map(format_date).unwrap_or_else(na),
+                    if item.locked {
+                        "<success>yes</success>".into()
+                    } else {
+                        "<mutedlight>no</mutedlight>".into()
+                    },
+                    item.metadata
+                        .as_ref()
+                        .map(|meta| create_metadata_labels(meta, today).join(", "))
+                        .unwrap_or_default(),
+                ]);
+
+                row
+            })
+            .collect(),
+    )?;
+
+    Ok(())
+}
+
 #[instrument(skip(session))]
 pub async fn versions(session: ProtoSession, args: VersionsArgs) -> SessionResult {
     let tool = session
@@ -86,15 +282,27 @@ pub async fn versions(session: ProtoSession, args: VersionsArgs) -> SessionResul
                 None
             } else {
                 Some(VersionItem {
+                    group: args.group_by.map(|group_by| get_group(version, group_by)),
+                    installed: installed_at.is_some(),
                     installed_at,
+                    last_used_at: if installed_at.is_some() {
+                        tool.inventory
+                            .create_product(version)
+                            .load_used_at()
+                            .ok()
+                            .flatten()
+                    } else {
+                        None
+                    },
                     locked: locked_versions.contains(version),
+                    metadata: tool.remote_metadata.get(version).cloned(),
                     version: version.to_owned(),
                 })
             }
         })
         .collect::<Vec<_>>();
 
-    if let Some(filter) = args.filter {
+    for filter in [args.filter, args.since].into_iter().flatten() {
         versions.retain(|item| {
             item.version
                 .as_version()
@@ -102,12 +310,41 @@ pub async fn versions(session: ProtoSession, args: VersionsArgs) -> SessionResul
         });
     }
 
-    if session.is_json_format() {
-        session.console.write_json_for_format(VersionsOutput {
-            versions,
-            local_aliases: tool.local_aliases,
-            remote_aliases: tool.remote_aliases,
-        })?;
+    match args.format {
+        Some(VersionsFormat::Json) => {
+            session.console.write_json_pretty(VersionsOutput {
+                versions,
+                local_aliases: tool.local_aliases,
+                remote_aliases: tool.remote_aliases,
+            })?;
+
+            return Ok(None);
+        }
+        // One version per line, so that entries can be streamed
+        Some(VersionsFormat::Ndjson) => {
+            for item in versions {
+                session.console.write_json(item)?;
+            }
+
+            return Ok(None);
+        }
+        Some(VersionsFormat::Table) => {}
+        None if session.is_json_format() => {
+            session.console.write_json_for_format(VersionsOutput {
+                versions,
+                local_aliases: tool.local_aliases,
+                remote_aliases: tool.remote_aliases,
+            })?;
+
+            return Ok(None);
+        }
+        None => {}
+    };
+
+    let today = get_today_date();
+
+    if args.format == Some(VersionsFormat::Table) {
+        render_table(&session, &versions, args.group_by, &today)?;
 
         return Ok(None);
     }
@@ -119,40 +356,29 @@ pub async fn versions(session: ProtoSession, args: VersionsArgs) -> SessionResul
         aliases.extend(&tool.local_aliases);
     }
 
-    session.console.render(element! {
-        Container {
-            #(versions.into_iter().map(|item| {
-                let mut labels = vec![];
-
-                if let Some(timestamp) = item.installed_at {
-                    labels.push(format!(
-                        "installed {}",
-                        create_datetime(timestamp).unwrap_or_default().format("%x")
-                    ));
-                }
+    // Preserve the order of versions while grouping
+    let mut groups = IndexMap::<Option<&String>, Vec<&VersionItem>>::default();
 
-                if item.locked {
-                    labels.push("locked".into());
-                }
+    for item in &versions {
+        groups.entry(item.group.as_ref()).or_default().push(item);
+    }
 
-                element! {
-                    View {
-                        #(if labels.is_empty() {
-                            element! {
-                                StyledText(content: item.version.to_string())
-                            }
-                        } else {
-                            element! {
-                                StyledText(
-                                    content: format!(
-                                        "<shell>{}</shell> <muted>-</muted> <mutedlight>{}</mutedlight>",
-                                        item.version,
-                                        labels.join(", ")
-                                    ),
-                                )
-                            }
-                        })
+    session.console.render(element! {
+        Container {
+            #(groups.into_iter().map(|(group, items)| {
+                match group {
+                    Some(group) => element! {
+                        Section(title: get_group_title(group)) {
+                            #(items.into_iter().map(|item| render_item(item, &today)))
+                        }
+                    }
+                    .into_any(),
+                    None => element! {
+                        View(flex_direction: FlexDirection::Column) {
+                            #(items.into_iter().map(|item| render_item(item, &today)))
+                        }
                     }
+                    .into_any(),
                 }
             }))
 
@@ -87,6 +87,34 @@ pub enum ProtoCliError {
     #[error("Cannot map an alias to itself.")]
     AliasNoMatchingToVersion,
 
+    #[diagnostic(code(proto::commands::alias::pin_missing))]
+    #[error(
+        "Alias expression {} references the pinned version of {}, but it has not been pinned.",
+        .expression.style(Style::Symbol),
+        .tool.style(Style::Id),
+    )]
+    AliasPinMissing { expression: String, tool: String },
+
+    #[diagnostic(code(proto::commands::alias::pin_self))]
+    #[error(
+        "Alias expression {} cannot reference the pinned version of the same tool.",
+        .expression.style(Style::Symbol),
+    )]
+    AliasPinSelf { expression: String },
+
+    #[diagnostic(code(proto::commands::alias::pin_unsupported))]
+    #[error(
+        "Alias expression {} requires a pinned version with a major (and minor) number, but {} is pinned to {}.",
+        .expression.style(Style::Symbol),
+        .tool.style(Style::Id),
+        .version.style(Style::Hash),
+    )]
+    AliasPinUnsupported {
+        expression: String,
+        tool: String,
+        version: String,
+    },
+
     // EXEC
     #[diagnostic(code(proto::commands::exec::missing_command))]
     #[error(
@@ -175,11 +203,65 @@ pub enum ProtoCliError {
     )]
     ShellPowerShellNotSupported,
 
+    // STORE
+    #[diagnostic(code(proto::commands::store::move_copy_mismatch))]
+    #[error(
+        "Unable to move the store, as {} was not copied to the destination intact. The original store has been kept.",
+        .path.style(Style::Path),
+    )]
+    StoreMoveCopyMismatch { path: PathBuf },
+
+    #[diagnostic(code(proto::commands::store::move_missing))]
+    #[error("Unable to move the store, as {} does not exist.", .path.style(Style::Path))]
+    StoreMoveMissing { path: PathBuf },
+
+    #[diagnostic(code(proto::commands::store::move_nested))]
+    #[error(
+        "Unable to move the store from {} to {}, as the destination is within the store.",
+        .from.style(Style::Path),
+        .to.style(Style::Path),
+    )]
+    StoreMoveNested { from: PathBuf, to: PathBuf },
+
+    #[diagnostic(code(proto::commands::store::move_not_empty))]
+    #[error(
+        "Unable to move the store, as the destination {} already exists and is not empty.",
+        .path.style(Style::Path),
+    )]
+    StoreMoveNotEmpty { path: PathBuf },
+
+    #[diagnostic(code(proto::commands::store::move_same))]
+    #[error("The store is already located at {}.", .path.style(Style::Path))]
+    StoreMoveSame { path: PathBuf },
+
     // UPGRADE
+    #[diagnostic(
+        code(proto::commands::upgrade::unsupported_version),
+        help = "The supported versions are configured with proto-version in .prototools."
+    )]
+    #[error(
+        "Unable to change proto to version {}, as the config {} requires proto {}.",
+        .version.style(Style::Hash),
+        .path.style(Style::Path),
+        .requirement.style(Style::Hash),
+    )]
+    UpgradeUnsupportedVersion {
+        path: PathBuf,
+        requirement: String,
+        version: String,
+    },
+
     #[diagnostic(code(proto::commands::upgrade::failed))]
     #[error("Failed to upgrade proto, {} binary could not be located after download!", .bin.style(Style::Shell))]
     UpgradeFailed { bin: String },
 
+    #[diagnostic(code(proto::commands::upgrade::no_rollback))]
+    #[error(
+        "Unable to rollback proto, as there are no previous versions in {}.",
+        .dir.style(Style::Path),
+    )]
+    UpgradeNoRollback { dir: PathBuf },
+
     #[diagnostic(code(proto::commands::upgrade::offline))]
     #[error("Upgrading proto requires an internet connection!")]
     UpgradeRequiresInternet,
@@ -1,9 +1,10 @@
 use crate::error::ProtoCliError;
-use proto_core::Version;
+use proto_core::{UpgradeChannel, Version, format_iso_date};
 use starbase_console::ui::{ConsoleTheme, Style, style_to_color};
 use starbase_styles::color;
 use starbase_utils::json::JsonValue;
 use std::env;
+use std::time::SystemTime;
 use tracing::debug;
 
 pub fn create_console_theme() -> ConsoleTheme {
@@ -79,6 +80,48 @@ pub async fn fetch_latest_version() -> Result<Version, ProtoCliError> {
     Ok(Version::parse(&version).unwrap())
 }
 
+async fn inner_fetch_latest_canary_version() -> reqwest::Result<Option<Version>> {
+    let response =
+        fetch_from_github("https://api.github.com/repos/moonrepo/proto/releases?per_page=30")
+            .await?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    let releases: Vec<JsonValue> = response.json().await?;
+
+    // Canary releases are published as pre-releases, so find the
+    // highest version across both pre-releases and stable releases
+    let version = releases
+        .iter()
+        .filter(|release| !release.get("draft").is_some_and(|draft| draft == true))
+        .filter_map(|release| release.get("tag_name").and_then(|tag| tag.as_str()))
+        .filter_map(|tag| Version::parse(tag.trim_start_matches('v')).ok())
+        .max();
+
+    Ok(version)
+}
+
+pub async fn fetch_latest_version_for_channel(
+    channel: UpgradeChannel,
+) -> Result<Version, ProtoCliError> {
+    let UpgradeChannel::Canary = channel else {
+        return fetch_latest_version().await;
+    };
+
+    let Some(version) = inner_fetch_latest_canary_version().await? else {
+        return Err(ProtoCliError::FailedToFetchVersion);
+    };
+
+    debug!(
+        "Found latest canary version {}",
+        color::hash(version.to_string())
+    );
+
+    Ok(version)
+}
+
 pub fn join_list(mut list: Vec<String>) -> String {
     match list.len() {
         0 => String::new(),
@@ -90,3 +133,9 @@ pub fn join_list(mut list: Vec<String>) -> String {
         }
     }
 }
+
+/// Return the current UTC date in ISO 8601 format (`YYYY-MM-DD`),
+/// for comparing against release metadata dates.
+pub fn get_today_date() -> String {
+    format_iso_date(SystemTime::now())
+}
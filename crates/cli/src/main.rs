@@ -11,7 +11,7 @@ mod telemetry;
 mod utils;
 mod workflows;
 
-use app::{App as CLI, Commands, DebugCommands, PluginCommands, StdoutOwner};
+use app::{App as CLI, Commands, DebugCommands, PluginCommands, StdoutOwner, StoreCommands};
 use clap::Parser;
 use proto_core::reporter::ReporterFormat;
 use session::ProtoSession;
@@ -99,6 +99,7 @@ async fn async_main() -> MainResult {
                     DebugCommands::Env(args) => commands::debug::env(session, args).await,
                 },
                 Commands::Diagnose(args) => commands::diagnose(session, args).await,
+                Commands::Du(args) => commands::du(session, args).await,
                 Commands::Exec(args) => commands::exec(session, args).await,
                 Commands::Install(args) => commands::install(session, args).await,
                 Commands::Mcp(args) => commands::mcp(session, args).await,
@@ -117,10 +118,14 @@ async fn async_main() -> MainResult {
                 Commands::Setup(args) => commands::setup(session, args).await,
                 Commands::Shell(args) => commands::shell(session, args).await,
                 Commands::Status(args) => commands::status(session, args).await,
+                Commands::Store { command } => match command {
+                    StoreCommands::Move(args) => commands::store::move_store(session, args).await,
+                },
                 Commands::Unalias(args) => commands::unalias(session, args).await,
                 Commands::Uninstall(args) => commands::uninstall(session, args).await,
                 Commands::Unpin(args) => commands::unpin(session, args).await,
                 Commands::Upgrade(args) => commands::upgrade(session, args).await,
+                Commands::Verify(args) => commands::verify(session, args).await,
                 Commands::Versions(args) => commands::versions(session, args).await,
             }
         })
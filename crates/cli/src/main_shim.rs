@@ -2,15 +2,18 @@
 // so these imports primarily use std, and avoid fat crates.
 
 use anyhow::{Result, anyhow};
-use proto_shim::{exec_command_and_replace, locate_proto_exe};
+use proto_shim::{
+    exec_command_and_replace, find_changed_cache_input, get_cache_file_name, locate_proto_exe,
+};
 use rust_json::{JsonElem as Json, json_parse};
 use starbase_utils::envx;
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
 static DEBUG: OnceLock<bool> = OnceLock::new();
@@ -123,6 +126,15 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         }
     }
 
+    // Use the precomputed resolution if it exists and is still fresh
+    if let Some(command) =
+        create_cached_command(&proto_home_dir, shim_name, &shim, &passthrough_args)
+    {
+        debug!("Created tool command from cache");
+
+        return Ok(command);
+    }
+
     // Create the command and handle alternate logic
     let proto_bin = locate_proto_exe("proto").unwrap_or_else(|| "proto".into());
 
@@ -180,6 +192,14 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
         command.args(passthrough_args);
     }
 
+    inherit_env_vars(&mut command, &shim);
+
+    debug!("Created proto command");
+
+    Ok(command)
+}
+
+fn inherit_env_vars(command: &mut Command, shim: &Json) {
     if let Json::Object(env_vars) = &shim["env_vars"] {
         debug!("Inheriting config `env_vars`");
 
@@ -189,10 +209,176 @@ fn create_command(args: Vec<OsString>, shim_name: &str) -> Result<Command> {
             }
         }
     }
+}
 
-    debug!("Created proto command");
+fn load_cache_entry(proto_home_dir: &Path, shim_name: &str) -> Option<Json> {
+    if env::var("PROTO_SHIM_CACHE").is_ok_and(|var| var == "0" || var == "false") {
+        debug!("Shim cache disabled with `PROTO_SHIM_CACHE`");
 
-    Ok(command)
+        return None;
+    }
+
+    let cache_path = proto_home_dir
+        .join("cache")
+        .join("shims")
+        .join(get_cache_file_name(&env::current_dir().ok()?));
+
+    if !cache_path.exists() {
+        debug!("No shim cache for the current directory");
+
+        return None;
+    }
+
+    debug!("Loading shim cache: {cache_path:?}");
+
+    let file = fs::read_to_string(cache_path).ok()?;
+
+    match json_parse(&file) {
+        Ok(Json::Object(mut entries)) => entries.remove(shim_name),
+        _ => None,
+    }
+}
+
+fn is_cache_entry_fresh(entry: &Json, passthrough_args: &[OsString]) -> bool {
+    let Json::Str(exe) = &entry["exe"] else {
+        return false;
+    };
+
+    let mut files = vec![];
+    let mut vars = vec![];
+
+    if let Json::Object(map) = &entry["files"] {
+        for (path, time) in map {
+            files.push((
+                Path::new(path),
+                match time {
+                    Json::Number(time) => Some(*time as u64),
+                    Json::Null => None,
+                    _ => return false,
+                },
+            ));
+        }
+    }
+
+    if let Json::Object(map) = &entry["vars"] {
+        for (name, value) in map {
+            vars.push((
+                name.as_str(),
+                match value {
+                    Json::Str(value) => Some(value.as_str()),
+                    Json::Null => None,
+                    _ => return false,
+                },
+            ));
+        }
+    }
+
+    if let Some(change) = find_changed_cache_input(Path::new(exe), files, vars) {
+        debug!("Cache is stale, {change}");
+
+        return false;
+    }
+
+    // Self-upgrades must go through proto, so that they can be blocked
+    if let Json::Array(groups) = &entry["self_upgrade_args"] {
+        for group in groups {
+            if let Json::Array(group) = group
+                && !group.is_empty()
+                && group.iter().enumerate().all(|(index, arg)| {
+                    matches!(arg, Json::Str(arg) if passthrough_args.get(index).is_some_and(|passed| passed == arg.as_str()))
+                })
+            {
+                debug!("Not using cache, arguments trigger a self-upgrade");
+
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn extract_strings(value: &Json) -> Vec<String> {
+    let mut list = vec![];
+
+    if let Json::Array(items) = value {
+        for item in items {
+            if let Json::Str(item) = item {
+                list.push(item.to_owned());
+            }
+        }
+    }
+
+    list
+}
+
+fn create_cached_command(
+    proto_home_dir: &Path,
+    shim_name: &str,
+    shim: &Json,
+    passthrough_args: &[OsString],
+) -> Option<Command> {
+    let entry = load_cache_entry(proto_home_dir, shim_name)?;
+
+    if !is_cache_entry_fresh(&entry, passthrough_args) {
+        return None;
+    }
+
+    let Json::Str(exe) = &entry["exe"] else {
+        return None;
+    };
+
+    let exe = PathBuf::from(exe);
+
+    debug!("Using cached executable: {exe:?}");
+
+    let mut command = Command::new(exe);
+    command.args(extract_strings(&entry["before_args"]));
+    command.args(passthrough_args);
+    command.args(extract_strings(&entry["after_args"]));
+
+    inherit_env_vars(&mut command, shim);
+
+    if let Json::Object(env_vars) = &entry["env"] {
+        for (env, value) in env_vars {
+            match value {
+                Json::Str(var) => command.env(env, var),
+                _ => command.env_remove(env),
+            };
+        }
+    }
+
+    let paths = extract_strings(&entry["paths"]);
+
+    if !paths.is_empty() {
+        let mut list = paths.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        if let Some(path) = env::var_os("PATH") {
+            list.extend(env::split_paths(&path));
+        }
+
+        if let Ok(path) = env::join_paths(list) {
+            command.env("PATH", path);
+        }
+    }
+
+    // Mark the tools as used so that auto-clean doesn't remove them!
+    if env::var("PROTO_SKIP_USED_AT").is_err() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+
+        for dir in extract_strings(&entry["products"]) {
+            let dir = PathBuf::from(dir);
+
+            if dir.is_dir() {
+                let _ = fs::write(dir.join(".last-used"), now.to_string());
+            }
+        }
+    }
+
+    Some(command)
 }
 
 pub fn main() -> Result<()> {
@@ -181,6 +181,7 @@ impl ProtoMcp {
                             None
                         },
                         quiet: true,
+                        require_verified: false,
                         skip_prompts: true,
                         strategy: None,
                     },
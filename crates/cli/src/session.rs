@@ -364,7 +364,10 @@ impl AppSession for ProtoSession {
     }
 
     async fn analyze(&mut self) -> AppResult<Self::Error> {
-        load_proto_configs(&self.env)?;
+        if let Err(error) = load_proto_configs(&self.env) {
+            // Boxed to keep the startup future small, as it installs proto
+            Box::pin(switch_proto_version(self, error)).await?;
+        }
 
         Ok(None)
     }
@@ -400,6 +403,7 @@ impl AppSession for ProtoSession {
                 &CleanArgs {
                     target: CleanTarget::All,
                     days: 30, // Doesn't inherit clap defaults
+                    ..Default::default()
                 },
             )
             .await?;
@@ -1,9 +1,19 @@
 use crate::app::{App as CLI, Commands};
+use crate::commands::{InstallArgs, install_one};
 use crate::helpers::fetch_latest_version;
-use proto_core::{ConfigMode, ProtoEnvironment, Version, is_offline, now, reporter::ProtoConsole};
-use proto_shim::get_exe_file_name;
+use crate::session::{ProtoSession, SessionResult};
+use miette::IntoDiagnostic;
+use proto_core::flow::resolve::Resolver;
+use proto_core::{
+    ConfigMode, IGNORE_PROTO_VERSION_VAR, Id, PROTO_PLUGIN_KEY, ProtoConfigError, ProtoEnvironment,
+    ToolContext, ToolSpec, Version, get_proto_version, is_offline, now, reporter::ProtoConsole,
+};
+use proto_shim::{exec_command_and_replace, get_exe_file_name};
 use starbase_utils::fs;
 use std::env;
+use std::pin::Pin;
+use std::process::Command;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, instrument};
 
@@ -22,6 +32,12 @@ pub fn detect_proto_env(cli: &CLI) -> miette::Result<ProtoEnvironment> {
     let mut env = ProtoEnvironment::new()?;
 
     env.otel_enabled = cli.otel;
+
+    // Upgrading must always be possible, regardless of
+    // the proto version that the current configs require
+    if matches!(cli.command, Commands::Upgrade(_)) {
+        env.check_proto_version = false;
+    }
     env.config_mode = cli.config_mode.unwrap_or(match cli.command {
         Commands::Activate(_)
         | Commands::Install(_)
@@ -62,6 +78,105 @@ pub fn load_proto_configs(env: &ProtoEnvironment) -> miette::Result<()> {
     Ok(())
 }
 
+const VERSION_SWITCH_GUARD_VAR: &str = "PROTO_INTERNAL_VERSION_SWITCH";
+
+#[instrument(skip(session))]
+pub async fn switch_proto_version(
+    session: &mut ProtoSession,
+    error: miette::Report,
+) -> miette::Result<()> {
+    // Only switch when a config requires a different proto version, and
+    // only once, as the switched version should satisfy the requirement
+    if !matches!(
+        error.downcast_ref::<ProtoConfigError>(),
+        Some(ProtoConfigError::UnsupportedProtoVersion { .. })
+    ) || env::var(VERSION_SWITCH_GUARD_VAR).is_ok()
+    {
+        return Err(error);
+    }
+
+    // Reload the configs without the requirement, so that
+    // settings are available and the proto tool can be loaded
+    Arc::make_mut(&mut session.env).check_proto_version = false;
+
+    let manager = session.env.load_file_manager()?;
+    let current_version = get_proto_version();
+
+    let Some(requirement) = manager
+        .get_config_files()
+        .into_iter()
+        .find(|file| file.check_proto_version(current_version).is_err())
+        .and_then(|file| file.config.proto_version.clone())
+    else {
+        return Err(error);
+    };
+
+    if !session.env.load_config()?.settings.auto_install {
+        return Err(error);
+    }
+
+    let tool = session
+        .load_tool(&ToolContext::new(Id::raw(PROTO_PLUGIN_KEY)))
+        .await?;
+    let mut spec = ToolSpec::new(requirement);
+
+    Resolver::resolve(&tool, &mut spec, true).await?;
+
+    let resolved_version = spec.get_resolved_version();
+
+    debug!(
+        current_version = current_version.to_string(),
+        required_version = spec.req.to_string(),
+        "Current proto version does not satisfy the configured requirement, switching to {}",
+        resolved_version
+    );
+
+    if !tool.is_installed(&spec) {
+        session.console.err.write_line(format!(
+            "Auto-install is enabled, attempting to install proto {resolved_version}",
+        ))?;
+
+        // Box the install to keep the session's startup future shallow,
+        // as its nested types would otherwise exceed the recursion limit
+        let install: Pin<Box<dyn Future<Output = SessionResult> + Send>> = Box::pin(install_one(
+            session.clone(),
+            InstallArgs {
+                internal: true,
+                spec: Some(ToolSpec {
+                    req: resolved_version.to_unresolved_spec(),
+                    version: Some(resolved_version.clone()),
+                    version_locked: None,
+                    version_held_back: None,
+                    resolve_from_manifest: false,
+                    resolve_from_lockfile: false,
+                    update_lockfile: false,
+                }),
+                ..Default::default()
+            },
+            tool.context.clone(),
+        ));
+
+        install.await?;
+    }
+
+    let exe_path = session
+        .env
+        .store
+        .inventory_dir
+        .join(PROTO_PLUGIN_KEY)
+        .join(resolved_version.to_string())
+        .join(get_exe_file_name("proto"));
+
+    let mut command = Command::new(exe_path);
+    command.args(env::args_os().skip(1));
+    // The switched version satisfies the requirement, so don't check it again
+    command.env(IGNORE_PROTO_VERSION_VAR, "true");
+    command.env(VERSION_SWITCH_GUARD_VAR, resolved_version.to_string());
+
+    // Must be the last line!
+    exec_command_and_replace(command).into_diagnostic()
+}
+
 // EXECUTE
 
 #[instrument]
@@ -4,6 +4,7 @@ use proto_core::flow::resolve::{ProtoResolveError, Resolver};
 use proto_core::{
     ProtoConfig, ProtoToolConfig, Tool, ToolContext, ToolSpec, UnresolvedVersionSpec, VersionSpec,
 };
+use proto_pdk_api::VersionMetadata;
 use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
@@ -19,6 +20,7 @@ pub struct ToolRecord {
     pub local_aliases: BTreeMap<String, ToolSpec>,
     pub remote_aliases: BTreeMap<String, ToolSpec>,
     pub remote_versions: Vec<VersionSpec>,
+    pub remote_metadata: BTreeMap<VersionSpec, VersionMetadata>,
 }
 
 impl ToolRecord {
@@ -42,6 +44,7 @@ impl ToolRecord {
             remote_aliases: BTreeMap::default(),
             installed_versions: versions,
             remote_versions: vec![],
+            remote_metadata: BTreeMap::default(),
         }
     }
 
@@ -74,6 +77,7 @@ impl ToolRecord {
                 .map(|(k, v)| (k, ToolSpec::new(v))),
         );
         self.remote_versions.extend(resolver.data.versions);
+        self.remote_metadata.extend(resolver.data.metadata);
         self.remote_versions.sort();
 
         Ok(())
@@ -35,6 +35,7 @@ pub struct ExecItem {
     args: Vec<String>,
     env: IndexMap<String, Option<String>>,
     paths: IndexSet<PathBuf>,
+    pre_run_hooked: bool,
 }
 
 impl ExecItem {
@@ -68,6 +69,7 @@ pub struct ExecWorkflow<'app> {
     pub args: Vec<String>,
     pub env: IndexMap<String, Option<String>>,
     pub paths: VecDeque<PathBuf>,
+    pub pre_run_hooked: bool,
     pub tools: Vec<ToolRecord>,
 
     config: &'app ProtoConfig,
@@ -82,12 +84,14 @@ impl<'app> ExecWorkflow<'app> {
             args: vec![],
             env: IndexMap::default(),
             paths: VecDeque::default(),
+            pre_run_hooked: false,
             config,
         }
     }
 
     pub fn collect_item(&mut self, mut item: ExecItem) {
         self.args.extend(item.args);
+        self.pre_run_hooked = self.pre_run_hooked || item.pre_run_hooked;
 
         for (key, value) in item.env {
             self.env.insert(key, value);
@@ -520,7 +524,7 @@ async fn prepare_tool(
             .call_func_with(
                 PluginFunction::ActivateEnvironment,
                 ActivateEnvironmentInput {
-                    context: tool.create_plugin_context(&spec),
+                    context: tool.create_plugin_locate_context(&spec),
                     globals_dir: locations
                         .globals_dir
                         .as_ref()
@@ -544,7 +548,7 @@ async fn prepare_tool(
             .call_func_with(
                 HookFunction::PreRun,
                 RunHook {
-                    context: tool.create_plugin_context(&spec),
+                    context: tool.create_plugin_locate_context(&spec),
                     globals_dir: locations
                         .globals_dir
                         .as_ref()
@@ -555,6 +559,8 @@ async fn prepare_tool(
             )
             .await?;
 
+        item.pre_run_hooked = true;
+
         if let Some(value) = output.args {
             item.add_args(value);
         }
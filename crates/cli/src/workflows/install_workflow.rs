@@ -46,6 +46,7 @@ pub struct InstallWorkflowParams {
     pub passthrough_args: Vec<String>,
     pub pin_to: Option<PinLocation>,
     pub quiet: bool,
+    pub require_verified: bool,
     pub skip_prompts: bool,
     pub strategy: Option<InstallStrategy>,
 }
@@ -336,6 +337,14 @@ impl InstallWorkflow {
             });
         });
 
+        let post_install_hook = InstallHook {
+            context: self.tool.create_plugin_context(spec),
+            forced: params.force,
+            passthrough_args: params.passthrough_args.clone(),
+            pinned: params.pin_to.is_some(),
+            quiet: params.quiet,
+        };
+
         let mut manager = Manager::new(&mut self.tool);
 
         let record = manager
@@ -347,6 +356,8 @@ impl InstallWorkflow {
                     on_phase_change: Some(on_phase_change),
                     force: params.force,
                     log_writer: params.log_writer.clone(),
+                    post_install_hook: Some(post_install_hook),
+                    require_verified: params.require_verified,
                     skip_prompts: params.skip_prompts,
                     // Multiple installs have a shared progress UI, while quiet
                     // callers such as the MCP server must not render one.
@@ -367,27 +378,8 @@ impl InstallWorkflow {
         spec: &ToolSpec,
         params: &InstallWorkflowParams,
     ) -> Result<(), ProtoCliError> {
-        let tool = &self.tool;
-
-        params.log_writer.as_ref().inspect(|log| {
-            log.add_header("Running post-install hooks");
-        });
-
-        if tool.plugin.has_func(HookFunction::PostInstall).await {
-            tool.plugin
-                .call_func_without_output(
-                    HookFunction::PostInstall,
-                    InstallHook {
-                        context: tool.create_plugin_context(spec),
-                        forced: params.force,
-                        passthrough_args: params.passthrough_args.clone(),
-                        pinned: params.pin_to.is_some(),
-                        quiet: params.quiet,
-                    },
-                )
-                .await?;
-        }
-
+        // Post-install hooks are ran by the installer, before the
+        // installed files are fingerprinted and made read-only
         self.update_shell(spec, params).await?;
 
         Ok(())
@@ -525,6 +517,13 @@ impl InstallWorkflow {
             message.push_str(&format!("<mutedlight>({duration})</mutedlight>"));
         }
 
+        // A newer version may have been held back by the minimum release age
+        if let Some(held_back) = &spec.version_held_back {
+            message.push_str(&format!(
+                " <caution>(held back {held_back}, as it was released too recently)</caution>"
+            ));
+        }
+
         self.progress_reporter
             .set_message(message)
             .set_display(ProgressDisplay::Bar)
@@ -19,6 +19,68 @@ mod alias_local {
             .stderr(predicate::str::contains("unknown is not a built-in plugin"));
     }
 
+    #[test]
+    fn errors_for_previous_expression_of_itself() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("alias").arg("protostar").arg("lts").arg("lts-1");
+        });
+
+        assert
+            .inner
+            .stderr(predicate::str::contains("Cannot map an alias to itself."));
+    }
+
+    #[test]
+    fn errors_for_pin_expression_of_same_tool() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("alias")
+                .arg("protostar")
+                .arg("example")
+                .arg("pin/protostar/major");
+        });
+
+        assert.inner.stderr(predicate::str::contains(
+            "cannot reference the pinned version of the same tool",
+        ));
+    }
+
+    #[test]
+    fn errors_for_pin_expression_of_unpinned_tool() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("alias")
+                .arg("protostar")
+                .arg("example")
+                .arg("pin/moonbase/major");
+        });
+
+        assert
+            .inner
+            .stderr(predicate::str::contains("but it has not been pinned"));
+    }
+
+    #[test]
+    fn errors_for_pin_expression_of_alias_pin() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(".prototools", r#"moonbase = "latest""#);
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("alias")
+                .arg("protostar")
+                .arg("example")
+                .arg("pin/moonbase/major");
+        });
+
+        assert
+            .inner
+            .stderr(predicate::str::contains("but moonbase is pinned to latest"));
+    }
+
     #[test]
     fn updates_config_file() {
         let sandbox = create_empty_proto_sandbox();
@@ -1,4 +1,5 @@
 use proto_core::test_utils::*;
+use starbase_sandbox::predicates::prelude::*;
 use std::fs;
 use std::time::{Duration, SystemTime};
 
@@ -225,4 +226,247 @@ mod clean {
         assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
         assert!(sandbox.path().join(".proto/tools/protostar/3.0.0").exists());
     }
+
+    #[test]
+    fn keeps_versions_referenced_by_projects() {
+        let sandbox = create_empty_proto_sandbox();
+
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install")
+                        .arg("protostar")
+                        .arg(version)
+                        .timeout(Duration::from_mins(3));
+                })
+                .success();
+        }
+
+        let stale_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            - (86400 * 2 * 1000);
+
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox.create_file(
+                format!(".proto/tools/protostar/{version}/.last-used"),
+                stale_time.to_string(),
+            );
+        }
+
+        // Register a project that pins an older version
+        sandbox.create_file("project/.prototools", "protostar = \"~1\"");
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("status")
+                    .current_dir(sandbox.path().join("project"));
+            })
+            .success();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("clean")
+                .arg("--yes")
+                .arg("tools")
+                .arg("--days")
+                .arg("1")
+                .arg("--json")
+                .timeout(Duration::from_mins(3));
+        });
+
+        let result: serde_json::Value =
+            serde_json::from_slice(&assert.inner.get_output().stdout).unwrap();
+
+        assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+        assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+        assert!(
+            result["kept_tools"][0]["reason"]
+                .as_str()
+                .unwrap()
+                .contains("referenced as ~1")
+        );
+    }
+
+    #[test]
+    fn keeps_versions_pinned_in_user_config() {
+        let sandbox = create_empty_proto_sandbox();
+
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install")
+                        .arg("protostar")
+                        .arg(version)
+                        .timeout(Duration::from_mins(3));
+                })
+                .success();
+        }
+
+        let stale_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            - (86400 * 2 * 1000);
+
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox.create_file(
+                format!(".proto/tools/protostar/{version}/.last-used"),
+                stale_time.to_string(),
+            );
+        }
+
+        sandbox.create_file(".home/.prototools", "protostar = \"1.0.0\"");
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("clean")
+                    .arg("--yes")
+                    .arg("tools")
+                    .arg("--days")
+                    .arg("1")
+                    .timeout(Duration::from_mins(3));
+            })
+            .success();
+
+        assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+        assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+
+    fn install_stale_versions(sandbox: &ProtoSandbox) {
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install")
+                        .arg("protostar")
+                        .arg(version)
+                        .timeout(Duration::from_secs(180));
+                })
+                .success();
+        }
+
+        let stale_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            - (86400 * 2 * 1000);
+
+        for version in ["1.0.0", "2.0.0"] {
+            sandbox.create_file(
+                format!(".proto/tools/protostar/{version}/.last-used"),
+                stale_time.to_string(),
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_versions_referenced_by_cached_remote_aliases() {
+        let sandbox = create_empty_proto_sandbox();
+
+        install_stale_versions(&sandbox);
+
+        sandbox.create_file(
+            ".proto/tools/protostar/remote-versions.json",
+            r#"{"aliases":{"lts":"~1"},"versions":["1.0.0","2.0.0"]}"#,
+        );
+        sandbox.create_file(".home/.prototools", "protostar = \"lts\"");
+
+        run_clean(&sandbox, "tools");
+
+        assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+        assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+
+    #[test]
+    fn keeps_all_stable_versions_for_unresolved_aliases() {
+        let sandbox = create_empty_proto_sandbox();
+
+        install_stale_versions(&sandbox);
+
+        let _ = fs::remove_file(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/remote-versions.json"),
+        );
+        sandbox.create_file(".home/.prototools", "protostar = \"lts\"");
+
+        run_clean(&sandbox, "tools");
+
+        assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+        assert!(sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+}
+
+mod clean_dedupe {
+    use super::*;
+    #[cfg(unix)]
+    use proto_core::{ToolManifest, ToolManifestVersion, VersionSpec};
+
+    #[cfg(unix)]
+    fn create_install(sandbox: &ProtoSandbox, version: &str) {
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+
+        manifest.add_version(
+            &VersionSpec::parse(version).unwrap(),
+            ToolManifestVersion::default(),
+        );
+        manifest.save().unwrap();
+
+        sandbox.create_file(
+            format!(".proto/tools/protostar/{version}/lib/shared.txt"),
+            "x".repeat(2048),
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_identical_files_across_versions() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+        create_install(&sandbox, "2.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("clean")
+                .arg("--yes")
+                .arg("cache")
+                .arg("--dedupe")
+                .arg("--json");
+        });
+
+        let result: serde_json::Value =
+            serde_json::from_slice(&assert.inner.get_output().stdout).unwrap();
+
+        assert_eq!(result["deduped"]["files"], 1);
+        assert_eq!(result["deduped"]["size"], 2048);
+
+        // Each version must keep working on its own
+        fs::remove_dir_all(sandbox.path().join(".proto/tools/protostar/1.0.0")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(
+                sandbox
+                    .path()
+                    .join(".proto/tools/protostar/2.0.0/lib/shared.txt")
+            )
+            .unwrap(),
+            "x".repeat(2048)
+        );
+    }
+
+    #[test]
+    fn errors_when_hardlinks_used_with_read_only_tools() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox.create_file(".prototools", "[settings]\nread-only-tools = true\n");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("clean").arg("--yes").arg("cache").arg("--dedupe");
+        });
+
+        assert.failure().stderr(predicate::str::contains(
+            "Deduplicating with hard links is not supported when tools are read-only",
+        ));
+    }
 }
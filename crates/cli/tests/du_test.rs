@@ -0,0 +1,169 @@
+use proto_core::test_utils::*;
+use proto_core::{ToolManifest, ToolManifestVersion, VersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+
+mod du {
+    use super::*;
+
+    fn create_inventory(sandbox: &ProtoSandbox) {
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+
+        manifest.versions.insert(
+            VersionSpec::parse("1.0.0").unwrap(),
+            ToolManifestVersion::default(),
+        );
+        manifest.save().unwrap();
+
+        sandbox.create_file(".proto/tools/protostar/1.0.0/bin/protostar", "1234567890");
+        sandbox.create_file(".proto/tools/protostar/2.0.0/bin/protostar", "12345");
+        sandbox.create_file(".proto/plugins/protostar.wasm", "123");
+    }
+
+    fn run_du(sandbox: &ProtoSandbox, prune: bool) -> serde_json::Value {
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("du").arg("--json");
+
+            if prune {
+                cmd.arg("--prune-orphans");
+            }
+        });
+
+        serde_json::from_slice(&assert.inner.get_output().stdout).unwrap()
+    }
+
+    #[test]
+    fn reports_sizes() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        let result = run_du(&sandbox, false);
+        let products = result["tools"][0]["products"].as_array().unwrap();
+
+        assert_eq!(result["tools"][0]["id"], "protostar");
+        assert_eq!(result["plugins"], 3);
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0]["name"], "1.0.0");
+        assert_eq!(products[0]["size"], 10);
+        assert_eq!(products[0]["orphan"], false);
+        assert_eq!(products[1]["name"], "2.0.0");
+        assert_eq!(products[1]["size"], 5);
+        assert_eq!(products[1]["orphan"], true);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_hard_links_once() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        std::fs::hard_link(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/bin/protostar"),
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/bin/protostar-link"),
+        )
+        .unwrap();
+
+        let result = run_du(&sandbox, false);
+
+        assert_eq!(result["tools"][0]["products"][0]["size"], 10);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_files_linked_into_dedupe_store_once() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        sandbox.create_file(".proto/dedupe/ab/abcdef", "1234567890");
+
+        std::fs::remove_file(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/bin/protostar"),
+        )
+        .unwrap();
+        std::fs::hard_link(
+            sandbox.path().join(".proto/dedupe/ab/abcdef"),
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/bin/protostar"),
+        )
+        .unwrap();
+
+        let result = run_du(&sandbox, false);
+
+        assert_eq!(result["tools"][0]["products"][0]["size"], 10);
+        assert_eq!(result["dedupe"], 0);
+    }
+
+    #[test]
+    fn flags_orphans() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("du");
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("orphan"))
+            .stderr(predicate::str::contains(
+                "Found 1 directories that are not tracked",
+            ));
+    }
+
+    #[test]
+    fn prunes_orphans() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        let result = run_du(&sandbox, true);
+
+        assert_eq!(result["tools"][0]["products"][1]["pruned"], true);
+        assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+        assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+
+    #[test]
+    fn doesnt_prune_versions_being_installed() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        let _lock = starbase_utils::fs::lock_directory(sandbox.path().join(format!(
+            ".proto/temp/protostar/{}",
+            starbase_utils::hash::base64::from_bytes("2.0.0")
+        )))
+        .unwrap();
+
+        let result = run_du(&sandbox, true);
+
+        assert_eq!(result["tools"][0]["products"][1]["orphan"], false);
+        assert!(sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+
+    #[test]
+    fn prunes_versions_left_behind_by_failed_installs() {
+        let sandbox = create_empty_proto_sandbox();
+        create_inventory(&sandbox);
+
+        // A failed install leaves the temp directory, but not the lock
+        sandbox.create_file(
+            format!(
+                ".proto/temp/protostar/{}/archive.tar.gz.part",
+                starbase_utils::hash::base64::from_bytes("2.0.0")
+            ),
+            "",
+        );
+
+        let result = run_du(&sandbox, true);
+
+        assert_eq!(result["tools"][0]["products"][1]["orphan"], true);
+        assert!(!sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+}
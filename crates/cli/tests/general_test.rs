@@ -1,4 +1,5 @@
 use proto_core::test_utils::*;
+use starbase_sandbox::predicates::prelude::*;
 
 mod general {
     use super::*;
@@ -98,4 +99,72 @@ mod general {
 
         assert!(output.get("info").is_some());
     }
+
+    mod proto_version {
+        use super::*;
+
+        #[test]
+        fn errors_if_requirement_not_satisfied() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(".prototools", r#"proto-version = ">=1000.0.0""#);
+
+            let assert = sandbox.run_bin(|cmd| {
+                cmd.arg("debug").arg("config");
+            });
+
+            assert
+                .failure()
+                .stderr(predicate::str::contains("requires proto >=1000.0.0"));
+        }
+
+        #[test]
+        fn runs_if_requirement_satisfied() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(".prototools", r#"proto-version = ">=0.1.0""#);
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("debug").arg("config");
+                })
+                .success();
+        }
+
+        #[test]
+        fn doesnt_switch_versions_more_than_once() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+proto-version = ">=1000.0.0"
+
+[settings]
+auto-install = true
+"#,
+            );
+
+            let assert = sandbox.run_bin(|cmd| {
+                cmd.arg("debug")
+                    .arg("config")
+                    .env("PROTO_INTERNAL_VERSION_SWITCH", "1000.0.0");
+            });
+
+            assert
+                .failure()
+                .stderr(predicate::str::contains("requires proto >=1000.0.0"));
+        }
+
+        #[test]
+        fn can_upgrade_if_requirement_not_satisfied() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(".prototools", r#"proto-version = ">=1000.0.0""#);
+
+            let assert = sandbox.run_bin(|cmd| {
+                cmd.arg("upgrade").arg("--rollback");
+            });
+
+            assert
+                .failure()
+                .stderr(predicate::str::contains("there are no previous versions"));
+        }
+    }
 }
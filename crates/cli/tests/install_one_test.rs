@@ -31,6 +31,105 @@ mod install_one {
         );
     }
 
+    #[test]
+    fn print_deps_requires_build() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install").arg("protostar").arg("--print-deps");
+            })
+            .failure()
+            .stderr(predicate::str::contains("--build"));
+    }
+
+    #[test]
+    fn print_deps_errors_if_build_not_supported() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("2.5")
+                    .arg("--build")
+                    .arg("--print-deps");
+            })
+            .failure()
+            .stderr(predicate::str::contains(
+                "Building from source is not supported",
+            ));
+
+        assert!(
+            std::fs::read_dir(sandbox.path().join(".proto/tools/protostar"))
+                .unwrap()
+                .all(|entry| !entry.unwrap().path().is_dir())
+        );
+    }
+
+    #[test]
+    fn print_deps_outputs_shell_commands() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[tools.protostar]
+build-dependencies = ["make", "git"]
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("2.5")
+                    .arg("--build")
+                    .arg("--print-deps")
+                    .arg("--package-manager")
+                    .arg("apt");
+            })
+            .success()
+            .stdout(predicate::eq(
+                "apt update -y\napt install --install-recommends git make -y\n",
+            ));
+
+        assert!(
+            std::fs::read_dir(sandbox.path().join(".proto/tools/protostar"))
+                .unwrap()
+                .all(|entry| !entry.unwrap().path().is_dir())
+        );
+    }
+
+    #[test]
+    fn print_deps_outputs_dockerfile_instruction() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[tools.protostar]
+build-dependencies = ["make", "git"]
+
+[settings.build]
+exclude-packages = ["make"]
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("2.5")
+                    .arg("--build")
+                    .arg("--print-deps")
+                    .arg("--print-deps-format")
+                    .arg("dockerfile")
+                    .arg("--package-manager")
+                    .arg("apk");
+            })
+            .success()
+            .stdout(predicate::eq("RUN apk update \\\n    && apk add git\n"));
+    }
+
     #[test]
     fn installs_without_minor() {
         let sandbox = create_empty_proto_sandbox();
@@ -794,6 +893,338 @@ mod install_one {
         }
     }
 
+    mod mirrors {
+        use super::*;
+        use proto_core::checksum::hash_file_contents_sha256;
+        use proto_core::warpgate::test_utils::TestHttpServer;
+
+        #[test]
+        fn fails_over_to_the_next_mirror() {
+            let sandbox = create_empty_proto_sandbox();
+            let body = b"#!/bin/sh\necho mirrored\n".to_vec();
+
+            sandbox.create_file("protostar", String::from_utf8(body.clone()).unwrap());
+
+            let hash = hash_file_contents_sha256(sandbox.path().join("protostar")).unwrap();
+            let server = TestHttpServer::serve_files([
+                ("/dist/protostar", body),
+                (
+                    "/dist/protostar.sha256",
+                    format!("{hash}  protostar").into_bytes(),
+                ),
+            ]);
+            let url = &server.url;
+
+            sandbox.create_file(
+                ".prototools",
+                format!(
+                    r#"
+[tools.protostar]
+download-url = "{url}/dist/protostar"
+checksum-url = "{url}/dist/protostar.sha256"
+
+[settings.url-mirrors]
+"{url}/dist/(.*)" = ["{url}/broken/$1"]
+"#
+                ),
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            assert!(sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+
+            assert_eq!(
+                server.request_paths(),
+                vec![
+                    "/broken/protostar",
+                    "/dist/protostar",
+                    "/dist/protostar.sha256",
+                ]
+            );
+        }
+
+        #[test]
+        fn doesnt_verify_against_checksum_from_failed_mirror() {
+            let sandbox = create_empty_proto_sandbox();
+            let body = b"#!/bin/sh\necho mirrored\n".to_vec();
+
+            sandbox.create_file("protostar", String::from_utf8(body.clone()).unwrap());
+
+            let hash = hash_file_contents_sha256(sandbox.path().join("protostar")).unwrap();
+            let server = TestHttpServer::serve_files([
+                ("/dist/protostar", body.clone()),
+                (
+                    "/dist/protostar.sha256",
+                    format!("{hash}  protostar").into_bytes(),
+                ),
+                ("/stale/protostar", b"#!/bin/sh\necho stale\n".to_vec()),
+                (
+                    "/stale/protostar.sha256",
+                    format!("{}  protostar", "0".repeat(64)).into_bytes(),
+                ),
+            ]);
+            let url = &server.url;
+
+            sandbox.create_file(
+                ".prototools",
+                format!(
+                    r#"
+[tools.protostar]
+download-url = "{url}/dist/protostar"
+checksum-url = "{url}/dist/protostar.sha256"
+
+[settings.url-mirrors]
+"{url}/dist/(.*)" = ["{url}/stale/$1"]
+"#
+                ),
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            assert_eq!(
+                fs::read(
+                    sandbox
+                        .path()
+                        .join(".proto/tools/protostar/1.0.0/protostar")
+                )
+                .unwrap(),
+                body
+            );
+            assert_eq!(
+                server.request_paths(),
+                vec![
+                    "/stale/protostar",
+                    "/stale/protostar.sha256",
+                    "/dist/protostar",
+                    "/dist/protostar.sha256",
+                ]
+            );
+        }
+    }
+
+    mod resume {
+        use super::*;
+        use proto_core::warpgate::test_utils::{TestHttpResponse, TestHttpServer};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Close the connection halfway through the body of
+        // the first request, and support `Range` requests after
+        fn start_server(body: Vec<u8>) -> TestHttpServer {
+            let count = AtomicUsize::new(0);
+
+            TestHttpServer::start(move |request| {
+                let start = request
+                    .header("range")
+                    .and_then(|value| value.strip_prefix("bytes="))
+                    .and_then(|value| value.strip_suffix('-'))
+                    .map(|value| value.parse::<usize>().unwrap());
+
+                let response = match start {
+                    Some(start) => TestHttpResponse::new(206, &body[start..]),
+                    None => TestHttpResponse::ok(body.clone()),
+                }
+                .header("etag", "\"abc\"");
+
+                if count.fetch_add(1, Ordering::Relaxed) == 0 {
+                    let size = response.body.len() / 2;
+
+                    response.truncate(size)
+                } else {
+                    response
+                }
+            })
+        }
+
+        #[test]
+        fn resumes_partial_download_after_failure() {
+            let sandbox = create_empty_proto_sandbox();
+            let body = "#!/bin/sh\necho resumed\n".repeat(1000).into_bytes();
+            let server = start_server(body.clone());
+            let url = &server.url;
+
+            sandbox.create_file(
+                ".prototools",
+                format!(
+                    r#"
+[tools.protostar]
+download-url = "{url}/dist/protostar"
+"#
+                ),
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .failure();
+
+            let part_files = starbase_utils::fs::read_dir_all(sandbox.path().join(".proto/temp"))
+                .unwrap()
+                .into_iter()
+                .filter(|entry| entry.file_name().to_string_lossy() == "protostar.part")
+                .collect::<Vec<_>>();
+
+            assert_eq!(part_files.len(), 1);
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            assert_eq!(
+                fs::read(
+                    sandbox
+                        .path()
+                        .join(".proto/tools/protostar/1.0.0/protostar")
+                )
+                .unwrap(),
+                body
+            );
+            assert_eq!(
+                server
+                    .requests()
+                    .iter()
+                    .map(|request| request.header("range").unwrap_or_default().to_owned())
+                    .collect::<Vec<_>>(),
+                vec!["".to_owned(), format!("bytes={}-", body.len() / 2)]
+            );
+        }
+    }
+
+    mod build_log {
+        use super::*;
+
+        #[test]
+        fn writes_a_status_for_each_instruction() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.protostar]
+build-dependencies = []
+build-instructions = ["echo first", "echo second", "false", "echo never"]
+
+[settings.build]
+install-system-packages = false
+"#,
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install")
+                        .arg("protostar")
+                        .arg("2.5")
+                        .arg("--build");
+                })
+                .failure();
+
+            let log = std::fs::read_to_string(sandbox.path().join("proto-protostar-install.log"))
+                .unwrap();
+
+            assert_eq!(log.matches("**STATUS**: success").count(), 2);
+            assert_eq!(log.matches("**STATUS**: failed").count(), 1);
+            assert!(log.contains("#### Instruction 3/4: run-command\n\n**STATUS**: failed"));
+            assert!(!log.contains("Child process: `echo never`"));
+        }
+    }
+
+    mod custom_shims {
+        use super::*;
+        use proto_core::layout::ShimRegistry;
+
+        #[test]
+        fn creates_shims_for_tool() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(
+                ".proto/.prototools",
+                r#"
+[shims.star]
+tool = "protostar"
+before-args = ["--before"]
+env = { STAR = "1" }
+
+[shims.other]
+tool = "moonstone"
+"#,
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            assert!(get_shim_path(sandbox.path(), "star").exists());
+            assert!(!get_shim_path(sandbox.path(), "other").exists());
+
+            let registry = ShimRegistry::load_from(sandbox.path().join(".proto/shims")).unwrap();
+            let shim = registry.get("star").unwrap();
+
+            assert_eq!(shim.context, Some(ToolContext::parse("protostar").unwrap()));
+            assert_eq!(shim.before_args, vec!["--before"]);
+            assert_eq!(shim.env_vars.get("STAR").unwrap(), "1");
+            assert!(registry.get("other").is_none());
+        }
+
+        #[test]
+        fn doesnt_overwrite_shims_registered_by_plugins() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(
+                ".proto/.prototools",
+                r#"
+[shims.protostarx]
+tool = "protostar"
+before-args = ["--before"]
+"#,
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            let registry = ShimRegistry::load_from(sandbox.path().join(".proto/shims")).unwrap();
+            let shim = registry.get("protostarx").unwrap();
+
+            assert!(shim.before_args.is_empty());
+            assert_eq!(shim.custom, None);
+        }
+
+        #[test]
+        fn ignores_shims_in_project_configs() {
+            let sandbox = create_empty_proto_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims.star]
+tool = "protostar"
+"#,
+            );
+
+            sandbox
+                .run_bin(|cmd| {
+                    cmd.arg("install").arg("protostar").arg("1.0.0");
+                })
+                .success();
+
+            assert!(!get_shim_path(sandbox.path(), "star").exists());
+
+            let registry = ShimRegistry::load_from(sandbox.path().join(".proto/shims")).unwrap();
+
+            assert!(registry.get("star").is_none());
+        }
+    }
+
     mod requirements {
         use super::*;
 
@@ -130,6 +130,32 @@ mod outdated {
         ]);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn updates_platform_version_instead_of_pinned_version() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+protostar = "1.0.0"
+
+[tools.protostar.platform.linux]
+version = "2.0.0"
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("outdated").arg("--update").arg("--yes");
+            })
+            .success();
+
+        let config = fs::read_to_string(sandbox.path().join(".prototools")).unwrap();
+
+        assert!(config.contains(r#"protostar = "1.0.0""#));
+        assert!(!config.contains(r#"version = "2.0.0""#));
+    }
+
     #[test]
     fn can_update_with_latest_version() {
         let sandbox = create_empty_proto_sandbox();
@@ -166,6 +192,47 @@ mod outdated {
         );
     }
 
+    #[test]
+    fn can_write_summary_file() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(".prototools", r#"protostar = "1.0.0""#);
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("outdated")
+                    .arg("--update")
+                    .arg("--summary")
+                    .arg("summary.md")
+                    .arg("--yes");
+            })
+            .success();
+
+        let summary = fs::read_to_string(sandbox.path().join("summary.md")).unwrap();
+
+        assert!(summary.starts_with("## Tool updates"));
+        assert!(summary.contains("| `protostar` | `1.0.0` |"));
+        assert!(summary.contains("| `.prototools` |"));
+        assert!(
+            summary.contains(
+                "[1.10.15](https://github.com/moonrepo/protostar/releases/tag/v1.10.15) |"
+            )
+        );
+    }
+
+    #[test]
+    fn requires_update_for_summary() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(".prototools", r#"protostar = "1.0.0""#);
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("outdated").arg("--summary").arg("summary.md");
+            })
+            .failure();
+
+        assert!(!sandbox.path().join("summary.md").exists());
+    }
+
     #[test]
     fn doesnt_overwrite_aliases() {
         let sandbox = create_empty_proto_sandbox();
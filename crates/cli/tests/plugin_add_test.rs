@@ -168,4 +168,30 @@ existing = "https://example.com/existing.wasm"
         );
         assert!(!config.tools.contains_key("id"));
     }
+
+    #[test]
+    fn adds_policy_plugins() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("plugin")
+                    .arg("add")
+                    .arg("company")
+                    .arg("https://example.com/policy.wasm")
+                    .arg("--type")
+                    .arg("policy");
+            })
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert_eq!(
+            config.plugins.policies.get("company").unwrap(),
+            &PluginLocator::Url(Box::new(UrlLocator {
+                url: "https://example.com/policy.wasm".into()
+            }))
+        );
+        assert!(!config.tools.contains_key("company"));
+    }
 }
@@ -114,4 +114,35 @@ mod plugin_remove {
 
         assert!(!config.plugins.tools.contains_key("id"));
     }
+
+    #[test]
+    fn only_removes_policy_plugins() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[plugins.tools]
+company = "https://example.com/tool.wasm"
+
+[plugins.policies]
+company = "https://example.com/policy.wasm"
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("plugin")
+                    .arg("remove")
+                    .arg("company")
+                    .arg("--type")
+                    .arg("policy");
+            })
+            .success();
+
+        let config = load_config(sandbox.path());
+
+        assert!(!config.plugins.policies.contains_key("company"));
+        assert!(config.plugins.tools.contains_key("company"));
+    }
 }
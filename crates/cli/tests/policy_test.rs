@@ -0,0 +1,87 @@
+use proto_core::test_utils::*;
+use starbase_sandbox::predicates::prelude::*;
+use std::fs;
+
+fn create_policy_sandbox() -> ProtoSandbox {
+    let sandbox = create_empty_proto_sandbox();
+
+    sandbox.create_file(
+        ".prototools",
+        format!(
+            "[plugins.policies]\nmocked = '{}'\n",
+            get_mocked_plugin_locator()
+        ),
+    );
+
+    sandbox
+}
+
+mod policy {
+    use super::*;
+
+    #[test]
+    fn denies_install() {
+        let sandbox = create_policy_sandbox();
+
+        let assert = sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install").arg("protostar").arg("1.0.0");
+            })
+            .failure();
+
+        assert.stderr(predicate::str::contains(
+            "denied by the mocked policy: protostar v1 is no longer supported",
+        ));
+
+        assert!(!sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+    }
+
+    #[test]
+    fn allows_install() {
+        let sandbox = create_policy_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install").arg("protostar").arg("2.0.0");
+            })
+            .success();
+
+        assert!(sandbox.path().join(".proto/tools/protostar/2.0.0").exists());
+    }
+
+    #[test]
+    fn denies_pin() {
+        let sandbox = create_policy_sandbox();
+
+        let assert = sandbox
+            .run_bin(|cmd| {
+                cmd.arg("pin").arg("protostar").arg("1.0.0");
+            })
+            .failure();
+
+        assert.stderr(predicate::str::contains(
+            "Unable to pin protostar 1.0.0, as it was denied by the mocked policy",
+        ));
+
+        assert!(
+            !fs::read_to_string(sandbox.path().join(".prototools"))
+                .unwrap()
+                .contains("protostar")
+        );
+    }
+
+    #[test]
+    fn denies_run() {
+        let sandbox = create_policy_sandbox();
+
+        let assert = sandbox
+            .run_bin(|cmd| {
+                cmd.arg("run").arg("protostar").arg("1.0.0");
+            })
+            .failure();
+
+        assert.stderr(predicate::str::contains(
+            "Unable to run protostar 1.0.0, as it was denied by the mocked policy",
+        ));
+    }
+}
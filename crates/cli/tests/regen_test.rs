@@ -73,6 +73,68 @@ mod regen_shim {
 
         assert!(!unknown_path.exists());
     }
+
+    #[test]
+    fn creates_custom_shims() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(
+            ".proto/.prototools",
+            r#"
+[shims.pnpx]
+tool = "pnpm"
+before-args = ["dlx"]
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("regen");
+            })
+            .success();
+
+        assert!(get_shim_path(sandbox.path(), "pnpx").exists());
+
+        let registry =
+            fs::read_to_string(sandbox.path().join(".proto/shims/registry.json")).unwrap();
+
+        assert!(registry.contains("\"pnpx\""));
+        assert!(registry.contains("\"dlx\""));
+    }
+
+    #[test]
+    fn removes_custom_shims_no_longer_configured() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file(
+            ".proto/.prototools",
+            r#"
+[shims.pnpx]
+tool = "pnpm"
+"#,
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("regen");
+            })
+            .success();
+
+        assert!(get_shim_path(sandbox.path(), "pnpx").exists());
+
+        sandbox.create_file(".proto/.prototools", "");
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("regen");
+            })
+            .success();
+
+        assert!(!get_shim_path(sandbox.path(), "pnpx").exists());
+
+        let registry =
+            fs::read_to_string(sandbox.path().join(".proto/shims/registry.json")).unwrap();
+
+        assert!(!registry.contains("\"pnpx\""));
+    }
 }
 
 mod regen_bin {
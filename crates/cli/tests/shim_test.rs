@@ -185,3 +185,186 @@ mod shim_bin {
         cmd.assert().success();
     }
 }
+
+#[cfg(unix)]
+mod shim_cache {
+    use super::*;
+    use proto_core::layout::{ShimCache, ShimCacheEntry};
+    use starbase_sandbox::predicates::prelude::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn setup_cached_tool(sandbox: &ProtoSandbox) {
+        let shim_path = get_shim_path(sandbox.path(), "tool");
+
+        std::fs::create_dir_all(shim_path.parent().unwrap()).unwrap();
+        std::fs::copy(
+            proto_shim::locate_proto_exe("proto-shim").unwrap(),
+            &shim_path,
+        )
+        .unwrap();
+
+        sandbox.create_file("tool.sh", "#!/bin/sh\necho \"cached $CACHED_VAR $@\"\n");
+        sandbox.create_file(".prototools", "tool = \"1.0.0\"");
+
+        std::fs::set_permissions(
+            sandbox.path().join("tool.sh"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+
+        let mut entry = ShimCacheEntry {
+            exe: sandbox.path().join("tool.sh"),
+            before_args: vec!["--before".into()],
+            after_args: vec!["--after".into()],
+            ..Default::default()
+        };
+        entry.env.insert("CACHED_VAR".into(), Some("yes".into()));
+        entry.track_file(sandbox.path().join(".prototools"));
+        entry.track_file(sandbox.path().join(".tool-version"));
+
+        let mut cache = ShimCache::load_from(sandbox.path().join(".proto/cache"), sandbox.path());
+        cache.insert("tool", entry);
+        cache.save().unwrap();
+    }
+
+    #[test]
+    fn executes_cached_resolution() {
+        let sandbox = create_empty_proto_sandbox();
+
+        setup_cached_tool(&sandbox);
+
+        let mut shim = create_shim_command(sandbox.path(), "tool");
+        shim.current_dir(sandbox.path());
+        shim.arg("arg");
+
+        shim.assert()
+            .success()
+            .stdout(predicate::str::contains("cached yes --before arg --after"));
+    }
+
+    #[test]
+    fn doesnt_use_cache_when_a_file_changes() {
+        let sandbox = create_empty_proto_sandbox();
+
+        setup_cached_tool(&sandbox);
+
+        sandbox.create_file(".tool-version", "2.0.0");
+
+        let mut shim = create_shim_command(sandbox.path(), "tool");
+        shim.current_dir(sandbox.path());
+
+        shim.assert()
+            .stdout(predicate::str::contains("cached").not());
+    }
+
+    #[test]
+    fn doesnt_use_cache_when_disabled() {
+        let sandbox = create_empty_proto_sandbox();
+
+        setup_cached_tool(&sandbox);
+
+        let mut shim = create_shim_command(sandbox.path(), "tool");
+        shim.current_dir(sandbox.path());
+        shim.env("PROTO_SHIM_CACHE", "false");
+
+        shim.assert()
+            .stdout(predicate::str::contains("cached").not());
+    }
+
+    #[test]
+    fn doesnt_use_cache_in_another_dir() {
+        let sandbox = create_empty_proto_sandbox();
+
+        setup_cached_tool(&sandbox);
+
+        sandbox.create_file("nested/file", "");
+
+        let mut shim = create_shim_command(sandbox.path(), "tool");
+        shim.current_dir(sandbox.path().join("nested"));
+
+        shim.assert()
+            .stdout(predicate::str::contains("cached").not());
+    }
+
+    #[test]
+    fn caches_resolution_when_run_from_shim() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("1.0.0")
+                    .arg("--pin");
+            })
+            .success();
+
+        sandbox.run_bin(|cmd| {
+            cmd.arg("run")
+                .arg("protostar")
+                .env("PROTO_SHIM_NAME", "protostar");
+        });
+
+        let cache = ShimCache::load_from(sandbox.path().join(".proto/cache"), sandbox.path());
+        let entry = cache.get("protostar").unwrap();
+
+        assert!(entry.exe.ends_with("tools/protostar/1.0.0/protostar"));
+        assert!(
+            entry
+                .files
+                .contains_key(&sandbox.path().join(".prototools"))
+        );
+        assert!(entry.is_fresh());
+    }
+
+    #[test]
+    fn doesnt_cache_resolution_when_not_run_from_shim() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("1.0.0")
+                    .arg("--pin");
+            })
+            .success();
+
+        sandbox.run_bin(|cmd| {
+            cmd.arg("run").arg("protostar");
+        });
+
+        let cache = ShimCache::load_from(sandbox.path().join(".proto/cache"), sandbox.path());
+
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn doesnt_cache_resolution_when_policies_configured() {
+        let sandbox = create_empty_proto_sandbox();
+
+        sandbox.create_file(
+            ".prototools",
+            format!(
+                "protostar = \"2.0.0\"\n\n[plugins.policies]\nmocked = '{}'\n",
+                get_mocked_plugin_locator()
+            ),
+        );
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install").arg("protostar");
+            })
+            .success();
+
+        sandbox.run_bin(|cmd| {
+            cmd.arg("run")
+                .arg("protostar")
+                .env("PROTO_SHIM_NAME", "protostar");
+        });
+
+        let cache = ShimCache::load_from(sandbox.path().join(".proto/cache"), sandbox.path());
+
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+use proto_core::layout::{Shim, ShimRegistry};
+use proto_core::test_utils::*;
+use proto_core::{ToolManifest, ToolManifestVersion, VersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+use std::path::Path;
+
+fn create_install(root: &Path, version: &str) {
+    let tool_dir = root.join("tools/protostar");
+    let mut manifest = ToolManifest::load_from(&tool_dir).unwrap();
+
+    manifest.add_version(
+        &VersionSpec::parse(version).unwrap(),
+        ToolManifestVersion::default(),
+    );
+    manifest.save().unwrap();
+
+    for exe in ["protostar", "lib/protostarx"] {
+        let path = tool_dir.join(version).join(exe);
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+}
+
+mod store_move {
+    use super::*;
+
+    #[test]
+    fn errors_if_destination_within_store() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("store")
+                .arg("move")
+                .arg(sandbox.path().join(".proto/nested"));
+        });
+
+        assert.failure().stderr(predicate::str::contains(
+            "the destination is within the store",
+        ));
+    }
+
+    #[test]
+    fn errors_if_destination_not_empty() {
+        let sandbox = create_empty_proto_sandbox();
+        sandbox.create_file("moved/file", "");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("store")
+                .arg("move")
+                .arg(sandbox.path().join("moved"));
+        });
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("already exists and is not empty"));
+    }
+
+    #[test]
+    fn moves_store_and_rewrites_paths() {
+        let sandbox = create_empty_proto_sandbox();
+        let old_dir = sandbox.path().join(".proto");
+        let new_dir = sandbox.path().join("moved");
+
+        create_install(&old_dir, "1.0.0");
+
+        // Registry
+        let mut registry = ShimRegistry::load_from(old_dir.join("shims")).unwrap();
+        registry
+            .update(
+                "protostar".into(),
+                Shim {
+                    env_vars: [(
+                        "PROTOSTAR_HOME".into(),
+                        old_dir.join("tools/protostar").display().to_string(),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        registry.save().unwrap();
+
+        // Profile
+        sandbox.create_file(
+            ".home/.profile",
+            format!("export PROTO_HOME=\"{}\"\n", old_dir.display()),
+        );
+        sandbox.create_file(
+            ".proto/profile",
+            sandbox.path().join(".home/.profile").display().to_string(),
+        );
+
+        // Bins
+        #[cfg(unix)]
+        {
+            std::fs::create_dir_all(old_dir.join("bin")).unwrap();
+            std::os::unix::fs::symlink(
+                old_dir.join("tools/protostar/1.0.0/protostar"),
+                old_dir.join("bin/protostar"),
+            )
+            .unwrap();
+        }
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("store").arg("move").arg(&new_dir);
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Moved store from"))
+            .stdout(predicate::str::contains("Updated the shell profile"));
+
+        assert!(!old_dir.exists());
+        assert!(new_dir.join("tools/protostar/1.0.0/protostar").exists());
+
+        let registry = ShimRegistry::load_from(new_dir.join("shims")).unwrap();
+
+        assert_eq!(
+            registry.shims["protostar"].env_vars["PROTOSTAR_HOME"],
+            new_dir.join("tools/protostar").display().to_string()
+        );
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".home/.profile")).unwrap(),
+            format!("export PROTO_HOME=\"{}\"\n", new_dir.display())
+        );
+
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                std::fs::read_link(new_dir.join("bin/protostar")).unwrap(),
+                new_dir.join("tools/protostar/1.0.0/protostar")
+            );
+        }
+    }
+
+    #[test]
+    fn doesnt_modify_profile_when_disabled() {
+        let sandbox = create_empty_proto_sandbox();
+        let old_dir = sandbox.path().join(".proto");
+        let profile = format!("export PROTO_HOME=\"{}\"\n", old_dir.display());
+
+        sandbox.create_file(".home/.profile", &profile);
+        sandbox.create_file(
+            ".proto/profile",
+            sandbox.path().join(".home/.profile").display().to_string(),
+        );
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("store")
+                .arg("move")
+                .arg(sandbox.path().join("moved"))
+                .arg("--no-modify-profile");
+        });
+
+        assert.success().stderr(predicate::str::contains(
+            "Update the PROTO_HOME environment variable",
+        ));
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".home/.profile")).unwrap(),
+            profile
+        );
+    }
+}
+
+mod system_store {
+    use super::*;
+
+    #[test]
+    fn locates_executables_in_system_store() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_install(&sandbox.path().join("system"), "1.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("bin")
+                .arg("protostar")
+                .arg("1.0.0")
+                .env("PROTO_SYSTEM_HOME", sandbox.path().join("system"));
+        });
+
+        if cfg!(windows) {
+            assert.success().stdout(predicate::str::contains(
+                "system\\tools\\protostar\\1.0.0\\protostar",
+            ));
+        } else {
+            assert.success().stdout(predicate::str::contains(
+                "system/tools/protostar/1.0.0/protostar",
+            ));
+        }
+    }
+
+    #[test]
+    fn prefers_system_store_over_user_store() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_install(&sandbox.path().join("system"), "1.0.0");
+        create_install(&sandbox.path().join(".proto"), "1.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("bin")
+                .arg("protostar")
+                .arg("1.0.0")
+                .env("PROTO_SYSTEM_HOME", sandbox.path().join("system"));
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("system"))
+            .stdout(predicate::str::contains(".proto").not());
+    }
+
+    #[test]
+    fn falls_back_to_user_store() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_install(&sandbox.path().join("system"), "2.0.0");
+        create_install(&sandbox.path().join(".proto"), "1.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("bin")
+                .arg("protostar")
+                .arg("1.0.0")
+                .env("PROTO_SYSTEM_HOME", sandbox.path().join("system"));
+        });
+
+        assert.success().stdout(predicate::str::contains(".proto"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_and_runs_versions_in_system_store() {
+        let sandbox = create_empty_proto_sandbox();
+        let system_dir = sandbox.path().join("system");
+
+        create_install(&system_dir, "1.0.0");
+
+        std::fs::write(
+            system_dir.join("tools/protostar/1.0.0/protostar"),
+            "#!/bin/sh\necho \"system protostar\"\n",
+        )
+        .unwrap();
+
+        sandbox
+            .run_bin(|cmd| {
+                cmd.arg("install")
+                    .arg("protostar")
+                    .arg("1.0.0")
+                    .env("PROTO_SYSTEM_HOME", &system_dir);
+            })
+            .success();
+
+        let bin = std::fs::read_link(sandbox.path().join(".proto/bin/protostar")).unwrap();
+
+        assert!(bin.starts_with(system_dir.join("tools/protostar/1.0.0")));
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("run")
+                .arg("protostar")
+                .arg("1.0.0")
+                .env("PROTO_SYSTEM_HOME", &system_dir);
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("system protostar"));
+    }
+
+    #[test]
+    fn doesnt_install_versions_in_system_store() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_install(&sandbox.path().join("system"), "1.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("install")
+                .arg("protostar")
+                .arg("1.0.0")
+                .env("PROTO_SYSTEM_HOME", sandbox.path().join("system"));
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("has already been installed"));
+
+        assert!(!sandbox.path().join(".proto/tools/protostar/1.0.0").exists());
+    }
+}
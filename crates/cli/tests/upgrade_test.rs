@@ -19,3 +19,127 @@ mod upgrade {
         assert!(sandbox.path().join(".proto/bin").join(shim_exe).exists());
     }
 }
+
+mod upgrade_rollback {
+    use proto_core::test_utils::*;
+    use proto_shim::get_exe_file_name;
+    use starbase_sandbox::predicates::prelude::*;
+
+    fn create_backup(sandbox: &ProtoSandbox, version: &str) {
+        for bin in ["proto", "proto-shim"] {
+            sandbox.create_file(
+                format!(".proto/backups/proto/{version}/{}", get_exe_file_name(bin)),
+                version,
+            );
+        }
+    }
+
+    #[test]
+    fn errors_if_no_backups() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("upgrade").arg("--rollback");
+        });
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("there are no previous versions"));
+    }
+
+    #[test]
+    fn restores_previous_binaries() {
+        let sandbox = create_empty_proto_sandbox();
+        let current_version = env!("CARGO_PKG_VERSION");
+        let main_exe = get_exe_file_name("proto");
+
+        create_backup(&sandbox, "0.1.0");
+        sandbox.create_file(format!(".proto/bin/{main_exe}"), "current");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("upgrade").arg("--rollback");
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Rolled back proto to 0.1.0"));
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join(".proto/bin").join(&main_exe)).unwrap(),
+            "0.1.0"
+        );
+        assert!(
+            sandbox
+                .path()
+                .join(".proto/bin")
+                .join(get_exe_file_name("proto-shim"))
+                .exists()
+        );
+
+        // The restored backup is removed, and the current binary
+        // is backed up, so that the rollback can be undone
+        assert!(!sandbox.path().join(".proto/backups/proto/0.1.0").exists());
+        assert_eq!(
+            std::fs::read_to_string(
+                sandbox
+                    .path()
+                    .join(".proto/backups/proto")
+                    .join(current_version)
+                    .join(&main_exe)
+            )
+            .unwrap(),
+            "current"
+        );
+    }
+
+    #[test]
+    fn doesnt_rollback_below_minimum_version() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_backup(&sandbox, "0.1.0");
+
+        sandbox.create_file(".prototools", r#"proto-version = "0.2.0""#);
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("upgrade").arg("--rollback");
+        });
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("requires proto"));
+
+        assert!(sandbox.path().join(".proto/backups/proto/0.1.0").exists());
+    }
+
+    #[test]
+    fn doesnt_rollback_to_an_unsupported_version() {
+        let sandbox = create_empty_proto_sandbox();
+
+        create_backup(&sandbox, "0.1.0");
+
+        sandbox.create_file(".prototools", r#"proto-version = ">=0.2.0""#);
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("upgrade").arg("--rollback");
+        });
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("requires proto >=0.2.0"));
+
+        assert!(sandbox.path().join(".proto/backups/proto/0.1.0").exists());
+    }
+
+    #[test]
+    fn doesnt_combine_rollback_with_target() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("upgrade").arg("0.1.0").arg("--rollback");
+        });
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
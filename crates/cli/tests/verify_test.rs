@@ -0,0 +1,283 @@
+use proto_core::checksum::fingerprint_dir;
+use proto_core::test_utils::*;
+use proto_core::{ToolManifest, ToolManifestVersion, VersionSpec};
+use starbase_sandbox::predicates::prelude::*;
+
+mod verify {
+    use super::*;
+
+    fn create_install(sandbox: &ProtoSandbox, version: &str) {
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+
+        manifest.add_version(
+            &VersionSpec::parse(version).unwrap(),
+            ToolManifestVersion::default(),
+        );
+        manifest.save().unwrap();
+
+        for exe in ["protostar", "lib/protostarx"] {
+            let path = format!(".proto/tools/protostar/{version}/{exe}");
+
+            sandbox.create_file(&path, "");
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+
+                std::fs::set_permissions(
+                    sandbox.path().join(path),
+                    std::fs::Permissions::from_mode(0o755),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn run_verify(sandbox: &ProtoSandbox) -> serde_json::Value {
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify").arg("protostar").arg("--json");
+        });
+
+        serde_json::from_slice(&assert.inner.get_output().stdout).unwrap()
+    }
+
+    #[test]
+    fn passes_for_valid_installs() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+        create_install(&sandbox, "2.0.0");
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify");
+        });
+
+        assert.success().stdout(predicate::str::contains(
+            "Verified 2 installed versions across 1 tools, no issues found!",
+        ));
+    }
+
+    #[test]
+    fn flags_missing_executables() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        std::fs::remove_file(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/lib/protostarx"),
+        )
+        .unwrap();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify").arg("protostar");
+        });
+
+        assert
+            .failure()
+            .stdout(predicate::str::contains("Executable protostarx"))
+            .stderr(predicate::str::contains("Found 1 issues!"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn flags_non_executable_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        std::fs::set_permissions(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/lib/protostarx"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+
+        let result = run_verify(&sandbox);
+        let issues = result["tools"][0]["versions"][0]["issues"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues[0]["issue"]
+                .as_str()
+                .unwrap()
+                .contains("is not executable")
+        );
+    }
+
+    #[test]
+    fn flags_missing_install_dirs() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        std::fs::remove_dir_all(sandbox.path().join(".proto/tools/protostar/1.0.0")).unwrap();
+
+        let result = run_verify(&sandbox);
+        let issues = result["tools"][0]["versions"][0]["issues"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(result["tools"][0]["versions"][0]["version"], "1.0.0");
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues[0]["issue"]
+                .as_str()
+                .unwrap()
+                .contains("Install directory")
+        );
+    }
+
+    #[test]
+    fn flags_missing_manifest_entries() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+        manifest.versions.clear();
+        manifest.save().unwrap();
+
+        let result = run_verify(&sandbox);
+        let issues = result["tools"][0]["versions"][0]["issues"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues[0]["issue"]
+                .as_str()
+                .unwrap()
+                .contains("has no entry in manifest")
+        );
+    }
+
+    #[test]
+    fn flags_modified_files() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+        manifest
+            .versions
+            .get_mut(&VersionSpec::parse("1.0.0").unwrap())
+            .unwrap()
+            .fingerprint =
+            Some(fingerprint_dir(sandbox.path().join(".proto/tools/protostar/1.0.0")).unwrap());
+        manifest.save().unwrap();
+
+        let result = run_verify(&sandbox);
+
+        assert_eq!(result["tools"][0]["versions"][0]["modified"], false);
+
+        sandbox.create_file(".proto/tools/protostar/1.0.0/lib/package.json", "{}");
+
+        let result = run_verify(&sandbox);
+        let warnings = result["tools"][0]["versions"][0]["warnings"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(result["tools"][0]["versions"][0]["modified"], true);
+        assert_eq!(
+            result["tools"][0]["versions"][0]["issues"]
+                .as_array()
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0]["issue"]
+                .as_str()
+                .unwrap()
+                .contains("have changed since it was installed")
+        );
+
+        // Modified files are not issues, and are not repaired automatically
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify").arg("protostar").arg("--repair");
+        });
+
+        assert
+            .success()
+            .stdout(predicate::str::contains("Modified"));
+
+        assert!(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/lib/package.json")
+                .exists()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn flags_and_repairs_broken_bins() {
+        let sandbox = create_empty_proto_sandbox();
+        create_install(&sandbox, "1.0.0");
+
+        let bin = sandbox.path().join(".proto/bin/protostar");
+
+        std::fs::create_dir_all(bin.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/0.1.0/protostar"),
+            &bin,
+        )
+        .unwrap();
+
+        let result = run_verify(&sandbox);
+
+        assert!(
+            result["tools"][0]["issues"][0]["issue"]
+                .as_str()
+                .unwrap()
+                .contains("points to a target that does not exist")
+        );
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify").arg("protostar").arg("--repair");
+        });
+
+        assert.success().stdout(predicate::str::contains(
+            "Relinked shims and bins for protostar",
+        ));
+
+        assert_eq!(
+            std::fs::read_link(&bin).unwrap(),
+            sandbox
+                .path()
+                .join(".proto/tools/protostar/1.0.0/protostar")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removes_broken_bins_for_unknown_tools() {
+        let sandbox = create_empty_proto_sandbox();
+        let bin = sandbox.path().join(".proto/bin/unknown");
+
+        std::fs::create_dir_all(bin.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(
+            sandbox.path().join(".proto/tools/unknown/1.0.0/unknown"),
+            &bin,
+        )
+        .unwrap();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("verify").arg("--repair");
+        });
+
+        assert.success();
+
+        assert!(!bin.is_symlink());
+    }
+}
@@ -95,6 +95,96 @@ mod versions {
         assert_eq!(output.lines().collect::<Vec<_>>().len(), 3);
     }
 
+    #[test]
+    fn outputs_ndjson_for_installed_versions() {
+        let sandbox = create_empty_proto_sandbox();
+        let versions = vec!["1.0.0", "1.1.0", "2.0.0"];
+
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+
+        for version in &versions {
+            manifest.versions.insert(
+                VersionSpec::parse(version).unwrap(),
+                ToolManifestVersion::default(),
+            );
+        }
+
+        manifest.save().unwrap();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("versions")
+                .arg("protostar")
+                .arg("--installed-only")
+                .arg("--group-by")
+                .arg("major")
+                .arg("--since")
+                .arg("1.1")
+                .arg("--format")
+                .arg("ndjson");
+        });
+
+        let output = output_to_string(&assert.inner.get_output().stdout);
+        let items = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(items.len(), 2);
+
+        for item in items {
+            assert_eq!(item["installed"], true);
+            assert!(["1", "2"].contains(&item["group"].as_str().unwrap()));
+        }
+    }
+
+    #[test]
+    fn doesnt_suffix_non_numeric_groups() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let mut manifest =
+            ToolManifest::load(sandbox.path().join(".proto/tools/protostar/manifest.json"))
+                .unwrap();
+
+        for version in ["1.0.0", "canary"] {
+            manifest.versions.insert(
+                VersionSpec::parse(version).unwrap(),
+                ToolManifestVersion::default(),
+            );
+        }
+
+        manifest.save().unwrap();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("versions")
+                .arg("protostar")
+                .arg("--installed-only")
+                .arg("--group-by")
+                .arg("major");
+        });
+
+        let output = output_to_string(&assert.inner.get_output().stdout);
+
+        assert!(output.contains("1.x"));
+        assert!(output.contains("other"));
+        assert!(!output.contains("other.x"));
+    }
+
+    #[test]
+    fn errors_for_invalid_since() {
+        let sandbox = create_empty_proto_sandbox();
+
+        let assert = sandbox.run_bin(|cmd| {
+            cmd.arg("versions")
+                .arg("protostar")
+                .arg("--since")
+                .arg("not a version");
+        });
+
+        assert.failure();
+    }
+
     // Windows doesn't support asdf
     #[cfg(unix)]
     mod backend {
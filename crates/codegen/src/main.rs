@@ -57,6 +57,7 @@ fn generate_types() {
     generator.add::<ExecutableConfig>();
     generator.add::<LocateExecutablesOutput>();
     generator.add::<LoadVersionsInput>();
+    generator.add::<VersionMetadata>();
     generator.add::<LoadVersionsOutput>();
     generator.add::<ResolveVersionInput>();
     generator.add::<ResolveVersionOutput>();
@@ -75,6 +76,11 @@ fn generate_types() {
     generator.add::<BuildInstructionsOutput>();
     generator.add::<BuildInstructionsInput>();
 
+    generator.add::<PolicyOperation>();
+    generator.add::<PolicyLockRecord>();
+    generator.add::<PolicyCheckInput>();
+    generator.add::<PolicyCheckOutput>();
+
     generator
         .generate(
             PathBuf::from("package/src/api-types.ts"),
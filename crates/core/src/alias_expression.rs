@@ -0,0 +1,115 @@
+use crate::id::Id;
+use std::fmt;
+use version_spec::{Op, Requirement, UnresolvedVersionSpec};
+
+/// Part of another tool's pinned version that an alias matches against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AliasPinPart {
+    /// The pinned version as-is.
+    Version,
+    /// The same major version (`~<major>`).
+    Major,
+    /// The same major and minor version (`~<major>.<minor>`).
+    Minor,
+}
+
+/// An alias that computes its version, instead of mapping to a static
+/// version. Expressions are only used when an alias with the same name
+/// does not exist in the tool's configuration or plugin.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AliasExpression {
+    /// `<alias>-<n>`, the highest stable version in the release line that is
+    /// `n` major versions before the version the alias resolves to. If
+    /// the alias resolves to an LTS version, only LTS lines are counted.
+    Previous { alias: String, offset: usize },
+
+    /// `pin/<tool>`, `pin/<tool>/major`, or `pin/<tool>/minor`, the
+    /// version that matches the version pinned for another tool.
+    Pin { tool: Id, part: AliasPinPart },
+}
+
+impl AliasExpression {
+    /// Parse an expression from a version specification, if applicable.
+    pub fn from_spec(spec: &UnresolvedVersionSpec) -> Option<Self> {
+        match spec {
+            UnresolvedVersionSpec::Alias(alias) => Self::parse(alias),
+            UnresolvedVersionSpec::Requirement(req) => Self::from_requirement(req),
+            _ => None,
+        }
+    }
+
+    /// Parse a `pin/<tool>` expression from an alias.
+    pub fn parse(alias: &str) -> Option<Self> {
+        let rest = alias.strip_prefix("pin/")?;
+        let (tool, part) = match rest.split_once('/') {
+            Some((tool, "major")) => (tool, AliasPinPart::Major),
+            Some((tool, "minor")) => (tool, AliasPinPart::Minor),
+            Some(_) => return None,
+            None => (rest, AliasPinPart::Version),
+        };
+
+        Id::new(tool)
+            .ok()
+            .map(|tool| AliasExpression::Pin { tool, part })
+    }
+
+    /// An expression like `lts-1` is parsed as a `~1` requirement with an
+    /// `lts` scope, so convert these requirements into a previous expression.
+    pub fn from_requirement(req: &Requirement) -> Option<Self> {
+        if req.op != Op::Tilde
+            || req.minor.is_some()
+            || req.patch.is_some()
+            || req.prerelease.is_some()
+        {
+            return None;
+        }
+
+        let offset = req.major.filter(|major| *major > 0)?;
+
+        Some(AliasExpression::Previous {
+            alias: req.scope.as_ref()?.to_string(),
+            offset: offset as usize,
+        })
+    }
+
+    /// Convert the pinned version of another tool into a specification
+    /// to resolve with, according to the part of the pin being matched.
+    /// Returns `None` if the pin does not include the required parts.
+    pub fn apply_pin(
+        part: AliasPinPart,
+        pinned: &UnresolvedVersionSpec,
+    ) -> Option<UnresolvedVersionSpec> {
+        if part == AliasPinPart::Version {
+            return Some(pinned.to_owned());
+        }
+
+        let (major, minor) = match pinned {
+            UnresolvedVersionSpec::Version(version) => (version.major, Some(version.minor)),
+            UnresolvedVersionSpec::Requirement(req) => (req.major?, req.minor),
+            _ => return None,
+        };
+
+        let req = if part == AliasPinPart::Minor {
+            format!("~{major}.{}", minor?)
+        } else {
+            format!("~{major}")
+        };
+
+        Requirement::parse(req)
+            .ok()
+            .map(UnresolvedVersionSpec::Requirement)
+    }
+}
+
+impl fmt::Display for AliasExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Previous { alias, offset } => write!(f, "{alias}-{offset}"),
+            Self::Pin { tool, part } => match part {
+                AliasPinPart::Version => write!(f, "pin/{tool}"),
+                AliasPinPart::Major => write!(f, "pin/{tool}/major"),
+                AliasPinPart::Minor => write!(f, "pin/{tool}/minor"),
+            },
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::checksum_error::ProtoChecksumError;
+use sha2::{Digest, Sha256};
+use starbase_utils::fs::{self, FsError};
+use starbase_utils::hash;
+use std::fmt::Debug;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tracing::{instrument, trace};
+
+/// Files that proto writes into a product directory after it
+/// has been installed, and must not change the fingerprint.
+const IGNORED_FILES: &[&str] = &[".last-used"];
+
+/// Calculate a Merkle-style fingerprint of a directory. Files are hashed by
+/// their contents (and executable bit), symlinks by their target, and
+/// directories by the sorted names, kinds, and hashes of their children.
+/// Adding, removing, or modifying any file will change the root hash.
+#[instrument]
+pub fn fingerprint_dir<P: AsRef<Path> + Debug>(dir: P) -> Result<String, ProtoChecksumError> {
+    let dir = dir.as_ref();
+
+    trace!(dir = ?dir, "Calculating fingerprint");
+
+    let hash = hash_dir(dir, true, true)?;
+
+    trace!(dir = ?dir, hash, "Calculated fingerprint");
+
+    Ok(hash)
+}
+
+/// Calculate a stamp of a directory, like [`fingerprint_dir`], but with files
+/// hashed by their size and modified time, instead of their contents. This is
+/// cheap enough to detect changes without reading every file, but can't
+/// detect changes that preserve the size and modified time.
+#[instrument]
+pub fn stamp_dir<P: AsRef<Path> + Debug>(dir: P) -> Result<String, ProtoChecksumError> {
+    let dir = dir.as_ref();
+
+    trace!(dir = ?dir, "Calculating stamp");
+
+    let hash = hash_dir(dir, true, false)?;
+
+    trace!(dir = ?dir, hash, "Calculated stamp");
+
+    Ok(hash)
+}
+
+fn hash_dir(dir: &Path, root: bool, contents: bool) -> Result<String, ProtoChecksumError> {
+    let mut entries = fs::read_dir(dir)?;
+    let mut hasher = Sha256::new();
+
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        if root && IGNORED_FILES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_type = entry.file_type().map_err(|error| FsError::Read {
+            path: path.clone(),
+            error: Box::new(error),
+        })?;
+
+        let (kind, hash) = if file_type.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(|error| FsError::Read {
+                path: path.clone(),
+                error: Box::new(error),
+            })?;
+
+            (
+                "l",
+                hex::encode(Sha256::digest(target.to_string_lossy().as_bytes())),
+            )
+        } else if file_type.is_dir() {
+            ("d", hash_dir(&path, false, contents)?)
+        } else {
+            (
+                if fs::is_executable(&path) { "x" } else { "f" },
+                if contents {
+                    hash::sha256::from_file(&path).map_err(|error| ProtoChecksumError::Sha {
+                        error: Box::new(error),
+                    })?
+                } else {
+                    hash_file_metadata(&path)?
+                },
+            )
+        };
+
+        hasher.update(format!("{kind} {hash} {name}\n"));
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn hash_file_metadata(path: &Path) -> Result<String, ProtoChecksumError> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    Ok(format!("{}:{}", metadata.len(), modified.as_nanos()))
+}
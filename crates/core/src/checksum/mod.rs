@@ -1,4 +1,5 @@
 mod checksum_error;
+mod fingerprint;
 mod minisign;
 mod sha;
 
@@ -8,6 +9,7 @@ use std::path::Path;
 use tracing::instrument;
 
 pub use checksum_error::*;
+pub use fingerprint::{fingerprint_dir, stamp_dir};
 pub use sha::{hash_file_contents_sha256, hash_file_contents_sha512};
 
 #[instrument]
@@ -23,6 +23,7 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use toml_edit::DocumentMut;
 use tracing::{debug, instrument, trace};
+use version_spec::UnresolvedVersionSpec;
 use warpgate::{Id, PluginLocator, RegistryLocator, UrlLocator, find_debug_locator};
 
 // Re-export settings from here!
@@ -32,6 +33,7 @@ pub const PROTO_CONFIG_NAME: &str = ".prototools";
 pub const SCHEMA_PLUGIN_KEY: &str = "internal-schema";
 pub const PROTO_PLUGIN_KEY: &str = "proto";
 pub const ENV_FILE_KEY: &str = "file";
+pub const IGNORE_PROTO_VERSION_VAR: &str = "PROTO_IGNORE_PROTO_VERSION";
 
 #[derive(Clone, Config, Debug, Serialize)]
 #[config(allow_unknown_fields)]
@@ -52,9 +54,16 @@ pub struct ProtoConfig {
     #[setting(nested)]
     pub plugins: ProtoPluginsConfig,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proto_version: Option<UnresolvedVersionSpec>,
+
     #[setting(nested)]
     pub shell: ProtoShellConfig,
 
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[setting(nested, merge = merge_partials_iter, validate = validate_shim_names)]
+    pub shims: BTreeMap<String, ProtoShimConfig>,
+
     #[setting(nested)]
     pub settings: ProtoSettingsConfig,
 
@@ -443,6 +452,12 @@ impl ProtoConfig {
                     inner.path = Some(make_absolute(inner.get_unresolved_path(), path));
                 }
 
+                if let Some(allow) = &tool.allow {
+                    tool._allow_layers
+                        .get_or_insert(vec![])
+                        .push(allow.to_owned());
+                }
+
                 push_env_file(tool.env.as_mut(), &mut tool._env_files, 5)?;
             }
         }
@@ -519,7 +534,9 @@ impl ProtoConfig {
     }
 
     pub fn get_plugin(&self, context: &ToolContext, ty: PluginType) -> Option<&PluginLocator> {
-        if ty == PluginType::Backend
+        if ty == PluginType::Policy {
+            self.plugins.get(&context.id, ty)
+        } else if ty == PluginType::Backend
             && let Some(id) = &context.backend
         {
             self.backends
@@ -669,6 +686,34 @@ impl ProtoConfig {
         url
     }
 
+    /// Return a list of URLs to attempt downloads from, in order. The URL is
+    /// first rewritten, and then each matching mirror is applied to it. The
+    /// rewritten (canonical) URL is always the last entry, as a fallback.
+    ///
+    /// The provided URL must not have been rewritten already, as rewrite
+    /// rules are not guaranteed to be idempotent.
+    pub fn get_mirror_urls(&self, url: impl AsRef<str>) -> Vec<String> {
+        let url = self.rewrite_url(url);
+        let mut urls = vec![];
+
+        for (pattern, mirrors) in &self.settings.url_mirrors {
+            if !pattern.is_match(&url) {
+                continue;
+            }
+
+            for mirror in mirrors {
+                let mirror_url = pattern.replace_all(&url, mirror).to_string();
+
+                if mirror_url != url && !urls.contains(&mirror_url) {
+                    urls.push(mirror_url);
+                }
+            }
+        }
+
+        urls.push(url);
+        urls
+    }
+
     fn resolve_path(path: impl AsRef<Path>) -> PathBuf {
         let path = path.as_ref();
 
@@ -57,6 +57,22 @@ pub enum ProtoConfigError {
         config: String,
         config_path: PathBuf,
     },
+
+    #[diagnostic(
+        code(proto::config::unsupported_proto_version),
+        help = "Run proto upgrade to install a supported version, or enable settings.auto-install to switch versions automatically."
+    )]
+    #[error(
+        "The config {} requires proto {}, but the current version is {}.",
+        .path.style(Style::Path),
+        .requirement.style(Style::Hash),
+        .version.style(Style::Hash),
+    )]
+    UnsupportedProtoVersion {
+        path: PathBuf,
+        requirement: String,
+        version: String,
+    },
 }
 
 impl From<FsError> for ProtoConfigError {
@@ -1,16 +1,21 @@
-use crate::config::{ConfigMode, PROTO_CONFIG_NAME, PinLocation, ProtoConfig};
+use crate::config::{
+    ConfigMode, IGNORE_PROTO_VERSION_VAR, PROTO_CONFIG_NAME, PinLocation, ProtoConfig,
+};
 use crate::config_error::ProtoConfigError;
 use crate::env_error::ProtoEnvError;
 use crate::file_manager::{ProtoConfigFile, ProtoDirEntry, ProtoFileManager};
-use crate::helpers::is_offline;
-use crate::layout::Store;
+use crate::helpers::{get_proto_version, is_offline, now};
+use crate::layout::{Store, is_project_dir};
+use crate::loader::PolicyPlugins;
 use crate::lockfile::ProtoLock;
 use crate::telemetry::MetricTimer;
 use crate::tool_context::ToolContext;
 use once_cell::sync::OnceCell;
 use proto_pdk_api::sort_paths_list;
+use starbase_styles::color;
 use starbase_utils::dirs::home_dir;
 use starbase_utils::{envx, string_vec};
+use std::collections::BTreeMap;
 use std::env;
 use std::fmt;
 use std::path::{Path, PathBuf};
@@ -22,6 +27,8 @@ use warpgate::PluginLoader;
 
 #[derive(Clone, Default)]
 pub struct ProtoEnvironment {
+    /// Verify the `proto-version` requirement of configs when loading them.
+    pub check_proto_version: bool,
     pub config_mode: ConfigMode,
     pub env_mode: Option<String>,
     pub home_dir: PathBuf, // ~
@@ -35,6 +42,7 @@ pub struct ProtoEnvironment {
 
     file_manager: Arc<OnceCell<ProtoFileManager>>,
     plugin_loader: Arc<OnceCell<PluginLoader>>,
+    pub(crate) policy_plugins: Arc<tokio::sync::OnceCell<PolicyPlugins>>,
 }
 
 impl ProtoEnvironment {
@@ -48,7 +56,7 @@ impl ProtoEnvironment {
             root = home.join(rel_root);
         }
 
-        Self::from(root, home)
+        Self::create(root, home, true)
     }
 
     pub fn new_testing(sandbox: &Path) -> Result<Self, ProtoEnvError> {
@@ -59,6 +67,14 @@ impl ProtoEnvironment {
     }
 
     pub fn from<R: AsRef<Path>, H: AsRef<Path>>(root: R, home: H) -> Result<Self, ProtoEnvError> {
+        Self::create(root, home, false)
+    }
+
+    fn create<R: AsRef<Path>, H: AsRef<Path>>(
+        root: R,
+        home: H,
+        with_default_system_dir: bool,
+    ) -> Result<Self, ProtoEnvError> {
         let root = root.as_ref();
         let home = home.as_ref();
 
@@ -69,6 +85,7 @@ impl ProtoEnvironment {
         );
 
         Ok(ProtoEnvironment {
+            check_proto_version: !envx::bool_var(IGNORE_PROTO_VERSION_VAR),
             config_mode: ConfigMode::Upwards,
             working_dir: env::current_dir().map_err(|_| ProtoEnvError::MissingWorkingDir)?,
             env_mode: env::var("PROTO_ENV").ok(),
@@ -76,8 +93,9 @@ impl ProtoEnvironment {
             otel_enabled: false,
             file_manager: Arc::new(OnceCell::new()),
             plugin_loader: Arc::new(OnceCell::new()),
+            policy_plugins: Arc::new(tokio::sync::OnceCell::new()),
             test_only: env::var("PROTO_TEST").is_ok(),
-            store: Store::new(root),
+            store: Store::new(root, get_system_dir(root, with_default_system_dir)),
             os: SystemOS::default(),
             arch: SystemArch::default(),
         })
@@ -125,6 +143,13 @@ impl ProtoEnvironment {
             (self.home_dir.clone(), "/userhome".into()),
         ];
 
+        // The system store is read-only, so it must already exist
+        if let Some(system_dir) = &self.store.system_dir
+            && system_dir.exists()
+        {
+            paths.push((system_dir.clone(), "/proto-system".into()));
+        }
+
         // This is required for situtations where users are using proto
         // outside of the home directory, and the WASM plugin will need
         // access to it!
@@ -225,24 +250,112 @@ impl ProtoEnvironment {
             // Always load the proto home/root config last
             let path = self.store.dir.join(PROTO_CONFIG_NAME);
 
+            let file = ProtoConfigFile {
+                exists: path.exists(),
+                path,
+                config: ProtoConfig::load_from(&self.store.dir, true)?,
+                platform_pins: BTreeMap::default(),
+            };
+
             manager.entries.push(ProtoDirEntry {
                 path: self.store.dir.clone(),
                 location: PinLocation::Global,
-                configs: vec![ProtoConfigFile {
-                    exists: path.exists(),
-                    path,
-                    config: ProtoConfig::load_from(&self.store.dir, true)?,
-                }],
+                configs: vec![file],
                 locked: false,
             });
 
+            if self.check_proto_version {
+                manager.check_proto_version(get_proto_version())?;
+            }
+
+            // Swap pinned versions with their host platform overrides
+            manager.apply_platform_pins(self.os, self.arch);
+
             // Remove the pinned `proto` version from global/user configs,
             // as it causes massive recursion and `proto` process chains
             manager.remove_proto_pins();
 
+            self.register_projects(&manager);
+
             Ok(manager)
         })
     }
+
+    /// Record the local directories with a config or lockfile in the
+    /// projects registry, so that `proto clean` can keep their versions.
+    /// Failures are not fatal, as the registry is only a hint.
+    fn register_projects(&self, manager: &ProtoFileManager) {
+        let dirs = manager
+            .entries
+            .iter()
+            .filter(|entry| entry.location == PinLocation::Local && is_project_dir(&entry.path))
+            .map(|entry| entry.path.as_path())
+            .collect::<Vec<_>>();
+
+        if dirs.is_empty() {
+            return;
+        }
+
+        let result = self.store.load_project_registry().and_then(|mut registry| {
+            let now = now();
+            let mut changed = false;
+
+            for dir in dirs {
+                changed |= registry.register(dir, now);
+            }
+
+            if changed {
+                registry.save()?;
+            }
+
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            debug!("Failed to update projects registry: {error}");
+        }
+    }
+}
+
+/// Return the read-only system store, from `PROTO_SYSTEM_HOME` (an empty value
+/// disables it), or the default location if requested. The default location
+/// is only used when it has been provisioned with installed tools.
+fn get_system_dir(store_dir: &Path, with_default: bool) -> Option<PathBuf> {
+    match env::var_os("PROTO_SYSTEM_HOME") {
+        Some(value) if value.is_empty() => None,
+        Some(value) => {
+            let custom = PathBuf::from(value);
+
+            debug!(
+                system_dir = ?custom,
+                "Using system store from {}",
+                color::symbol("PROTO_SYSTEM_HOME")
+            );
+
+            Some(custom)
+        }
+        None if with_default => {
+            let default = get_default_system_dir()
+                .filter(|dir| dir != store_dir && dir.join("tools").is_dir())?;
+
+            debug!(system_dir = ?default, "Using default system store");
+
+            Some(default)
+        }
+        None => None,
+    }
+}
+
+fn get_default_system_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        envx::path_var("ProgramData").map(|dir| dir.join("proto"))
+    }
+
+    #[cfg(not(windows))]
+    {
+        Some(PathBuf::from("/opt/proto"))
+    }
 }
 
 impl AsRef<ProtoEnvironment> for ProtoEnvironment {
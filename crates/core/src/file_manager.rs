@@ -2,7 +2,9 @@ use crate::config::*;
 use crate::config_error::ProtoConfigError;
 use crate::id::Id;
 use crate::lockfile::*;
+use crate::settings::get_platform_config;
 use crate::tool_context::ToolContext;
+use crate::tool_spec::ToolSpec;
 use once_cell::sync::OnceCell;
 use schematic::{Config, PartialConfig};
 use serde::Serialize;
@@ -11,13 +13,50 @@ use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use system_env::{SystemArch, SystemOS};
 use tracing::debug;
+use version_spec::{MatchesVersion, Range, UnresolvedVersionSpec, Version};
 
 #[derive(Debug, Serialize)]
 pub struct ProtoConfigFile {
     pub exists: bool,
     pub path: PathBuf,
     pub config: PartialProtoConfig,
+
+    /// Tools in which the pinned version was replaced with a platform specific
+    /// version, mapped to the key of the `[tools.<id>.platform.<key>]` table.
+    /// Writers must update this table instead of the pinned version.
+    #[serde(skip)]
+    pub platform_pins: BTreeMap<ToolContext, String>,
+}
+
+impl ProtoConfigFile {
+    /// Verify that the provided proto version satisfies the `proto-version`
+    /// requirement of this config, if one has been defined. A bare version is
+    /// the minimum supported version (`>=`). Aliases are ignored, as they can
+    /// only be checked once resolved.
+    pub fn check_proto_version(&self, version: &Version) -> Result<(), ProtoConfigError> {
+        let Some(requirement) = &self.config.proto_version else {
+            return Ok(());
+        };
+
+        let supported = match requirement {
+            UnresolvedVersionSpec::Range(range) => range.matches(version),
+            UnresolvedVersionSpec::Requirement(req) => req.matches(version),
+            UnresolvedVersionSpec::Version(minimum) => version >= minimum,
+            _ => true,
+        };
+
+        if supported {
+            return Ok(());
+        }
+
+        Err(ProtoConfigError::UnsupportedProtoVersion {
+            path: self.path.clone(),
+            requirement: requirement.to_string(),
+            version: version.to_string(),
+        })
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -28,6 +67,12 @@ pub struct ProtoDirEntry {
     pub locked: bool,
 }
 
+#[derive(Debug)]
+pub struct ProtoVersionConflict {
+    pub context: ToolContext,
+    pub specs: Vec<(PathBuf, UnresolvedVersionSpec)>,
+}
+
 #[derive(Debug)]
 pub struct ProtoFileManager {
     // Paths are sorted from current working directory,
@@ -74,6 +119,7 @@ impl ProtoFileManager {
                     config: ProtoConfig::load(&env_path, false)?,
                     exists: env_path.exists(),
                     path: env_path,
+                    platform_pins: BTreeMap::default(),
                 });
             }
 
@@ -84,6 +130,7 @@ impl ProtoFileManager {
                 config: ProtoConfig::load(&path, false)?,
                 exists: path.exists(),
                 path,
+                platform_pins: BTreeMap::default(),
             });
 
             // Only load the lockfile if any of the configs
@@ -193,6 +240,111 @@ impl ProtoFileManager {
         self.entries.iter().flat_map(|dir| &dir.configs).collect()
     }
 
+    /// Verify that the provided proto version satisfies the `proto-version`
+    /// requirement of every loaded config, and return an error for the first
+    /// config that it does not.
+    pub fn check_proto_version(&self, version: &Version) -> Result<(), ProtoConfigError> {
+        for file in self.get_config_files() {
+            file.check_proto_version(version)?;
+        }
+
+        Ok(())
+    }
+
+    /// Detect tools with configured versions that cannot be satisfied together,
+    /// for example `^20` and `>=22`. Versions in parent directories are simply
+    /// overridden by closer configs, so only the following are detected:
+    ///
+    /// - Versions within the same directory (for example, `.prototools` and
+    ///   `.prototools.<env>`) that do not intersect.
+    /// - The closest version, which is the one that will be used, that does
+    ///   not intersect with an `allow` constraint of any config.
+    ///
+    /// Aliases are ignored, as they can only be checked once resolved.
+    pub fn get_version_conflicts(&self) -> Vec<ProtoVersionConflict> {
+        type Specs<'a> = Vec<(&'a Path, &'a UnresolvedVersionSpec)>;
+
+        let mut versions_by_context: BTreeMap<&ToolContext, Vec<Specs>> = BTreeMap::default();
+        let mut allows_by_context: BTreeMap<&ToolContext, Specs> = BTreeMap::default();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            for file in &entry.configs {
+                if let Some(versions) = &file.config.versions {
+                    for (context, spec) in versions {
+                        let scopes = versions_by_context.entry(context).or_default();
+                        scopes.resize_with(index + 1, Vec::new);
+                        scopes[index].push((&file.path, &spec.req));
+                    }
+                }
+
+                if let Some(tools) = &file.config.tools {
+                    for (context, tool) in tools {
+                        if let Some(allow) = &tool.allow {
+                            allows_by_context
+                                .entry(context)
+                                .or_default()
+                                .push((&file.path, allow));
+                        }
+                    }
+                }
+            }
+        }
+
+        let is_satisfiable = |specs: &[&(&Path, &UnresolvedVersionSpec)]| {
+            let mut range = Range::default();
+
+            for (_, spec) in specs {
+                if let Some(spec_range) = spec.to_range() {
+                    match range.intersect(&spec_range) {
+                        Some(next) => range = next,
+                        None => return false,
+                    };
+                }
+            }
+
+            true
+        };
+
+        let mut conflicts = vec![];
+
+        for (context, scopes) in versions_by_context {
+            let mut specs = vec![];
+
+            for scope in &scopes {
+                if !is_satisfiable(&scope.iter().collect::<Vec<_>>()) {
+                    specs.extend(scope.iter());
+                }
+            }
+
+            if let (Some(closest), Some(allows)) = (
+                scopes.iter().flatten().next(),
+                allows_by_context.get(context),
+            ) {
+                for allow in allows {
+                    if !is_satisfiable(&[closest, allow]) {
+                        if !specs.contains(&closest) {
+                            specs.push(closest);
+                        }
+
+                        specs.push(allow);
+                    }
+                }
+            }
+
+            if !specs.is_empty() {
+                conflicts.push(ProtoVersionConflict {
+                    context: context.to_owned(),
+                    specs: specs
+                        .into_iter()
+                        .map(|(path, spec)| (path.to_path_buf(), (*spec).to_owned()))
+                        .collect(),
+                });
+            }
+        }
+
+        conflicts
+    }
+
     pub fn get_global_config(&self) -> Result<&ProtoConfig, ProtoConfigError> {
         self.global_config.get_or_try_init(|| {
             debug!("Loading global config only");
@@ -266,6 +418,47 @@ impl ProtoFileManager {
         })
     }
 
+    /// Replace the pinned versions of each config file with their
+    /// platform specific versions for the provided operating system
+    /// and architecture, if one has been configured for the tool.
+    /// Replaced versions are tracked in [`ProtoConfigFile::platform_pins`].
+    pub fn apply_platform_pins(&mut self, os: SystemOS, arch: SystemArch) {
+        self.entries.iter_mut().for_each(|dir| {
+            dir.configs.iter_mut().for_each(|file| {
+                let Some(tools) = &file.config.tools else {
+                    return;
+                };
+
+                for (context, tool) in tools {
+                    let Some((key, spec)) = tool
+                        .platform
+                        .as_ref()
+                        .and_then(|platform| get_platform_config(platform, os, arch))
+                        .and_then(|(key, config)| Some((key, config.version.as_ref()?)))
+                    else {
+                        continue;
+                    };
+
+                    debug!(
+                        tool = context.as_str(),
+                        spec = spec.to_string(),
+                        platform = format!("{os}-{arch}"),
+                        config = ?file.path,
+                        "Using platform specific version",
+                    );
+
+                    file.config
+                        .versions
+                        .get_or_insert_default()
+                        .insert(context.to_owned(), ToolSpec::new(spec.to_owned()));
+
+                    file.platform_pins
+                        .insert(context.to_owned(), key.to_owned());
+                }
+            });
+        });
+    }
+
     pub(crate) fn remove_proto_pins(&mut self) {
         let context = ToolContext::new(Id::raw(PROTO_PLUGIN_KEY));
 
@@ -297,6 +490,7 @@ impl ProtoFileManager {
         }
 
         let mut config = ProtoConfig::from_partial(partial.finalize(context)?);
+
         config.inherit_builtin_plugins();
         config.setup_env_vars();
 
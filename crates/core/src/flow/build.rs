@@ -1,6 +1,6 @@
 use super::build_error::*;
 use super::install::{InstallPhase, OnPhaseFn};
-use crate::config::ProtoConfig;
+use crate::config::{BuildSandbox, ProtoConfig};
 use crate::env::ProtoEnvironment;
 use crate::id::Id;
 use crate::lockfile::LockRecord;
@@ -8,6 +8,7 @@ use crate::reporter::ProtoConsole;
 use crate::tool::Tool;
 use crate::utils::log::LogWriter;
 use crate::utils::process::{self, ProcessResult, ProtoProcessError};
+use crate::utils::sandbox::BuildSandboxRunner;
 use crate::utils::{archive, git};
 use iocraft::prelude::{FlexDirection, View, element};
 use proto_pdk_api::{
@@ -23,7 +24,7 @@ use starbase_styles::{apply_style_tags, color, remove_style_tags};
 use starbase_utils::fs::LOCK_FILE;
 use starbase_utils::net::DownloadOptions;
 use starbase_utils::{envx::is_ci, fs, net, path};
-use std::env;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
 use system_env::{
@@ -32,7 +33,7 @@ use system_env::{
 };
 use tokio::process::Command;
 use tokio::sync::{Mutex, OwnedMutexGuard};
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, instrument, warn};
 use version_spec::{MatchesVersion, Requirement, Version, VersionSpec};
 use warpgate::{HttpClient, extract_file_name_from_url};
 
@@ -55,25 +56,39 @@ pub struct BuilderOptions<'a> {
 pub struct Builder<'tool> {
     pub options: BuilderOptions<'tool>,
     errors: u8,
+    sandbox: BuildSandboxRunner,
     tool: &'tool Tool,
 }
 
 impl<'tool> Builder<'tool> {
     pub fn new(tool: &'tool Tool, options: BuilderOptions<'tool>) -> Self {
+        let sandbox = BuildSandboxRunner::new(
+            options.config.settings.build.sandbox,
+            &options.temp_dir.join("sandbox"),
+            vec![
+                options.temp_dir.to_path_buf(),
+                options.install_dir.to_path_buf(),
+            ],
+        );
+
         Builder {
             errors: 0,
             options,
+            sandbox,
             tool,
         }
     }
 
-    pub async fn build(
-        mut self,
-        output: BuildInstructionsOutput,
-    ) -> Result<LockRecord, ProtoBuildError> {
+    /// Prepare the host for building, by logging information, installing
+    /// system dependencies, and checking requirements. This must run before
+    /// [`Builder::build`], and before restoring a build from the cache,
+    /// as the built output may depend on these at runtime.
+    pub async fn prepare(
+        &mut self,
+        output: &BuildInstructionsOutput,
+    ) -> Result<(), ProtoBuildError> {
         let context = &self.tool.context;
         let proto = &self.tool.proto;
-        let mut record = self.tool.create_locked_record();
 
         // The build process may require using itself to build itself,
         // so allow proto to use any available version instead of failing
@@ -83,7 +98,7 @@ impl<'tool> Builder<'tool> {
         proto.create_metric().record_tool_install_step(
             context,
             "build_information",
-            self.log_build_information(&output),
+            self.log_build_information(output),
         )?;
 
         // Step 1
@@ -91,7 +106,7 @@ impl<'tool> Builder<'tool> {
             proto.create_metric().record_tool_install_step(
                 context,
                 "install_system_dependencies",
-                self.install_system_dependencies(&output).await,
+                self.install_system_dependencies(output).await,
             )?;
         } else {
             debug!(
@@ -105,9 +120,20 @@ impl<'tool> Builder<'tool> {
         proto.create_metric().record_tool_install_step(
             context,
             "check_requirements",
-            self.check_requirements(&output).await,
+            self.check_requirements(output).await,
         )?;
 
+        Ok(())
+    }
+
+    pub async fn build(
+        mut self,
+        output: BuildInstructionsOutput,
+    ) -> Result<LockRecord, ProtoBuildError> {
+        let context = &self.tool.context;
+        let proto = &self.tool.proto;
+        let mut record = self.tool.create_locked_record();
+
         // Step 3
         proto.create_metric().record_tool_install_step(
             context,
@@ -368,6 +394,7 @@ impl Builder<'_> {
                 arch = ?system.arch,
                 pm = ?system.manager,
                 version = self.options.version.to_string(),
+                sandbox = self.sandbox.mode.to_string(),
                 "Gathering system information",
             );
         } else if let Some(console) = self.options.console {
@@ -385,6 +412,11 @@ impl Builder<'_> {
                         Entry(name: "Target version", value: element! {
                             StyledText(content: self.options.version.to_string(), style: Style::Hash)
                         }.into_any())
+                        #(self.sandbox.is_enabled().then(|| {
+                            element! {
+                                Entry(name: "Sandbox", content: self.sandbox.mode.to_string())
+                            }
+                        }))
                         #(build.help_url.as_ref().map(|url| {
                             element! {
                                 Entry(name: "Documentation", value: element! {
@@ -410,6 +442,48 @@ enum InstallSystemDepsChoice {
     YesButElevated = 3,
 }
 
+/// Resolve the system dependencies of the build into package names (and
+/// optional version requirements) for the package manager of the provided
+/// system, without packages that have been excluded.
+pub fn resolve_system_packages(
+    system: &System,
+    build: &BuildInstructionsOutput,
+    excluded_packages: &[String],
+) -> BTreeMap<String, Option<String>> {
+    let Some(pm) = &system.manager else {
+        return BTreeMap::default();
+    };
+
+    let mut packages = BTreeMap::from_iter(
+        system
+            .resolve_dependencies(&build.system_dependencies)
+            .iter()
+            .filter_map(|cfg| cfg.get_package_names_and_versions(pm).ok())
+            .flatten(),
+    );
+
+    for excluded in excluded_packages {
+        packages.remove(excluded);
+    }
+
+    packages
+}
+
+/// Convert resolved package names into dependency configs,
+/// for use in package manager commands.
+pub fn create_dependency_configs(
+    packages: BTreeMap<String, Option<String>>,
+) -> Vec<DependencyConfig> {
+    packages
+        .into_iter()
+        .map(|(name, version)| DependencyConfig {
+            dep: DependencyName::Single(name),
+            version,
+            ..Default::default()
+        })
+        .collect()
+}
+
 impl Builder<'_> {
     #[instrument(skip(self))]
     async fn install_system_dependencies(
@@ -422,22 +496,14 @@ impl Builder<'_> {
 
         // Determine packages to install
         let pm_config = pm.get_config();
-        let dep_configs = self
-            .get_system()
-            .resolve_dependencies(&build.system_dependencies);
 
         // 1) Check if packages have already been installed
-        let mut not_installed_packages = FxHashMap::from_iter(
-            dep_configs
-                .iter()
-                .filter_map(|cfg| cfg.get_package_names_and_versions(&pm).ok())
-                .flatten(),
+        let mut not_installed_packages = resolve_system_packages(
+            self.get_system(),
+            build,
+            &self.options.config.settings.build.exclude_packages,
         );
 
-        for excluded in &self.options.config.settings.build.exclude_packages {
-            not_installed_packages.remove(excluded);
-        }
-
         if not_installed_packages.is_empty() {
             return Ok(());
         }
@@ -587,14 +653,7 @@ impl Builder<'_> {
         }
 
         // Recreate the dep configs since they've been filtered
-        let dep_configs = not_installed_packages
-            .into_iter()
-            .map(|(name, version)| DependencyConfig {
-                dep: DependencyName::Single(name),
-                version,
-                ..Default::default()
-            })
-            .collect::<Vec<_>>();
+        let dep_configs = create_dependency_configs(not_installed_packages);
 
         // 4) Install the missing packages
         if let Some(mut install_args) = self
@@ -898,6 +957,20 @@ impl Builder<'_> {
 
 // STEP 4
 
+fn get_instruction_name(instruction: &BuildInstruction) -> &'static str {
+    match instruction {
+        BuildInstruction::InstallBuilder(_) => "install-builder",
+        BuildInstruction::MakeExecutable(_) => "make-executable",
+        BuildInstruction::MoveFile(_, _) => "move-file",
+        BuildInstruction::RemoveAllExcept(_) => "remove-all-except",
+        BuildInstruction::RemoveDir(_) => "remove-dir",
+        BuildInstruction::RemoveFile(_) => "remove-file",
+        BuildInstruction::RequestScript(_) => "request-script",
+        BuildInstruction::RunCommand(_) => "run-command",
+        BuildInstruction::SetEnvVar(_, _) => "set-env-var",
+    }
+}
+
 impl Builder<'_> {
     #[instrument(skip(self))]
     async fn execute_instructions(
@@ -915,15 +988,7 @@ impl Builder<'_> {
             func(InstallPhase::ExecuteInstructions);
         });
 
-        let make_absolute = |path: &Path| {
-            if path.is_absolute() {
-                PathBuf::from(path::normalize_separators(path))
-            } else {
-                self.options
-                    .install_dir
-                    .join(path::normalize_separators(path))
-            }
-        };
+        self.prepare_sandbox()?;
 
         let total = build.instructions.len();
         let mut builder_exes = FxHashMap::default();
@@ -932,172 +997,241 @@ impl Builder<'_> {
             debug!("Executing build instruction {} of {total}", index + 1);
 
             let prefix = format!("<mutedlight>[{}/{total}]</mutedlight>", index + 1);
+            let result = self
+                .execute_instruction(instruction, &prefix, build, proto, &mut builder_exes)
+                .await;
+
+            let log = self.options.log_writer;
+            log.add_subsection(format!(
+                "Instruction {}/{total}: {}",
+                index + 1,
+                get_instruction_name(instruction)
+            ));
+            log.add_value("STATUS", if result.is_ok() { "success" } else { "failed" });
+
+            if let Err(error) = &result {
+                log.add_error(error);
+            }
 
-            match instruction {
-                BuildInstruction::InstallBuilder(item) => {
-                    self.render_checkpoint(format!(
-                        "{prefix} Installing <id>{}</id> builder (<url>{}<url>)",
-                        item.id, item.git.url
-                    ))?;
+            result?;
+        }
 
-                    let builder_dir = proto.store.builders_dir.join(item.id.as_str());
+        Ok(())
+    }
 
-                    checkout_git_repo(&item.git, &builder_dir, self).await?;
+    fn prepare_sandbox(&mut self) -> Result<(), ProtoBuildError> {
+        if matches!(self.sandbox.mode, BuildSandbox::Bubblewrap) {
+            if !cfg!(target_os = "linux") {
+                warn!(
+                    tool = self.tool.context.as_str(),
+                    "The bubblewrap sandbox is only supported on Linux, falling back to the env sandbox"
+                );
+
+                self.sandbox.mode = BuildSandbox::Env;
+            } else if !is_command_on_path("bwrap") {
+                return Err(ProtoBuildError::MissingSandboxProgram {
+                    program: "bwrap".into(),
+                });
+            }
+        }
 
-                    let main_exe_name = String::new();
-                    let mut exes = FxHashMap::default();
-                    exes.extend(&item.exes);
-                    exes.insert(&main_exe_name, &item.exe);
+        if self.sandbox.is_enabled() {
+            debug!(
+                tool = self.tool.context.as_str(),
+                mode = self.sandbox.mode.to_string(),
+                home_dir = ?self.sandbox.home_dir,
+                tmp_dir = ?self.sandbox.tmp_dir,
+                "Executing build instructions in a sandbox",
+            );
 
-                    for (exe_name, exe_rel_path) in exes {
-                        let exe_abs_path =
-                            builder_dir.join(path::normalize_separators(exe_rel_path));
+            self.options
+                .log_writer
+                .add_value("SANDBOX", self.sandbox.mode.to_string());
+        }
 
-                        if !exe_abs_path.exists() {
-                            return Err(ProtoBuildError::MissingBuilderExe {
-                                exe: exe_abs_path,
-                                id: item.id.clone(),
-                            });
-                        }
+        self.sandbox.prepare()?;
 
-                        if !fs::is_executable(&exe_abs_path) {
-                            fs::update_perms(&exe_abs_path, None)?;
-                        }
+        Ok(())
+    }
 
-                        builder_exes.insert(
-                            if exe_name.is_empty() {
-                                item.id.to_string()
-                            } else {
-                                format!("{}:{exe_name}", item.id)
-                            },
-                            exe_abs_path,
-                        );
-                    }
-                }
-                BuildInstruction::MakeExecutable(file) => {
-                    let file = make_absolute(file);
+    async fn execute_instruction(
+        &mut self,
+        instruction: &BuildInstruction,
+        prefix: &str,
+        build: &BuildInstructionsOutput,
+        proto: &ProtoEnvironment,
+        builder_exes: &mut FxHashMap<String, PathBuf>,
+    ) -> Result<(), ProtoBuildError> {
+        let install_dir = self.options.install_dir;
+        let make_absolute = |path: &Path| {
+            if path.is_absolute() {
+                PathBuf::from(path::normalize_separators(path))
+            } else {
+                install_dir.join(path::normalize_separators(path))
+            }
+        };
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Making file <path>{}</path> executable",
-                        file.display()
-                    ))?;
+        match instruction {
+            BuildInstruction::InstallBuilder(item) => {
+                self.render_checkpoint(format!(
+                    "{prefix} Installing <id>{}</id> builder (<url>{}<url>)",
+                    item.id, item.git.url
+                ))?;
 
-                    fs::update_perms(file, None)?;
-                }
-                BuildInstruction::MoveFile(from, to) => {
-                    let from = make_absolute(from);
-                    let to = make_absolute(to);
+                let builder_dir = proto.store.builders_dir.join(item.id.as_str());
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Moving <path>{}</path> to <path>{}</path>",
-                        from.display(),
-                        to.display(),
-                    ))?;
+                checkout_git_repo(&item.git, &builder_dir, self).await?;
 
-                    fs::rename(from, to)?;
-                }
-                BuildInstruction::RemoveAllExcept(exceptions) => {
-                    let dir = self.options.install_dir;
+                let main_exe_name = String::new();
+                let mut exes = FxHashMap::default();
+                exes.extend(&item.exes);
+                exes.insert(&main_exe_name, &item.exe);
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Removing directory <path>{}</path> except for {}",
-                        dir.display(),
-                        exceptions
-                            .iter()
-                            .map(|p| format!("<file>{}</file>", p.display()))
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    ))?;
+                for (exe_name, exe_rel_path) in exes {
+                    let exe_abs_path = builder_dir.join(path::normalize_separators(exe_rel_path));
 
-                    let mut exclude = exceptions.to_owned();
+                    if !exe_abs_path.exists() {
+                        return Err(ProtoBuildError::MissingBuilderExe {
+                            exe: exe_abs_path,
+                            id: item.id.clone(),
+                        });
+                    }
 
-                    // If we don't exclude the lock, it will trigger a permissions error
-                    // when we attempt to remove it, failing the entire build
-                    exclude.push(LOCK_FILE.into());
+                    if !fs::is_executable(&exe_abs_path) {
+                        fs::update_perms(&exe_abs_path, None)?;
+                    }
 
-                    fs::remove_dir_all_except(dir, exclude)?;
+                    builder_exes.insert(
+                        if exe_name.is_empty() {
+                            item.id.to_string()
+                        } else {
+                            format!("{}:{exe_name}", item.id)
+                        },
+                        exe_abs_path,
+                    );
                 }
-                BuildInstruction::RemoveDir(dir) => {
-                    let dir = make_absolute(dir);
+            }
+            BuildInstruction::MakeExecutable(file) => {
+                let file = make_absolute(file);
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Removing directory <path>{}</path>",
-                        dir.display()
-                    ))?;
+                self.render_checkpoint(format!(
+                    "{prefix} Making file <path>{}</path> executable",
+                    file.display()
+                ))?;
 
-                    fs::remove_dir_all(dir)?;
-                }
-                BuildInstruction::RemoveFile(file) => {
-                    let file = make_absolute(file);
+                fs::update_perms(file, None)?;
+            }
+            BuildInstruction::MoveFile(from, to) => {
+                let from = make_absolute(from);
+                let to = make_absolute(to);
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Removing file <path>{}</path>",
-                        file.display()
-                    ))?;
+                self.render_checkpoint(format!(
+                    "{prefix} Moving <path>{}</path> to <path>{}</path>",
+                    from.display(),
+                    to.display(),
+                ))?;
 
-                    fs::remove_file(file)?;
-                }
-                BuildInstruction::RequestScript(url) => {
-                    let url = self.options.config.rewrite_url(url);
-                    let filename = extract_file_name_from_url(&url);
-                    let download_file = self.options.temp_dir.join(&filename);
+                fs::rename(from, to)?;
+            }
+            BuildInstruction::RemoveAllExcept(exceptions) => {
+                let dir = self.options.install_dir;
+
+                self.render_checkpoint(format!(
+                    "{prefix} Removing directory <path>{}</path> except for {}",
+                    dir.display(),
+                    exceptions
+                        .iter()
+                        .map(|p| format!("<file>{}</file>", p.display()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))?;
+
+                let mut exclude = exceptions.to_owned();
+
+                // If we don't exclude the lock, it will trigger a permissions error
+                // when we attempt to remove it, failing the entire build
+                exclude.push(LOCK_FILE.into());
+
+                fs::remove_dir_all_except(dir, exclude)?;
+            }
+            BuildInstruction::RemoveDir(dir) => {
+                let dir = make_absolute(dir);
 
-                    self.render_checkpoint(format!("{prefix} Requesting script <url>{url}</url>"))?;
+                self.render_checkpoint(format!(
+                    "{prefix} Removing directory <path>{}</path>",
+                    dir.display()
+                ))?;
 
-                    net::download_from_url_with_options(
-                        &url,
-                        &download_file,
-                        DownloadOptions::new(
-                            self.options
-                                .http_client
-                                .create_downloader_with_headers(build.http_headers.clone()),
-                        ),
-                    )
-                    .await?;
+                fs::remove_dir_all(dir)?;
+            }
+            BuildInstruction::RemoveFile(file) => {
+                let file = make_absolute(file);
 
-                    fs::rename(download_file, self.options.install_dir.join(filename))?;
-                }
-                BuildInstruction::RunCommand(cmd) => {
-                    let exe = if cmd.builder {
-                        builder_exes.get(&cmd.exe).cloned().ok_or_else(|| {
-                            ProtoBuildError::MissingBuilder {
-                                id: Id::raw(&cmd.exe),
-                            }
-                        })?
-                    } else {
-                        PathBuf::from(&cmd.exe)
-                    };
+                self.render_checkpoint(format!(
+                    "{prefix} Removing file <path>{}</path>",
+                    file.display()
+                ))?;
 
-                    self.render_checkpoint(format!(
-                        "{prefix} Running command <shell>{} {}</shell>",
-                        fs::file_name(&exe),
-                        shell_words::join(&cmd.args)
-                    ))?;
+                fs::remove_file(file)?;
+            }
+            BuildInstruction::RequestScript(url) => {
+                let url = self.options.config.rewrite_url(url);
+                let filename = extract_file_name_from_url(&url);
+                let download_file = self.options.temp_dir.join(&filename);
+
+                self.render_checkpoint(format!("{prefix} Requesting script <url>{url}</url>"))?;
+
+                net::download_from_url_with_options(
+                    &url,
+                    &download_file,
+                    DownloadOptions::new(
+                        self.options
+                            .http_client
+                            .create_downloader_with_headers(build.http_headers.clone()),
+                    ),
+                )
+                .await?;
 
-                    self.exec_command(
-                        Command::new(exe)
-                            .args(&cmd.args)
-                            .envs(&cmd.env)
-                            .current_dir(
-                                cmd.cwd
-                                    .as_deref()
-                                    .map(|cwd| self.tool.to_real_path(cwd))
-                                    .map(|cwd| make_absolute(&cwd))
-                                    .unwrap_or_else(|| self.options.install_dir.to_path_buf()),
-                            ),
-                        false,
-                    )
-                    .await?;
-                }
-                BuildInstruction::SetEnvVar(key, value) => {
-                    self.render_checkpoint(format!(
-                        "{prefix} Setting environment variable <property>{key}</property> to <symbol>{value}</symbol>",
-                    ))?;
+                fs::rename(download_file, self.options.install_dir.join(filename))?;
+            }
+            BuildInstruction::RunCommand(cmd) => {
+                let exe = if cmd.builder {
+                    builder_exes.get(&cmd.exe).cloned().ok_or_else(|| {
+                        ProtoBuildError::MissingBuilder {
+                            id: Id::raw(&cmd.exe),
+                        }
+                    })?
+                } else {
+                    PathBuf::from(&cmd.exe)
+                };
 
-                    unsafe { env::set_var(key, value) };
-                }
-            };
-        }
+                self.render_checkpoint(format!(
+                    "{prefix} Running command <shell>{} {}</shell>",
+                    fs::file_name(&exe),
+                    shell_words::join(&cmd.args)
+                ))?;
+
+                let cwd = cmd
+                    .cwd
+                    .as_deref()
+                    .map(|cwd| self.tool.to_real_path(cwd))
+                    .map(|cwd| make_absolute(&cwd))
+                    .unwrap_or_else(|| install_dir.to_path_buf());
+
+                let mut command = self.sandbox.create_command(&exe, &cmd.args, &cmd.env, &cwd);
+
+                self.exec_command(&mut command, false).await?;
+            }
+            BuildInstruction::SetEnvVar(key, value) => {
+                self.render_checkpoint(format!(
+                    "{prefix} Setting environment variable <property>{key}</property> to <symbol>{value}</symbol>",
+                ))?;
+
+                // Passed to subsequent commands, instead of
+                // modifying the environment of the host process
+                self.sandbox.set_env_var(key, value);
+            }
+        };
 
         Ok(())
     }
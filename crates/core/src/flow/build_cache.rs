@@ -0,0 +1,768 @@
+pub use super::build_cache_error::ProtoBuildCacheError;
+use crate::config::ProtoBuildCacheConfig;
+use crate::lockfile::LockRecord;
+use crate::tool::Tool;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use proto_pdk_api::BuildInstructionsOutput;
+use reqwest::{Body, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use starbase_archive::Archiver;
+use starbase_utils::fs::{self, FsError};
+use starbase_utils::{json, path};
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use system_env::SystemLibc;
+use tracing::{debug, instrument, warn};
+use version_spec::VersionSpec;
+use warpgate::HttpClient;
+
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Inputs that uniquely identify the output of a build from source.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BuildCacheKey {
+    pub tool: String,
+    pub version: String,
+    pub plugin_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub libc: String,
+    pub instructions_hash: String,
+}
+
+impl BuildCacheKey {
+    pub fn new(
+        tool: &Tool,
+        version: &VersionSpec,
+        build: &BuildInstructionsOutput,
+    ) -> Result<Self, ProtoBuildCacheError> {
+        let os = tool.proto.os;
+
+        Ok(Self {
+            tool: tool.context.to_string(),
+            version: version.to_string(),
+            plugin_version: tool.metadata.plugin_version.as_ref().map(|v| v.to_string()),
+            os: os.to_string(),
+            arch: tool.proto.arch.to_string(),
+            libc: SystemLibc::detect(os).to_string(),
+            instructions_hash: hash_build_instructions(build)?,
+        })
+    }
+
+    /// Hash all fields of the key into a SHA256 digest.
+    pub fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+
+        for value in [
+            self.tool.as_str(),
+            self.version.as_str(),
+            self.plugin_version.as_deref().unwrap_or_default(),
+            self.os.as_str(),
+            self.arch.as_str(),
+            self.libc.as_str(),
+            self.instructions_hash.as_str(),
+        ] {
+            hasher.update(value.as_bytes());
+            hasher.update([0]);
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Return the file name of the archive that is stored in the cache.
+    pub fn to_archive_name(&self) -> String {
+        format!(
+            "{}-{}-{}-{}-{}.tar.gz",
+            path::encode_component(&self.tool),
+            path::encode_component(&self.version),
+            self.os,
+            self.arch,
+            &self.hash()[0..32],
+        )
+    }
+}
+
+/// Hash the parts of the build instructions that affect the built output.
+/// HTTP headers are excluded, as they may contain short-lived credentials.
+pub fn hash_build_instructions(
+    build: &BuildInstructionsOutput,
+) -> Result<String, ProtoBuildCacheError> {
+    let build = BuildInstructionsOutput {
+        help_url: None,
+        http_headers: Default::default(),
+        ..build.to_owned()
+    };
+
+    Ok(hex::encode(Sha256::digest(json::format(&build, false)?)))
+}
+
+/// Credentials for signing requests to an S3-compatible storage.
+#[derive(Clone, Debug, Default)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl S3Credentials {
+    /// Load credentials from the standard `AWS_*` environment variables.
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            access_key_id: env::var("AWS_ACCESS_KEY_ID").ok()?,
+            secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").ok()?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildCacheRemote {
+    Http {
+        url: String,
+    },
+    S3 {
+        endpoint: String,
+        bucket: String,
+        prefix: String,
+        region: String,
+    },
+}
+
+impl BuildCacheRemote {
+    pub fn parse(config: &ProtoBuildCacheConfig) -> Result<Option<Self>, ProtoBuildCacheError> {
+        let Some(remote) = &config.remote else {
+            return Ok(None);
+        };
+
+        if remote.starts_with("http://") || remote.starts_with("https://") {
+            return Ok(Some(Self::Http {
+                url: remote.trim_end_matches('/').to_owned(),
+            }));
+        }
+
+        if let Some(location) = remote.strip_prefix("s3://") {
+            let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+
+            if !bucket.is_empty() {
+                let region = config
+                    .s3_region
+                    .clone()
+                    .or_else(|| env::var("AWS_REGION").ok())
+                    .or_else(|| env::var("AWS_DEFAULT_REGION").ok())
+                    .unwrap_or_else(|| "us-east-1".into());
+
+                return Ok(Some(Self::S3 {
+                    endpoint: config
+                        .s3_endpoint
+                        .clone()
+                        .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"))
+                        .trim_end_matches('/')
+                        .to_owned(),
+                    bucket: bucket.to_owned(),
+                    prefix: prefix.trim_matches('/').to_owned(),
+                    region,
+                }));
+            }
+        }
+
+        Err(ProtoBuildCacheError::InvalidRemote {
+            remote: remote.to_owned(),
+        })
+    }
+
+    /// Return the URL of the archive within the remote. S3 uses path-style
+    /// addressing, as it's supported by all S3-compatible storages.
+    pub fn get_archive_url(&self, archive_name: &str) -> String {
+        match self {
+            Self::Http { url } => format!("{url}/{archive_name}"),
+            Self::S3 {
+                endpoint,
+                bucket,
+                prefix,
+                ..
+            } => {
+                if prefix.is_empty() {
+                    format!("{endpoint}/{bucket}/{archive_name}")
+                } else {
+                    format!("{endpoint}/{bucket}/{prefix}/{archive_name}")
+                }
+            }
+        }
+    }
+
+    /// Return the headers required for the request, which includes
+    /// an AWS signature (v4) for S3 when credentials are available.
+    pub fn get_headers(&self, method: &str, url: &str) -> Vec<(String, String)> {
+        let Self::S3 { region, .. } = self else {
+            return vec![];
+        };
+
+        let Some(credentials) = S3Credentials::from_env() else {
+            return vec![];
+        };
+
+        let Ok(url) = url::Url::parse(url) else {
+            return vec![];
+        };
+
+        let mut headers = BTreeMap::from_iter([
+            ("host".to_owned(), get_host_header(&url)),
+            (
+                "x-amz-content-sha256".to_owned(),
+                UNSIGNED_PAYLOAD.to_owned(),
+            ),
+            ("x-amz-date".to_owned(), format_amz_date(SystemTime::now())),
+        ]);
+
+        if let Some(token) = &credentials.session_token {
+            headers.insert("x-amz-security-token".into(), token.to_owned());
+        }
+
+        let authorization =
+            create_s3_authorization(method, url.path(), &headers, region, &credentials);
+
+        headers.remove("host");
+        headers.insert("authorization".into(), authorization);
+        headers.into_iter().collect()
+    }
+}
+
+fn get_host_header(url: &url::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_owned(),
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Format the timestamp as an ISO 8601 basic format, as required by AWS.
+pub fn format_amz_date(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// A digest of an archive and its lock record, which is a SHA256 checksum,
+/// or an HMAC-SHA256 signature when a signing key is configured, so that
+/// archives in a shared remote cannot be replaced without also knowing the key.
+///
+/// Without a signing key, the checksum is downloaded from the same remote as
+/// the archive, so it only detects corruption (like a partial upload), and
+/// not tampering by anyone with write access to the remote.
+pub enum BuildArchiveDigest {
+    Checksum(Sha256),
+    Signature(Box<Hmac<Sha256>>),
+}
+
+impl BuildArchiveDigest {
+    pub fn new(signing_key: Option<&str>) -> Self {
+        match signing_key {
+            Some(key) => Self::Signature(Box::new(
+                Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                    .expect("HMAC accepts keys of any size"),
+            )),
+            None => Self::Checksum(Sha256::new()),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Checksum(hasher) => Digest::update(hasher, data),
+            Self::Signature(mac) => Mac::update(mac.as_mut(), data),
+        }
+    }
+
+    pub fn finalize(self) -> String {
+        match self {
+            Self::Checksum(hasher) => hex::encode(hasher.finalize()),
+            Self::Signature(mac) => hex::encode(mac.finalize().into_bytes()),
+        }
+    }
+
+    /// Verify the digest against the expected hex value, in constant time for signatures.
+    pub fn verify(self, expected: &str) -> bool {
+        let Ok(expected) = hex::decode(expected.trim()) else {
+            return false;
+        };
+
+        match self {
+            Self::Checksum(hasher) => hasher.finalize().as_slice() == expected.as_slice(),
+            Self::Signature(mac) => mac.verify_slice(&expected).is_ok(),
+        }
+    }
+}
+
+/// Create an `Authorization` header value for an S3 request, using the AWS
+/// signature (v4) algorithm. The provided headers are signed, and must include
+/// `host`, `x-amz-date`, and `x-amz-content-sha256`, with lowercase names.
+pub fn create_s3_authorization(
+    method: &str,
+    path: &str,
+    headers: &BTreeMap<String, String>,
+    region: &str,
+    credentials: &S3Credentials,
+) -> String {
+    let amz_date = headers.get("x-amz-date").map(|v| v.as_str()).unwrap_or("");
+    let date = &amz_date[0..8.min(amz_date.len())];
+    let scope = format!("{date}/{region}/s3/aws4_request");
+
+    let canonical_headers = headers
+        .iter()
+        .map(|(key, value)| format!("{key}:{}\n", value.trim()))
+        .collect::<String>();
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{method}\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        headers
+            .get("x-amz-content-sha256")
+            .map(|v| v.as_str())
+            .unwrap_or(UNSIGNED_PAYLOAD)
+    );
+
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+        credentials.access_key_id,
+        hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()))
+    )
+}
+
+/// Stores the product directories of tools built from source as archives,
+/// in a local directory and/or a remote HTTP or S3-compatible storage,
+/// so that subsequent installs can skip the build entirely. The lock record
+/// of the build (source checksum, etc) is stored alongside each archive.
+pub struct BuildCache<'a> {
+    pub archive_name: String,
+    pub local_dir: Option<PathBuf>,
+    pub remote: Option<BuildCacheRemote>,
+    pub s3_unlistable: bool,
+    pub signing_key: Option<String>,
+    pub upload: bool,
+
+    http_client: &'a HttpClient,
+}
+
+impl<'a> BuildCache<'a> {
+    pub fn new(
+        key: &BuildCacheKey,
+        config: &ProtoBuildCacheConfig,
+        cache_dir: &Path,
+        http_client: &'a HttpClient,
+    ) -> Result<Self, ProtoBuildCacheError> {
+        Ok(Self {
+            archive_name: key.to_archive_name(),
+            local_dir: config.local.then(|| cache_dir.join("builds")),
+            remote: BuildCacheRemote::parse(config)?,
+            s3_unlistable: config.s3_unlistable,
+            signing_key: config.signing_key.clone(),
+            upload: config.upload,
+            http_client,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.local_dir.is_some() || self.remote.is_some()
+    }
+
+    /// Return the file name of the lock record that is stored next to the archive.
+    pub fn get_record_name(&self) -> String {
+        format!("{}.json", self.archive_name)
+    }
+
+    /// Return the file name of the checksum that is stored next to the archive.
+    pub fn get_checksum_name(&self) -> String {
+        format!("{}.sha256", self.archive_name)
+    }
+
+    /// Attempt to restore a previously built archive into the product directory,
+    /// first from the local cache, and then from the remote cache.
+    /// Returns the lock record of the build if the archive was found and unpacked.
+    #[instrument(skip(self))]
+    pub async fn restore(
+        &self,
+        product_dir: &Path,
+        temp_dir: &Path,
+    ) -> Result<Option<LockRecord>, ProtoBuildCacheError> {
+        if let Some(local_dir) = &self.local_dir {
+            let archive_file = local_dir.join(&self.archive_name);
+            let record_file = local_dir.join(self.get_record_name());
+
+            let checksum_file = local_dir.join(self.get_checksum_name());
+
+            if archive_file.exists() && record_file.exists() {
+                let record_content = fs::read_file(&record_file)?;
+
+                // Archives are only trusted if they are unchanged since being stored,
+                // otherwise they are removed, so that they can be replaced
+                if checksum_file.exists()
+                    && fs::read_file(&checksum_file)?.trim()
+                        == self.create_digest(&archive_file, &record_content, None)?
+                {
+                    debug!(archive_file = ?archive_file, "Restoring build from local cache");
+
+                    let record: LockRecord = json::parse(&record_content)?;
+
+                    self.unpack(product_dir, &archive_file)?;
+
+                    return Ok(Some(record));
+                }
+
+                warn!(
+                    archive_file = ?archive_file,
+                    "Build in local cache failed verification, removing it",
+                );
+
+                fs::remove_file(&archive_file)?;
+                fs::remove_file(&record_file)?;
+                fs::remove_file(&checksum_file)?;
+            }
+        }
+
+        let Some(remote) = &self.remote else {
+            return Ok(None);
+        };
+
+        let url = remote.get_archive_url(&self.archive_name);
+        let record_url = remote.get_archive_url(&self.get_record_name());
+        let archive_file = temp_dir.join(&self.archive_name);
+
+        debug!(url = &url, "Attempting to restore build from remote cache");
+
+        // Archives without a digest are never trusted
+        let checksum_url = format!("{url}.sha256");
+
+        let (Some(checksum), Some(record_content)) = (
+            self.send_get_text_request(remote, &checksum_url).await?,
+            self.send_get_text_request(remote, &record_url).await?,
+        ) else {
+            debug!(url = &url, "Build does not exist in remote cache");
+
+            return Ok(None);
+        };
+
+        let Some(mut response) = self.send_get_request(remote, &url).await? else {
+            debug!(url = &url, "Build does not exist in remote cache");
+
+            return Ok(None);
+        };
+
+        // Stream the archive to disk, while creating a digest of its contents
+        let mut digest = BuildArchiveDigest::new(self.signing_key.as_deref());
+        let mut file = fs::create_file(&archive_file)?;
+
+        while let Some(chunk) =
+            response
+                .chunk()
+                .await
+                .map_err(|error| warpgate::WarpgateHttpClientError::Http {
+                    url: url.clone(),
+                    error: Box::new(error),
+                })?
+        {
+            digest.update(&chunk);
+
+            file.write_all(&chunk).map_err(|error| FsError::Write {
+                path: archive_file.clone(),
+                error: Box::new(error),
+            })?;
+        }
+
+        drop(file);
+
+        // The record is part of the digest, so that it can't be replaced either
+        digest.update(record_content.as_bytes());
+
+        if !digest.verify(&checksum) {
+            fs::remove_file(&archive_file)?;
+
+            return Err(ProtoBuildCacheError::ChecksumMismatch { url });
+        }
+
+        let record: LockRecord = json::parse(&record_content)?;
+
+        self.unpack(product_dir, &archive_file)?;
+
+        // Persist to the local cache to avoid subsequent requests
+        if let Some(local_dir) = &self.local_dir {
+            self.save_local(local_dir, &archive_file, &record_content)?;
+        }
+
+        fs::remove_file(&archive_file)?;
+
+        Ok(Some(record))
+    }
+
+    /// Pack the product directory into an archive, and store it with the lock
+    /// record in the local cache, and the remote cache when uploading is enabled.
+    #[instrument(skip(self, record))]
+    pub async fn save(
+        &self,
+        product_dir: &Path,
+        temp_dir: &Path,
+        record: &LockRecord,
+    ) -> Result<(), ProtoBuildCacheError> {
+        let upload_remote = self.remote.as_ref().filter(|_| self.upload);
+
+        if self.local_dir.is_none() && upload_remote.is_none() {
+            return Ok(());
+        }
+
+        let archive_file = temp_dir.join(&self.archive_name);
+        let record_content = json::format(record, true)?;
+
+        debug!(archive_file = ?archive_file, "Packing build into an archive");
+
+        let mut archiver = Archiver::new(product_dir, &archive_file);
+        archiver.add_source_file(product_dir, None);
+        archiver.pack_from_ext()?;
+
+        if let Some(local_dir) = &self.local_dir {
+            self.save_local(local_dir, &archive_file, &record_content)?;
+        }
+
+        if let Some(remote) = upload_remote {
+            let url = remote.get_archive_url(&self.archive_name);
+
+            debug!(url = &url, "Uploading build to remote cache");
+
+            // Upload the digest last, so that a partially uploaded
+            // archive is never considered valid
+            let checksum =
+                self.create_digest(&archive_file, &record_content, self.signing_key.as_deref())?;
+
+            self.send_put_file_request(remote, &url, &archive_file)
+                .await?;
+            self.send_put_request(
+                remote,
+                &remote.get_archive_url(&self.get_record_name()),
+                record_content.into_bytes(),
+            )
+            .await?;
+            self.send_put_request(remote, &format!("{url}.sha256"), checksum.into_bytes())
+                .await?;
+        }
+
+        fs::remove_file(&archive_file)?;
+
+        Ok(())
+    }
+
+    /// Copy the archive and write the lock record into the local cache,
+    /// with a checksum of both, that is verified when restoring.
+    fn save_local(
+        &self,
+        local_dir: &Path,
+        archive_file: &Path,
+        record_content: &str,
+    ) -> Result<(), ProtoBuildCacheError> {
+        let checksum = self.create_digest(archive_file, record_content, None)?;
+
+        fs::copy_file(archive_file, local_dir.join(&self.archive_name))?;
+        fs::write_file(local_dir.join(self.get_record_name()), record_content)?;
+        fs::write_file(local_dir.join(self.get_checksum_name()), checksum)?;
+
+        Ok(())
+    }
+
+    fn create_digest(
+        &self,
+        archive_file: &Path,
+        record_content: &str,
+        signing_key: Option<&str>,
+    ) -> Result<String, ProtoBuildCacheError> {
+        let mut digest = BuildArchiveDigest::new(signing_key);
+        let mut file = fs::open_file(archive_file)?;
+        let mut buffer = vec![0; 65536];
+
+        loop {
+            let size = file.read(&mut buffer).map_err(|error| FsError::Read {
+                path: archive_file.to_path_buf(),
+                error: Box::new(error),
+            })?;
+
+            if size == 0 {
+                break;
+            }
+
+            digest.update(&buffer[0..size]);
+        }
+
+        digest.update(record_content.as_bytes());
+
+        Ok(digest.finalize())
+    }
+
+    /// Send a `GET` request to the remote, and return the response,
+    /// or `None` if the file does not exist.
+    async fn send_get_request(
+        &self,
+        remote: &BuildCacheRemote,
+        url: &str,
+    ) -> Result<Option<Response>, ProtoBuildCacheError> {
+        let mut request = self.http_client.get(url);
+
+        for (key, value) in remote.get_headers("GET", url) {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| HttpClient::map_error(url.to_owned(), error))?;
+        let status = response.status();
+
+        // S3 returns a 403 for missing objects when listing is not allowed,
+        // but also for invalid credentials, so it must be opted into
+        if status == StatusCode::NOT_FOUND
+            || (status == StatusCode::FORBIDDEN
+                && self.s3_unlistable
+                && matches!(remote, BuildCacheRemote::S3 { .. }))
+        {
+            return Ok(None);
+        }
+
+        if status == StatusCode::FORBIDDEN {
+            return Err(ProtoBuildCacheError::Forbidden {
+                url: url.to_owned(),
+            });
+        }
+
+        if !status.is_success() {
+            return Err(ProtoBuildCacheError::DownloadFailed {
+                url: url.to_owned(),
+                status: status.to_string(),
+            });
+        }
+
+        Ok(Some(response))
+    }
+
+    /// Send a `GET` request to the remote, and return the response
+    /// as text, or `None` if the file does not exist.
+    async fn send_get_text_request(
+        &self,
+        remote: &BuildCacheRemote,
+        url: &str,
+    ) -> Result<Option<String>, ProtoBuildCacheError> {
+        let Some(response) = self.send_get_request(remote, url).await? else {
+            return Ok(None);
+        };
+
+        let text =
+            response
+                .text()
+                .await
+                .map_err(|error| warpgate::WarpgateHttpClientError::Http {
+                    url: url.to_owned(),
+                    error: Box::new(error),
+                })?;
+
+        Ok(Some(text))
+    }
+
+    /// Send a `PUT` request to the remote, with the body streamed from the
+    /// file, so that large archives are not buffered in memory. The middleware
+    /// is bypassed, as it requires a body that can be cloned for retries.
+    async fn send_put_file_request(
+        &self,
+        remote: &BuildCacheRemote,
+        url: &str,
+        file: &Path,
+    ) -> Result<(), ProtoBuildCacheError> {
+        let size = fs::metadata(file)?.len();
+        let handle = tokio::fs::File::open(file)
+            .await
+            .map_err(|error| FsError::Read {
+                path: file.to_path_buf(),
+                error: Box::new(error),
+            })?;
+
+        let mut request = self
+            .http_client
+            .to_inner()
+            .put(url)
+            // S3 requires a length, as the body is not signed in chunks
+            .header("content-length", size)
+            .body(Body::from(handle));
+
+        for (key, value) in remote.get_headers("PUT", url) {
+            request = request.header(key, value);
+        }
+
+        let response =
+            request
+                .send()
+                .await
+                .map_err(|error| warpgate::WarpgateHttpClientError::Http {
+                    url: url.to_owned(),
+                    error: Box::new(error),
+                })?;
+
+        check_upload_status(url, response.status())
+    }
+
+    async fn send_put_request(
+        &self,
+        remote: &BuildCacheRemote,
+        url: &str,
+        body: Vec<u8>,
+    ) -> Result<(), ProtoBuildCacheError> {
+        let mut request = self.http_client.put(url).body(body);
+
+        for (key, value) in remote.get_headers("PUT", url) {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| HttpClient::map_error(url.to_owned(), error))?;
+
+        check_upload_status(url, response.status())
+    }
+
+    fn unpack(&self, product_dir: &Path, archive_file: &Path) -> Result<(), ProtoBuildCacheError> {
+        fs::remove_dir_all(product_dir)?;
+        fs::create_dir_all(product_dir)?;
+
+        Archiver::new(product_dir, archive_file).unpack_from_ext()?;
+
+        Ok(())
+    }
+}
+
+fn check_upload_status(url: &str, status: StatusCode) -> Result<(), ProtoBuildCacheError> {
+    if !status.is_success() {
+        return Err(ProtoBuildCacheError::UploadFailed {
+            url: url.to_owned(),
+            status: status.to_string(),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use starbase_archive::ArchiveError;
+use starbase_styles::{Style, Stylize};
+use starbase_utils::fs::FsError;
+use starbase_utils::json::JsonError;
+use thiserror::Error;
+use warpgate::WarpgateHttpClientError;
+
+#[derive(Error, Debug, miette::Diagnostic)]
+pub enum ProtoBuildCacheError {
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Archive(#[from] Box<ArchiveError>),
+
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Fs(#[from] Box<FsError>),
+
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    HttpClient(#[from] Box<WarpgateHttpClientError>),
+
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Json(#[from] Box<JsonError>),
+
+    #[diagnostic(code(proto::build_cache::download_failed))]
+    #[error(
+        "Failed to download build archive from {}: {status}",
+        .url.style(Style::Url),
+    )]
+    DownloadFailed { url: String, status: String },
+
+    #[diagnostic(
+        code(proto::build_cache::forbidden),
+        help = "Check your AWS credentials. If the bucket does not allow listing, enable the settings.build.cache.s3-unlistable setting, so that missing archives are not treated as an error."
+    )]
+    #[error(
+        "Access to build archive {} was denied by the remote cache.",
+        .url.style(Style::Url),
+    )]
+    Forbidden { url: String },
+
+    #[diagnostic(code(proto::build_cache::checksum_mismatch))]
+    #[error(
+        "Build archive {} from the remote cache failed verification. The checksum does not match, or the archive was signed with a different key.",
+        .url.style(Style::Url),
+    )]
+    ChecksumMismatch { url: String },
+
+    #[diagnostic(code(proto::build_cache::upload_failed))]
+    #[error(
+        "Failed to upload build archive to {}: {status}",
+        .url.style(Style::Url),
+    )]
+    UploadFailed { url: String, status: String },
+
+    #[diagnostic(code(proto::build_cache::invalid_remote))]
+    #[error(
+        "Invalid build cache remote {}, expected an {}, {}, or {} URL.",
+        .remote.style(Style::Url),
+        "http://".style(Style::Symbol),
+        "https://".style(Style::Symbol),
+        "s3://".style(Style::Symbol),
+    )]
+    InvalidRemote { remote: String },
+}
+
+impl From<ArchiveError> for ProtoBuildCacheError {
+    fn from(e: ArchiveError) -> ProtoBuildCacheError {
+        ProtoBuildCacheError::Archive(Box::new(e))
+    }
+}
+
+impl From<FsError> for ProtoBuildCacheError {
+    fn from(e: FsError) -> ProtoBuildCacheError {
+        ProtoBuildCacheError::Fs(Box::new(e))
+    }
+}
+
+impl From<JsonError> for ProtoBuildCacheError {
+    fn from(e: JsonError) -> ProtoBuildCacheError {
+        ProtoBuildCacheError::Json(Box::new(e))
+    }
+}
+
+impl From<WarpgateHttpClientError> for ProtoBuildCacheError {
+    fn from(e: WarpgateHttpClientError) -> ProtoBuildCacheError {
+        ProtoBuildCacheError::HttpClient(Box::new(e))
+    }
+}
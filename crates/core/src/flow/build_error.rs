@@ -53,6 +53,17 @@ pub enum ProtoBuildError {
     )]
     MissingBuilderExe { exe: PathBuf, id: Id },
 
+    #[diagnostic(
+        code(proto::install::build::missing_sandbox_program),
+        help = "Install it with your system package manager, or change the settings.build.sandbox setting."
+    )]
+    #[error(
+        "Unable to sandbox the build, as {} could not be found on {}.",
+        .program.style(Style::Shell),
+        "PATH".style(Style::Property),
+    )]
+    MissingSandboxProgram { program: String },
+
     #[diagnostic(code(proto::install::build::unmet_requirements))]
     #[error(
         "Build requirements have not been met, unable to proceed.\nPlease satisfy the requirements before attempting the build again."
@@ -88,6 +88,31 @@ impl<'tool> Detector<'tool> {
         })
     }
 
+    /// Return the names of the tool's ecosystem files that a version may be detected from.
+    pub async fn get_version_files(&self) -> Result<Vec<String>, ProtoDetectError> {
+        if !self
+            .tool
+            .plugin
+            .has_func(PluginFunction::DetectVersionFiles)
+            .await
+        {
+            return Ok(vec![]);
+        }
+
+        let output: DetectVersionOutput = self
+            .tool
+            .plugin
+            .cache_func_with(
+                PluginFunction::DetectVersionFiles,
+                DetectVersionInput {
+                    context: self.tool.create_plugin_unresolved_context(),
+                },
+            )
+            .await?;
+
+        Ok(output.files)
+    }
+
     /// Attempt to detect a version from the provided directory by scanning for applicable files.
     #[instrument(skip(self))]
     pub async fn detect_version_from(
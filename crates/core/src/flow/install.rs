@@ -1,9 +1,13 @@
 use super::build::*;
+use super::build_cache::{BuildCache, BuildCacheKey};
+pub use super::build_cache_error::ProtoBuildCacheError;
 pub use super::build_error::ProtoBuildError;
 pub use super::install_error::ProtoInstallError;
 use crate::checksum::*;
+use crate::config::{DedupeMode, ProtoConfig};
 use crate::flow::lock::Locker;
-use crate::helpers::{is_archive_file, is_offline};
+use crate::helpers::{is_archive_file, is_offline, set_read_only_recursive};
+use crate::layout::ContentIndex;
 use crate::lockfile::*;
 use crate::reporter::ProtoConsole;
 use crate::tool::Tool;
@@ -15,13 +19,13 @@ use starbase_archive::get_compression_extensions;
 use starbase_shell::{ShellType, join_exe_args};
 use starbase_styles::color;
 use starbase_utils::net::DownloadOptions;
-use starbase_utils::{fs, net, path};
+use starbase_utils::{fs, hash, net, path};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use system_env::System;
+use system_env::{System, SystemPackageManager};
 use tokio::process::Command;
 use tracing::{debug, instrument, warn};
-use warpgate::extract_file_name_from_url;
+use warpgate::{DownloadFileOptions, extract_file_name_from_url};
 
 pub use starbase_utils::net::OnChunkFn;
 pub type OnPhaseFn = Arc<dyn Fn(InstallPhase) + Send + Sync>;
@@ -47,6 +51,11 @@ pub struct InstallOptions {
     pub log_writer: Option<LogWriter>,
     pub on_download_chunk: Option<OnChunkFn>,
     pub on_phase_change: Option<OnPhaseFn>,
+    pub post_install_hook: Option<InstallHook>,
+    /// Require the pre-built archive to be verified against a published
+    /// checksum or signature, instead of generating a checksum for it.
+    /// Native installs and builds from source can't be verified, so fail.
+    pub require_verified: bool,
     pub skip_prompts: bool,
     pub skip_ui: bool,
     pub strategy: InstallStrategy,
@@ -82,7 +91,7 @@ impl<'tool> Installer<'tool> {
     #[instrument(skip(self, options))]
     pub async fn install(
         &self,
-        options: InstallOptions,
+        mut options: InstallOptions,
     ) -> Result<Option<LockRecord>, ProtoInstallError> {
         if self.tool.is_installed(self.spec) && !options.force {
             debug!(
@@ -97,9 +106,24 @@ impl<'tool> Installer<'tool> {
             return Err(ProtoInstallError::RequiredInternetConnection);
         }
 
+        // A previous install may have been made read-only,
+        // so restore permissions before overwriting it
+        set_read_only_recursive(&self.product_dir, false)?;
+
+        // Files may be linked to other versions through the content index,
+        // so break those links before overwriting them in place
+        let content_index = ContentIndex::new(&self.tool.proto.store.dedupe_dir);
+
+        if content_index.dir.exists() {
+            content_index.detach_dir(&self.product_dir)?;
+        }
+
         // Downloads (and the lock file managed by the caller) live here
         fs::create_dir_all(&self.temp_dir)?;
 
+        let post_install_hook = options.post_install_hook.take();
+        let log_writer = options.log_writer.clone();
+
         // If this function is defined, it acts like an escape hatch and
         // takes precedence over all other install strategies
         if self
@@ -108,6 +132,15 @@ impl<'tool> Installer<'tool> {
             .has_func(PluginFunction::NativeInstall)
             .await
         {
+            // Natively installed files are managed by the plugin
+            if options.require_verified {
+                return Err(ProtoInstallError::UnverifiedInstall {
+                    method: "natively".into(),
+                    tool: self.tool.get_name().to_owned(),
+                    version: self.spec.get_resolved_version().to_string(),
+                });
+            }
+
             debug!(
                 tool = self.tool.context.as_str(),
                 "Installing tool natively"
@@ -137,6 +170,9 @@ impl<'tool> Installer<'tool> {
                 )?;
 
             if output.installed {
+                self.run_post_install_hook(post_install_hook, log_writer.as_ref())
+                    .await?;
+
                 let mut record = self.tool.create_locked_record();
                 record.checksum = output.checksum;
 
@@ -156,6 +192,14 @@ impl<'tool> Installer<'tool> {
 
         // Build the tool from source
         let result = if matches!(options.strategy, InstallStrategy::BuildFromSource) {
+            if options.require_verified {
+                return Err(ProtoInstallError::UnverifiedInstall {
+                    method: "from source".into(),
+                    tool: self.tool.get_name().to_owned(),
+                    version: self.spec.get_resolved_version().to_string(),
+                });
+            }
+
             self.build_from_source(options).await
         }
         // Install from a prebuilt archive
@@ -164,7 +208,7 @@ impl<'tool> Installer<'tool> {
         };
 
         // Ensure that we installed something
-        let record = match result {
+        let mut record = match result {
             Ok(record) => {
                 if !self.product_dir.exists() || fs::read_dir(&self.product_dir)?.is_empty() {
                     return Err(ProtoInstallError::FailedInstallNoFiles {
@@ -180,9 +224,45 @@ impl<'tool> Installer<'tool> {
             }
         };
 
+        // Hooks may modify the installed files, so they must run
+        // before the files are deduped, fingerprinted, and locked
+        self.run_post_install_hook(post_install_hook, log_writer.as_ref())
+            .await?;
+
+        let settings = &self.tool.proto.load_config()?.settings;
+
+        // Link files that are identical to those of other installed
+        // versions, to reduce the size of the store
+        if !matches!(settings.dedupe, DedupeMode::Off) && self.tool.inventory.dir_original.is_none()
+        {
+            self.tool.proto.create_metric().record_tool_install_step(
+                &self.tool.context,
+                "dedupe",
+                content_index.dedupe_dir(&self.product_dir, settings.dedupe),
+            )?;
+        }
+
+        // Fingerprint the installed files, so that we can
+        // detect partial unpacks and modifications later on
+        record.fingerprint = Some(self.tool.proto.create_metric().record_tool_install_step(
+            &self.tool.context,
+            "fingerprint",
+            fingerprint_dir(&self.product_dir),
+        )?);
+
         // Verify against lockfile
         Locker::new(self.tool).verify_locked_record(self.spec, &record)?;
 
+        if settings.read_only_tools {
+            debug!(
+                tool = self.tool.context.as_str(),
+                install_dir = ?self.product_dir,
+                "Making install directory read-only",
+            );
+
+            set_read_only_recursive(&self.product_dir, true)?;
+        }
+
         debug!(
             tool = self.tool.context.as_str(),
             install_dir = ?self.product_dir,
@@ -192,18 +272,33 @@ impl<'tool> Installer<'tool> {
         Ok(Some(record))
     }
 
-    /// Build the tool from source using a set of requirements and instructions
-    /// into the `~/.proto/tools/<version>` folder.
-    #[instrument(skip(self, options))]
-    async fn build_from_source(
+    async fn run_post_install_hook(
         &self,
-        options: InstallOptions,
-    ) -> Result<LockRecord, ProtoInstallError> {
-        debug!(
-            tool = self.tool.context.as_str(),
-            "Installing tool by building from source"
-        );
+        hook: Option<InstallHook>,
+        log_writer: Option<&LogWriter>,
+    ) -> Result<(), ProtoInstallError> {
+        let Some(hook) = hook else {
+            return Ok(());
+        };
+
+        log_writer.inspect(|log| {
+            log.add_header("Running post-install hooks");
+        });
+
+        if self.tool.plugin.has_func(HookFunction::PostInstall).await {
+            self.tool
+                .plugin
+                .call_func_without_output(HookFunction::PostInstall, hook)
+                .await?;
+        }
 
+        Ok(())
+    }
+
+    /// Load the instructions for building the tool from source.
+    pub async fn load_build_instructions(
+        &self,
+    ) -> Result<BuildInstructionsOutput, ProtoInstallError> {
         if !self
             .tool
             .plugin
@@ -215,25 +310,70 @@ impl<'tool> Installer<'tool> {
             });
         }
 
-        let proto = &self.tool.proto;
+        let output: BuildInstructionsOutput =
+            self.tool.proto.create_metric().record_tool_install_step(
+                &self.tool.context,
+                "prepare_build_instructions",
+                self.tool
+                    .plugin
+                    .cache_func_with(
+                        PluginFunction::BuildInstructions,
+                        BuildInstructionsInput {
+                            context: self.tool.create_plugin_context(self.spec),
+                            install_dir: self.tool.to_virtual_path(&self.product_dir),
+                        },
+                    )
+                    .await,
+            )?;
 
-        let output: BuildInstructionsOutput = proto.create_metric().record_tool_install_step(
-            &self.tool.context,
-            "prepare_build_instructions",
-            self.tool
-                .plugin
-                .cache_func_with(
-                    PluginFunction::BuildInstructions,
-                    BuildInstructionsInput {
-                        context: self.tool.create_plugin_context(self.spec),
-                        install_dir: self.tool.to_virtual_path(&self.product_dir),
-                    },
-                )
-                .await,
-        )?;
+        Ok(output)
+    }
 
+    /// Return the package manager commands that would be executed to install
+    /// the system dependencies required for building from source, without
+    /// executing them. An explicit package manager can be provided to generate
+    /// commands for another system, for example, a Docker image.
+    #[instrument(skip(self))]
+    pub async fn get_system_dependency_commands(
+        &self,
+        manager: Option<SystemPackageManager>,
+    ) -> Result<Vec<Vec<String>>, ProtoInstallError> {
+        let build = self.load_build_instructions().await?;
+        let config = self.tool.proto.load_config()?;
+        let system = match manager {
+            Some(pm) => System::for_manager_target(pm),
+            None => self.create_build_system(config),
+        };
+
+        let packages =
+            resolve_system_packages(&system, &build, &config.settings.build.exclude_packages);
+
+        if packages.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let dep_configs = create_dependency_configs(packages);
+        let mut commands = vec![];
+
+        if let Some(args) = system
+            .get_update_index_command(false)
+            .map_err(ProtoBuildError::from)?
+        {
+            commands.push(args);
+        }
+
+        if let Some(args) = system
+            .get_install_packages_command(&dep_configs, false)
+            .map_err(ProtoBuildError::from)?
+        {
+            commands.push(args);
+        }
+
+        Ok(commands)
+    }
+
+    fn create_build_system(&self, config: &ProtoConfig) -> System {
         let mut system = System::default();
-        let config = proto.load_config()?;
 
         if let Some(pm) = config.settings.build.system_package_manager.get(&system.os) {
             if let Some(pm) = pm {
@@ -255,13 +395,36 @@ impl<'tool> Installer<'tool> {
             }
         }
 
-        let record = Builder::new(
+        system
+    }
+
+    /// Build the tool from source using a set of requirements and instructions
+    /// into the `~/.proto/tools/<version>` folder.
+    #[instrument(skip(self, options))]
+    async fn build_from_source(
+        &self,
+        options: InstallOptions,
+    ) -> Result<LockRecord, ProtoInstallError> {
+        debug!(
+            tool = self.tool.context.as_str(),
+            "Installing tool by building from source"
+        );
+
+        let proto = &self.tool.proto;
+        let output = self.load_build_instructions().await?;
+        let config = proto.load_config()?;
+        let system = self.create_build_system(config);
+
+        let version = self.spec.get_resolved_version();
+        let http_client = proto.get_plugin_loader()?.get_http_client()?;
+
+        let mut builder = Builder::new(
             self.tool,
             BuilderOptions {
                 config,
                 console: options.console.as_ref(),
                 install_dir: &self.product_dir,
-                http_client: proto.get_plugin_loader()?.get_http_client()?,
+                http_client,
                 log_writer: options
                     .log_writer
                     .as_ref()
@@ -271,11 +434,57 @@ impl<'tool> Installer<'tool> {
                 skip_ui: options.skip_ui,
                 system,
                 temp_dir: &self.temp_dir,
-                version: self.spec.get_resolved_version(),
+                version: version.clone(),
             },
-        )
-        .build(output)
-        .await?;
+        );
+
+        // System dependencies and requirements are still
+        // required when restoring a build from the cache
+        builder.prepare(&output).await?;
+
+        // Check the cache for an archive of a previous build
+        let cache = BuildCache::new(
+            &BuildCacheKey::new(self.tool, &version, &output)?,
+            &config.settings.build.cache,
+            &proto.store.cache_dir,
+            http_client,
+        )?;
+
+        if cache.is_enabled() {
+            match cache.restore(&self.product_dir, &self.temp_dir).await {
+                Ok(Some(record)) => {
+                    debug!(
+                        tool = self.tool.context.as_str(),
+                        archive = &cache.archive_name,
+                        "Restored build from cache, skipping build instructions"
+                    );
+
+                    return Ok(LockRecord {
+                        checksum: record.checksum,
+                        source: record.source,
+                        ..self.tool.create_locked_record()
+                    });
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    warn!(
+                        tool = self.tool.context.as_str(),
+                        "Failed to restore build from cache: {error}"
+                    );
+                }
+            };
+        }
+
+        let record = builder.build(output).await?;
+
+        if cache.is_enabled()
+            && let Err(error) = cache.save(&self.product_dir, &self.temp_dir, &record).await
+        {
+            warn!(
+                tool = self.tool.context.as_str(),
+                "Failed to save build to cache: {error}"
+            );
+        }
 
         Ok(record)
     }
@@ -304,7 +513,6 @@ impl<'tool> Installer<'tool> {
         }
 
         let proto = &self.tool.proto;
-        let client = proto.get_plugin_loader()?.get_http_client()?;
         let config = proto.load_config()?;
 
         let output: DownloadPrebuiltOutput = proto.create_metric().record_tool_install_step(
@@ -322,122 +530,55 @@ impl<'tool> Installer<'tool> {
                 .await,
         )?;
 
-        let mut record = self.tool.create_locked_record();
-
-        // Download the prebuilt
-        let download_url = config.rewrite_url(output.download_url);
-        let download_filename = output
-            .download_name
-            .unwrap_or_else(|| extract_file_name_from_url(&download_url));
-        let download_file = self.temp_dir.join(&download_filename);
-
-        record.source = Some(download_url.clone());
-        options.on_phase_change.as_ref().inspect(|func| {
-            func(InstallPhase::Download {
-                url: download_url.clone(),
-                file: download_filename.clone(),
+        // Fail before downloading anything, as the archive can't be verified
+        if options.require_verified && output.checksum_url.is_none() && output.checksum.is_none() {
+            return Err(ProtoInstallError::UnverifiedDownload {
+                tool: self.tool.get_name().to_owned(),
+                version: self.spec.get_resolved_version().to_string(),
             });
-        });
-
-        debug!(
-            tool = self.tool.context.as_str(),
-            "Downloading tool archive"
-        );
-
-        proto.create_metric().record_tool_install_step(
-            &self.tool.context,
-            "download_archive",
-            net::download_from_url_with_options(
-                &download_url,
-                &download_file,
-                DownloadOptions {
-                    downloader: Some(Box::new(
-                        client.create_downloader_with_headers(output.http_headers.clone()),
-                    )),
-                    on_chunk: options.on_download_chunk.clone(),
-                    ..Default::default()
-                },
-            )
-            .await,
-        )?;
-
-        // Verify against a URL that contains the checksum
-        if let Some(checksum_url) = output.checksum_url {
-            let checksum_url = config.rewrite_url(checksum_url);
-            let checksum_filename = output
-                .checksum_name
-                .unwrap_or_else(|| extract_file_name_from_url(&checksum_url));
-            let checksum_file = self.temp_dir.join(&checksum_filename);
+        }
 
-            options.on_phase_change.as_ref().inspect(|func| {
-                func(InstallPhase::Verify {
-                    url: checksum_url.clone(),
-                    file: checksum_filename.clone(),
-                });
-            });
+        let mut record = self.tool.create_locked_record();
 
-            debug!(
-                tool = self.tool.context.as_str(),
-                "Downloading tool checksum"
-            );
+        // Download and verify the prebuilt, and if either fails,
+        // fail over to the next mirror (the canonical URL is last)
+        let mirror_urls = config.get_mirror_urls(&output.download_url);
+        let download_filename = output.download_name.clone().unwrap_or_else(|| {
+            extract_file_name_from_url(&config.rewrite_url(&output.download_url))
+        });
+        let mut download_file = PathBuf::new();
 
-            proto.create_metric().record_tool_install_step(
-                &self.tool.context,
-                "download_checksum",
-                net::download_from_url_with_options(
-                    &checksum_url,
-                    &checksum_file,
-                    DownloadOptions::new(
-                        client.create_downloader_with_headers(output.http_headers),
-                    ),
-                )
-                .await,
-            )?;
+        for (index, mirror_url) in mirror_urls.iter().enumerate() {
+            download_file = self.get_mirror_temp_file(mirror_url, &download_filename);
 
-            record.checksum = Some(
-                self.verify_checksum(
-                    &checksum_file,
+            let result = self
+                .download_and_verify_prebuilt(
+                    mirror_url,
+                    index,
                     &download_file,
-                    output.checksum_public_key.as_deref(),
+                    &download_filename,
+                    &output,
+                    &options,
                 )
-                .await?,
-            );
-        }
-        // Verify against an explicitly provided checksum
-        else if let Some(checksum) = output.checksum {
-            let checksum_file = self
-                .temp_dir
-                .join(format!("CHECKSUM.{:?}", checksum.algo).to_lowercase());
-
-            fs::write_file(&checksum_file, checksum.hash.as_deref().unwrap_or_default())?;
+                .await;
 
-            debug!(
-                tool = self.tool.context.as_str(),
-                checksum = checksum.to_string(),
-                "Using provided checksum"
-            );
-
-            record.checksum = Some(
-                self.verify_checksum(
-                    &checksum_file,
-                    &download_file,
-                    output
-                        .checksum_public_key
-                        .as_deref()
-                        .or(checksum.key.as_deref()),
-                )
-                .await?,
-            );
-        }
-        // No available checksum, so generate one ourselves for the lockfile
-        else {
-            record.checksum = Some(Checksum::sha256(
-                proto.create_metric().record_tool_install_step(
-                    &self.tool.context,
-                    "generate_checksum",
-                    hash_file_contents_sha256(&download_file),
-                )?,
-            ));
+            match result {
+                Ok(checksum) => {
+                    record.checksum = Some(checksum);
+                    record.source = Some(mirror_url.to_owned());
+                    break;
+                }
+                Err(error) if index + 1 < mirror_urls.len() => {
+                    warn!(
+                        tool = self.tool.context.as_str(),
+                        url = mirror_url,
+                        "Failed to download or verify tool archive, trying the next mirror: {error}",
+                    );
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
         }
 
         // Attempt to unpack the archive
@@ -559,6 +700,183 @@ impl<'tool> Installer<'tool> {
         Ok(record)
     }
 
+    /// Return a file in the temp directory for a download from the provided
+    /// URL. Files are kept apart per URL, so that a partial download, or
+    /// a checksum, from one mirror is never resumed or used by another.
+    fn get_mirror_temp_file(&self, url: &str, file_name: &str) -> PathBuf {
+        self.temp_dir
+            .join(&hash::sha256::from_bytes(url)[0..16])
+            .join(file_name)
+    }
+
+    /// Download the prebuilt archive from the provided URL (typically a mirror),
+    /// and verify it against the checksum strategy for the tool.
+    async fn download_and_verify_prebuilt(
+        &self,
+        download_url: &str,
+        mirror_index: usize,
+        download_file: &Path,
+        download_filename: &str,
+        output: &DownloadPrebuiltOutput,
+        options: &InstallOptions,
+    ) -> Result<Checksum, ProtoInstallError> {
+        let proto = &self.tool.proto;
+        let client = proto.get_plugin_loader()?.get_http_client()?;
+
+        options.on_phase_change.as_ref().inspect(|func| {
+            func(InstallPhase::Download {
+                url: download_url.to_owned(),
+                file: download_filename.to_owned(),
+            });
+        });
+
+        debug!(
+            tool = self.tool.context.as_str(),
+            url = download_url,
+            "Downloading tool archive"
+        );
+
+        // Resumes a partial download from a previous
+        // attempt, and splits large files into segments
+        proto.create_metric().record_tool_install_step(
+            &self.tool.context,
+            "download_archive",
+            client
+                .download_file(
+                    download_url,
+                    download_file,
+                    DownloadFileOptions {
+                        headers: output.http_headers.clone(),
+                        on_chunk: options.on_download_chunk.clone(),
+                    },
+                )
+                .await,
+        )?;
+
+        // Verify against a URL that contains the checksum. The checksum is
+        // downloaded for each mirror, so that a bad checksum from a failed
+        // mirror doesn't cause every following mirror to fail
+        if let Some(checksum_url) = &output.checksum_url {
+            let checksum_file = self
+                .download_checksum(checksum_url, mirror_index, output, options)
+                .await?;
+
+            self.verify_checksum(
+                &checksum_file,
+                download_file,
+                output.checksum_public_key.as_deref(),
+            )
+            .await
+        }
+        // Verify against an explicitly provided checksum
+        else if let Some(checksum) = &output.checksum {
+            let checksum_file = self
+                .temp_dir
+                .join(format!("CHECKSUM.{:?}", checksum.algo).to_lowercase());
+
+            fs::write_file(&checksum_file, checksum.hash.as_deref().unwrap_or_default())?;
+
+            debug!(
+                tool = self.tool.context.as_str(),
+                checksum = checksum.to_string(),
+                "Using provided checksum"
+            );
+
+            self.verify_checksum(
+                &checksum_file,
+                download_file,
+                output
+                    .checksum_public_key
+                    .as_deref()
+                    .or(checksum.key.as_deref()),
+            )
+            .await
+        }
+        // No available checksum, so generate one ourselves for the lockfile
+        else {
+            Ok(Checksum::sha256(
+                proto.create_metric().record_tool_install_step(
+                    &self.tool.context,
+                    "generate_checksum",
+                    hash_file_contents_sha256(download_file),
+                )?,
+            ))
+        }
+    }
+
+    /// Download the checksum file for the prebuilt archive, and
+    /// fail over to the next mirror if the download fails. Mirrors are
+    /// tried starting from the same position as the archive's mirror.
+    async fn download_checksum(
+        &self,
+        checksum_url: &str,
+        mirror_index: usize,
+        output: &DownloadPrebuiltOutput,
+        options: &InstallOptions,
+    ) -> Result<PathBuf, ProtoInstallError> {
+        let proto = &self.tool.proto;
+        let client = proto.get_plugin_loader()?.get_http_client()?;
+        let config = proto.load_config()?;
+
+        let mut mirror_urls = config.get_mirror_urls(checksum_url);
+        let start_index = mirror_index.min(mirror_urls.len() - 1);
+        mirror_urls.rotate_left(start_index);
+
+        let checksum_filename = output
+            .checksum_name
+            .clone()
+            .unwrap_or_else(|| extract_file_name_from_url(&config.rewrite_url(checksum_url)));
+        let mut checksum_file = PathBuf::new();
+
+        for (index, mirror_url) in mirror_urls.iter().enumerate() {
+            checksum_file = self.get_mirror_temp_file(mirror_url, &checksum_filename);
+
+            options.on_phase_change.as_ref().inspect(|func| {
+                func(InstallPhase::Verify {
+                    url: mirror_url.to_owned(),
+                    file: checksum_filename.clone(),
+                });
+            });
+
+            debug!(
+                tool = self.tool.context.as_str(),
+                url = mirror_url,
+                "Downloading tool checksum"
+            );
+
+            let result = proto.create_metric().record_tool_install_step(
+                &self.tool.context,
+                "download_checksum",
+                net::download_from_url_with_options(
+                    mirror_url,
+                    &checksum_file,
+                    DownloadOptions::new(
+                        client.create_downloader_with_headers(output.http_headers.clone()),
+                    ),
+                )
+                .await,
+            );
+
+            match result {
+                Ok(_) => {
+                    break;
+                }
+                Err(error) if index + 1 < mirror_urls.len() => {
+                    warn!(
+                        tool = self.tool.context.as_str(),
+                        url = mirror_url,
+                        "Failed to download tool checksum, trying the next mirror: {error}",
+                    );
+                }
+                Err(error) => {
+                    return Err(error.into());
+                }
+            }
+        }
+
+        Ok(checksum_file)
+    }
+
     /// Uninstall the tool by deleting the current install directory.
     #[instrument(skip(self))]
     pub async fn uninstall(&self) -> Result<bool, ProtoInstallError> {
@@ -608,6 +926,7 @@ impl<'tool> Installer<'tool> {
             "Deleting install directory"
         );
 
+        set_read_only_recursive(&self.product_dir, false)?;
         fs::remove_dir_all(&self.product_dir)?;
 
         debug!(
@@ -1,7 +1,9 @@
+use super::build_cache_error::ProtoBuildCacheError;
 use super::build_error::ProtoBuildError;
 use super::lock_error::ProtoLockError;
 use crate::checksum::ProtoChecksumError;
 use crate::config_error::ProtoConfigError;
+use crate::layout::ProtoLayoutError;
 use crate::utils::archive::ProtoArchiveError;
 use crate::utils::process::ProtoProcessError;
 use starbase_styles::{Style, Stylize, apply_style_tags};
@@ -22,6 +24,10 @@ pub enum ProtoInstallError {
     #[error(transparent)]
     Build(#[from] Box<ProtoBuildError>),
 
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    BuildCache(#[from] Box<ProtoBuildCacheError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Checksum(#[from] Box<ProtoChecksumError>),
@@ -42,6 +48,10 @@ pub enum ProtoInstallError {
     #[error(transparent)]
     HttpClient(#[from] Box<WarpgateHttpClientError>),
 
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Layout(#[from] Box<ProtoLayoutError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Net(#[from] Box<NetError>),
@@ -89,6 +99,30 @@ pub enum ProtoInstallError {
         download: PathBuf,
     },
 
+    #[diagnostic(
+        code(proto::install::unverified_download),
+        help = "Pass --no-verify to install without verification."
+    )]
+    #[error(
+        "Unable to verify the downloaded archive for {tool} {}, as no checksum or signature was published for it.",
+        .version.style(Style::Hash),
+    )]
+    UnverifiedDownload { tool: String, version: String },
+
+    #[diagnostic(
+        code(proto::install::unverified_install),
+        help = "Pass --no-verify to install without verification."
+    )]
+    #[error(
+        "Unable to verify the install of {tool} {}, as it's installed {method}, which can't be verified against a published checksum or signature.",
+        .version.style(Style::Hash),
+    )]
+    UnverifiedInstall {
+        method: String,
+        tool: String,
+        version: String,
+    },
+
     #[diagnostic(code(proto::install::prebuilt_unsupported))]
     #[error("Downloading a pre-built is not supported for {tool}. Try building from source by passing {}.", "--build".style(Style::Shell))]
     UnsupportedDownloadPrebuilt { tool: String },
@@ -114,6 +148,12 @@ impl From<ProtoBuildError> for ProtoInstallError {
     }
 }
 
+impl From<ProtoBuildCacheError> for ProtoInstallError {
+    fn from(e: ProtoBuildCacheError) -> ProtoInstallError {
+        ProtoInstallError::BuildCache(Box::new(e))
+    }
+}
+
 impl From<ProtoChecksumError> for ProtoInstallError {
     fn from(e: ProtoChecksumError) -> ProtoInstallError {
         ProtoInstallError::Checksum(Box::new(e))
@@ -144,6 +184,12 @@ impl From<HashError> for ProtoInstallError {
     }
 }
 
+impl From<ProtoLayoutError> for ProtoInstallError {
+    fn from(e: ProtoLayoutError) -> ProtoInstallError {
+        ProtoInstallError::Layout(Box::new(e))
+    }
+}
+
 impl From<ProtoLockError> for ProtoInstallError {
     fn from(e: ProtoLockError) -> ProtoInstallError {
         ProtoInstallError::Lock(Box::new(e))
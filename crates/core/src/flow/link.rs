@@ -1,7 +1,10 @@
 pub use super::link_error::ProtoLinkError;
+use crate::config::{ConfigMode, ProtoConfig};
+use crate::env::ProtoEnvironment;
 use crate::flow::locate::Locator;
-use crate::layout::{BinManager, Shim, ShimRegistry};
+use crate::layout::{Shim, ShimRegistry};
 use crate::tool::Tool;
+use crate::tool_context::ToolContext;
 use crate::tool_spec::ToolSpec;
 use proto_pdk_api::*;
 use proto_shim::*;
@@ -61,8 +64,22 @@ impl<'tool> Linker<'tool> {
     pub async fn link_shims(&mut self, force: bool) -> Result<Vec<PathBuf>, ProtoLinkError> {
         let shims = Locator::new(self.tool, self.spec).locate_shims().await?;
 
+        // Custom shims are only supported in the global config, as they're
+        // created in the global shims directory and registry
+        let config = self.tool.proto.load_config_with_mode(ConfigMode::Global)?;
+
         if shims.is_empty() {
-            return Ok(vec![]);
+            let custom_shims = link_config_shims(
+                &self.tool.proto,
+                config,
+                &mut self.shim_registry,
+                Some(&self.tool.context),
+                force,
+            )?;
+
+            self.shim_registry.save()?;
+
+            return Ok(custom_shims);
         }
 
         let is_outdated = self.tool.inventory.manifest.shim_version != SHIM_VERSION;
@@ -161,6 +178,15 @@ impl<'tool> Linker<'tool> {
             manifest.save()?;
         }
 
+        // Custom shims that run through this tool
+        to_create.extend(link_config_shims(
+            &self.tool.proto,
+            config,
+            &mut self.shim_registry,
+            Some(&self.tool.context),
+            force_create,
+        )?);
+
         self.shim_registry.save()?;
 
         Ok(to_create)
@@ -229,7 +255,7 @@ impl<'tool> Linker<'tool> {
             }
 
             // Create a new product since we need to change the version for each bin
-            let tool_dir = self.tool.inventory.get_product_dir(&bin_version);
+            let tool_dir = self.tool.inventory.locate_product_dir(&bin_version);
 
             let input_path = tool_dir.join(path::normalize_separators(
                 bin.config
@@ -292,7 +318,7 @@ impl<'tool> Linker<'tool> {
     /// Remove all binaries for the tool across every installed version.
     #[instrument(skip(self))]
     pub async fn unlink_bins(&self) -> Result<(), ProtoLinkError> {
-        let bin_manager = BinManager::from_manifest(&self.tool.inventory.manifest);
+        let bin_manager = self.tool.inventory.create_bin_manager();
 
         for bin in Locator::new(self.tool, self.spec)
             .locate_bins_with_manager(&bin_manager, None)
@@ -316,7 +342,7 @@ impl<'tool> Linker<'tool> {
     ) -> Result<(), ProtoLinkError> {
         let store = &self.tool.proto.store;
         let locator = Locator::new(self.tool, self.spec);
-        let mut bin_manager = BinManager::from_manifest(&self.tool.inventory.manifest);
+        let mut bin_manager = self.tool.inventory.create_bin_manager();
 
         // Snapshot the affected bins before removal
         let old_bins = locator
@@ -355,7 +381,7 @@ impl<'tool> Linker<'tool> {
                         let src_path = self
                             .tool
                             .inventory
-                            .get_product_dir(new_version)
+                            .locate_product_dir(new_version)
                             .join(path::normalize_separators(exe_path));
 
                         if src_path.exists() {
@@ -382,3 +408,101 @@ impl<'tool> Linker<'tool> {
         Ok(())
     }
 }
+
+/// Create shims that have been defined in the global `~/.proto/.prototools`
+/// with `[shims.*]`, and update them in the provided registry. If a tool context
+/// is provided, only shims that run through that tool will be created. Custom
+/// shims that are no longer configured are removed. The registry is not saved.
+#[instrument(skip(proto, config, shim_registry))]
+pub fn link_config_shims(
+    proto: &ProtoEnvironment,
+    config: &ProtoConfig,
+    shim_registry: &mut ShimRegistry,
+    context: Option<&ToolContext>,
+    force: bool,
+) -> Result<Vec<PathBuf>, ProtoLinkError> {
+    let store = &proto.store;
+    let mut to_create = vec![];
+
+    // Remove custom shims that have been removed from the config
+    let stale = shim_registry
+        .iter()
+        .filter(|(name, shim)| {
+            shim.custom == Some(true)
+                && !config
+                    .shims
+                    .get(name.as_str())
+                    .is_some_and(|shim| shim.tool.is_some())
+        })
+        .map(|(name, _)| name.to_owned())
+        .collect::<Vec<_>>();
+
+    for name in stale {
+        shim_registry.remove(&name);
+        store.remove_shim(&store.shims_dir.join(get_shim_file_name(&name)))?;
+
+        debug!(
+            shim = name,
+            "Removing custom shim as it's no longer configured"
+        );
+    }
+
+    for (name, shim) in &config.shims {
+        let Some(tool) = &shim.tool else {
+            continue;
+        };
+
+        if context.is_some_and(|context| context != tool) {
+            continue;
+        }
+
+        // Don't overwrite a shim that has been registered by a plugin
+        if shim_registry
+            .get(name)
+            .is_some_and(|entry| entry.custom.is_none())
+        {
+            warn!(
+                shim = name,
+                tool = tool.as_str(),
+                "Shim {} is already provided by a plugin, ignoring the custom shim that runs through {}",
+                color::file(name),
+                color::id(tool.as_str()),
+            );
+
+            continue;
+        }
+
+        let shim_entry = Shim {
+            after_args: shim.after_args.clone(),
+            alt_exe: shim.alt_exe.filter(|alt| *alt),
+            before_args: shim.before_args.clone(),
+            context: Some(tool.to_owned()),
+            custom: Some(true),
+            env_vars: shim.env.clone(),
+        };
+
+        let shim_path = store.shims_dir.join(get_shim_file_name(name));
+
+        if force || !shim_path.exists() {
+            to_create.push(shim_path);
+        }
+
+        shim_registry.update(name.to_owned(), shim_entry)?;
+    }
+
+    if !to_create.is_empty() {
+        fs::create_dir_all(&store.shims_dir)?;
+
+        // Lock for our tests because of race conditions
+        #[cfg(debug_assertions)]
+        let _lock = fs::lock_directory(&store.shims_dir)?;
+
+        for shim_path in &to_create {
+            store.create_shim(shim_path)?;
+
+            debug!(shim = ?shim_path, "Creating custom shim");
+        }
+    }
+
+    Ok(to_create)
+}
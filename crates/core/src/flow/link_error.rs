@@ -1,4 +1,5 @@
 use super::locate_error::ProtoLocateError;
+use crate::config_error::ProtoConfigError;
 use crate::layout::ProtoLayoutError;
 use starbase_styles::{Style, Stylize};
 use starbase_utils::fs::FsError;
@@ -7,6 +8,10 @@ use thiserror::Error;
 
 #[derive(Error, Debug, miette::Diagnostic)]
 pub enum ProtoLinkError {
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Config(#[from] Box<ProtoConfigError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Fs(#[from] Box<FsError>),
@@ -32,6 +37,12 @@ pub enum ProtoLinkError {
     },
 }
 
+impl From<ProtoConfigError> for ProtoLinkError {
+    fn from(e: ProtoConfigError) -> ProtoLinkError {
+        ProtoLinkError::Config(Box::new(e))
+    }
+}
+
 impl From<FsError> for ProtoLinkError {
     fn from(e: FsError) -> ProtoLinkError {
         ProtoLinkError::Fs(Box::new(e))
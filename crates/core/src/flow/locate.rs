@@ -54,7 +54,7 @@ pub struct Locator<'tool> {
 impl<'tool> Locator<'tool> {
     pub fn new(tool: &'tool Tool, spec: &'tool ToolSpec) -> Self {
         Self {
-            product_dir: tool.get_product_dir(spec),
+            product_dir: tool.locate_product_dir(spec),
             tool,
             spec,
             exe_file: None,
@@ -94,7 +94,7 @@ impl<'tool> Locator<'tool> {
             .cache_func_with(
                 PluginFunction::LocateExecutables,
                 LocateExecutablesInput {
-                    context: self.tool.create_plugin_context(self.spec),
+                    context: self.tool.create_plugin_locate_context(self.spec),
                     install_dir: self.tool.to_virtual_path(&self.product_dir),
                 },
             )
@@ -155,6 +155,30 @@ impl<'tool> Locator<'tool> {
         Ok(locations)
     }
 
+    /// Return location information for all executables (primary and secondary)
+    /// within the tool directory. Unlike [`Locator::locate_primary_exe`],
+    /// this will not update the permissions of located files.
+    #[instrument(skip(self))]
+    pub async fn locate_exes(&self) -> Result<Vec<ExecutableLocation>, ProtoLocateError> {
+        let output = self.call_locate_executables().await?;
+        let mut locations = vec![];
+
+        for (name, config) in output.exes {
+            if let Some(exe_path) = &config.exe_path {
+                locations.push(ExecutableLocation {
+                    path: self.product_dir.join(path::normalize_separators(exe_path)),
+                    name,
+                    config,
+                    version: None,
+                });
+            }
+        }
+
+        locations.sort_by(|a, d| a.name.cmp(&d.name));
+
+        Ok(locations)
+    }
+
     /// Return a list of all binaries that get created in `~/.proto/bin`.
     /// The list will contain the executable config, and an absolute path
     /// to the binaries final location.
@@ -163,11 +187,8 @@ impl<'tool> Locator<'tool> {
         &self,
         focused_version: Option<&VersionSpec>,
     ) -> Result<Vec<ExecutableLocation>, ProtoLocateError> {
-        self.locate_bins_with_manager(
-            &BinManager::from_manifest(&self.tool.inventory.manifest),
-            focused_version,
-        )
-        .await
+        self.locate_bins_with_manager(&self.tool.inventory.create_bin_manager(), focused_version)
+            .await
     }
 
     pub async fn locate_bins_with_manager(
@@ -196,8 +217,10 @@ impl<'tool> Locator<'tool> {
                 .cache_func_with(
                     PluginFunction::LocateExecutables,
                     LocateExecutablesInput {
-                        context: self.tool.create_plugin_context(&spec),
-                        install_dir: self.tool.to_virtual_path(self.tool.get_product_dir(&spec)),
+                        context: self.tool.create_plugin_locate_context(&spec),
+                        install_dir: self
+                            .tool
+                            .to_virtual_path(self.tool.locate_product_dir(&spec)),
                     },
                 )
                 .await?;
@@ -3,7 +3,7 @@ use crate::lockfile::LockRecord;
 use crate::tool::Tool;
 use crate::tool_spec::ToolSpec;
 use std::collections::BTreeSet;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 use version_spec::{UnresolvedVersionSpec, VersionSpec};
 
 // [x] install many
@@ -395,6 +395,23 @@ impl<'tool> Locker<'tool> {
             _ => {}
         };
 
+        // Only compare the fingerprints of prebuilt archives, as their
+        // unpacked files should be identical across machines. However,
+        // post-install scripts and hooks may write machine specific files
+        // into the install directory, so a mismatch is only advisory
+        if install_record.checksum.is_some()
+            && let Some(ir) = &install_record.fingerprint
+            && let Some(lr) = &locked_record.fingerprint
+            && ir != lr
+        {
+            warn!(
+                tool = self.tool.context.as_str(),
+                fingerprint = ir,
+                locked_fingerprint = lr,
+                "Fingerprint of the installed files does not match the lockfile, the archive may have been partially unpacked or tampered with",
+            );
+        }
+
         if let Some(l_os) = install_record.os
             && let Some(r_os) = locked_record.os
             && l_os != r_os
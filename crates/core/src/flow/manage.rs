@@ -1,4 +1,5 @@
 pub use super::manage_error::ProtoManageError;
+use crate::checksum::stamp_dir;
 use crate::flow::install::{InstallOptions, Installer};
 use crate::flow::link::Linker;
 use crate::flow::lock::Locker;
@@ -8,9 +9,12 @@ use crate::telemetry::cache_status;
 use crate::tool::Tool;
 use crate::tool_manifest::ToolManifestVersion;
 use crate::tool_spec::ToolSpec;
-use proto_pdk_api::{InstallStrategy, PluginFunction, SyncManifestInput, SyncManifestOutput};
+use proto_pdk_api::{
+    InstallStrategy, PluginFunction, PolicyOperation, SyncManifestInput, SyncManifestOutput,
+};
 use starbase_utils::fs;
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 use tracing::{debug, instrument};
 
 #[allow(clippy::large_enum_variant)]
@@ -72,9 +76,15 @@ impl<'tool> Manager<'tool> {
 
         // Unlock and then remove the version-keyed temp directory. Removal must
         // happen after unlocking, as some platforms (Windows) cannot delete a
-        // directory containing an open lock file.
+        // directory containing an open lock file. When the install failed,
+        // partial downloads are kept, so that the next install can resume them.
         install_lock.unlock()?;
-        let _ = fs::remove_dir_all(temp_dir);
+
+        if result.is_ok() {
+            let _ = fs::remove_dir_all(temp_dir);
+        } else {
+            clean_temp_dir(&temp_dir);
+        }
 
         timer.record_tool_install(&self.tool.context, strategy, cache, result)
     }
@@ -86,6 +96,10 @@ impl<'tool> Manager<'tool> {
     ) -> Result<InstallOutcome, ProtoManageError> {
         let version = Resolver::resolve(self.tool, spec, false).await?;
 
+        Resolver::new(self.tool)
+            .check_policies(PolicyOperation::Install, spec)
+            .await?;
+
         if self.tool.is_installed(spec) && !options.force {
             return Ok(InstallOutcome::AlreadyInstalled);
         }
@@ -146,7 +160,9 @@ impl<'tool> Manager<'tool> {
             self.tool.inventory.manifest.add_version(
                 record.version.as_ref().unwrap(),
                 ToolManifestVersion {
+                    fingerprint: record.fingerprint.clone(),
                     lock: Some(record.for_manifest()),
+                    stamp: stamp_dir(self.tool.get_product_dir(spec)).ok(),
                     suffix: self.tool.inventory.config.version_suffix.clone(),
                     ..Default::default()
                 },
@@ -333,3 +349,32 @@ fn install_strategy_name(strategy: &InstallStrategy) -> &'static str {
         InstallStrategy::DownloadPrebuilt => "download-prebuilt",
     }
 }
+
+/// Remove everything in the temp directory, except for partial downloads
+/// (and their validators), which are named `<file>.part` and `<file>.part.etag`.
+/// Partial downloads are kept in a sub-directory per mirror, so directories
+/// are cleaned recursively, and only removed once empty.
+fn clean_temp_dir(temp_dir: &Path) {
+    let Ok(entries) = fs::read_dir(temp_dir) else {
+        return;
+    };
+
+    for entry in entries {
+        let path = entry.path();
+
+        if path.is_dir() {
+            clean_temp_dir(&path);
+
+            let _ = std::fs::remove_dir(&path);
+            continue;
+        }
+
+        let name = fs::file_name(&path);
+
+        if name.ends_with(".part") || name.ends_with(".part.etag") {
+            continue;
+        }
+
+        let _ = fs::remove_file(&path);
+    }
+}
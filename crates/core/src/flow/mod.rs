@@ -1,4 +1,6 @@
 mod build;
+pub mod build_cache;
+mod build_cache_error;
 mod build_error;
 pub mod detect;
 mod detect_error;
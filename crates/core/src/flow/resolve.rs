@@ -1,11 +1,14 @@
 pub use super::resolve_error::ProtoResolveError;
+use crate::alias_expression::AliasExpression;
 use crate::flow::lock::Locker;
 use crate::helpers::is_offline;
+use crate::loader::{load_policy_plugins, load_tool};
 use crate::tool::Tool;
 use crate::tool_spec::ToolSpec;
 use crate::version_resolver::VersionResolver;
 use proto_pdk_api::*;
 use std::env;
+use std::pin::Pin;
 use tracing::{debug, instrument};
 
 /// Loads, resolves, and validates versions.
@@ -14,6 +17,14 @@ pub struct Resolver<'tool> {
 
     /// Collection of loaded versions.
     pub data: VersionResolver<'tool>,
+
+    /// Newer version that was held back by the minimum release age,
+    /// when the version was resolved from the loaded versions.
+    pub held_back: Option<VersionSpec>,
+
+    /// Resolve the versions of other tools referenced by `pin/<tool>`
+    /// alias expressions. Disabled when resolving a pinned tool.
+    follow_pins: bool,
 }
 
 impl<'tool> Resolver<'tool> {
@@ -21,6 +32,8 @@ impl<'tool> Resolver<'tool> {
         Self {
             tool,
             data: VersionResolver::default(),
+            held_back: None,
+            follow_pins: true,
         }
     }
 
@@ -97,11 +110,19 @@ impl<'tool> Resolver<'tool> {
         resolver.with_manifest(&self.tool.inventory.manifest);
 
         let config = self.tool.proto.load_config()?;
+        let tool_config = config.get_tool_config(&self.tool.context);
 
-        if let Some(tool_config) = config.get_tool_config(&self.tool.context) {
+        if let Some(tool_config) = tool_config {
             resolver.with_config(tool_config);
         }
 
+        if let Some(days) = tool_config
+            .and_then(|cfg| cfg.minimum_release_age)
+            .or(config.settings.minimum_release_age)
+        {
+            resolver.with_minimum_release_age(days);
+        }
+
         self.data = resolver;
 
         Ok(())
@@ -158,11 +179,124 @@ impl<'tool> Resolver<'tool> {
             version
         );
 
+        self.validate_version_policy(&version)?;
+
         spec.resolve(version.clone());
+        spec.version_held_back = self.held_back.take();
 
         Ok(version)
     }
 
+    /// Validate the version against the tool's `allow` and `exclude` settings.
+    /// Requirements and ranges skip these versions while resolving, but explicitly
+    /// pinned versions must be validated.
+    pub fn validate_version_policy(&self, version: &VersionSpec) -> Result<(), ProtoResolveError> {
+        let config = self.tool.proto.load_config()?;
+
+        let Some(tool_config) = config.get_tool_config(&self.tool.context) else {
+            return Ok(());
+        };
+
+        if tool_config.is_version_excluded(version) {
+            return Err(ProtoResolveError::ExcludedVersion {
+                tool: self.tool.get_name().to_owned(),
+                version: version.to_string(),
+            });
+        }
+
+        if !tool_config.is_version_allowed(version) {
+            return Err(ProtoResolveError::DisallowedVersion {
+                tool: self.tool.get_name().to_owned(),
+                version: version.to_string(),
+                allow: tool_config
+                    .get_allow_requirements()
+                    .into_iter()
+                    .map(|allow| allow.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and "),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check the operation against all configured policy plugins, and
+    /// return an error for the first policy that denies it.
+    #[instrument(skip(self, spec))]
+    pub async fn check_policies(
+        &self,
+        operation: PolicyOperation,
+        spec: &ToolSpec,
+    ) -> Result<(), ProtoResolveError> {
+        let proto = &self.tool.proto;
+        let config = proto.load_config()?;
+
+        if config.plugins.policies.is_empty() {
+            return Ok(());
+        }
+
+        let record = Locker::new(self.tool)
+            .get_resolved_locked_record(spec)
+            .map(|record| PolicyLockRecord {
+                checksum: record.checksum.clone(),
+                source: record.source.clone(),
+                version: record.version.clone(),
+            });
+        let version = spec.version.clone().or_else(|| {
+            spec.req
+                .is_fully_qualified()
+                .then(|| spec.req.to_resolved_spec())
+        });
+
+        for (id, plugin) in load_policy_plugins(proto).await? {
+            // Fail closed, as a policy that can't be checked must not allow anything
+            if !plugin.has_func(PluginFunction::CheckPolicy).await {
+                return Err(ProtoResolveError::MissingPolicyFunction {
+                    func: PluginFunction::CheckPolicy.as_str().into(),
+                    policy: id.to_string(),
+                });
+            }
+
+            debug!(
+                tool = self.tool.context.as_str(),
+                policy = id.as_str(),
+                operation = ?operation,
+                "Checking policy",
+            );
+
+            let output: PolicyCheckOutput = plugin
+                .call_func_with(
+                    PluginFunction::CheckPolicy,
+                    PolicyCheckInput {
+                        context: self.tool.create_plugin_unresolved_context(),
+                        operation,
+                        id: self.tool.get_id().to_owned(),
+                        backend: self.tool.get_backend().cloned(),
+                        spec: spec.req.clone(),
+                        version: version.clone(),
+                        record: record.clone(),
+                    },
+                )
+                .await?;
+
+            if !output.allowed {
+                return Err(ProtoResolveError::DeniedByPolicy {
+                    operation: operation.to_string(),
+                    policy: id.to_string(),
+                    tool: self.tool.get_name().to_owned(),
+                    version: version
+                        .map(|version| version.to_string())
+                        .unwrap_or_else(|| spec.req.to_string()),
+                    message: output
+                        .message
+                        .unwrap_or_else(|| "no reason provided".into()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     pub async fn resolve_version_candidate(
         &mut self,
@@ -238,7 +372,7 @@ impl<'tool> Resolver<'tool> {
 
             version = self
                 .resolve_version_from_list(&candidate, resolve_from_manifest)
-                .await;
+                .await?;
         }
 
         version.ok_or_else(|| ProtoResolveError::FailedVersionResolve {
@@ -251,14 +385,78 @@ impl<'tool> Resolver<'tool> {
     /// calling the plugin to validate and choose.
     #[instrument(skip(self))]
     pub async fn resolve_version_from_list(
-        &self,
+        &mut self,
         candidate: &UnresolvedVersionSpec,
         with_manifest: bool,
-    ) -> Option<VersionSpec> {
-        if with_manifest {
-            self.data.resolve(candidate)
-        } else {
-            self.data.resolve_without_manifest(candidate)
-        }
+    ) -> Result<Option<VersionSpec>, ProtoResolveError> {
+        self.resolve_pins(candidate).await?;
+
+        let (version, held_back) = self.data.resolve_with_held_back(candidate, with_manifest)?;
+
+        self.held_back = held_back;
+
+        Ok(version)
+    }
+
+    /// Resolve the versions pinned for other tools that are referenced by
+    /// `pin/<tool>` alias expressions, through each tool's own versions and
+    /// aliases, so that the expressions match an actual version. The future is
+    /// boxed, as pinned tools are resolved recursively.
+    fn resolve_pins<'a>(
+        &'a mut self,
+        candidate: &'a UnresolvedVersionSpec,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ProtoResolveError>> + Send + 'a>> {
+        Box::pin(async move {
+            if !self.follow_pins {
+                return Ok(());
+            }
+
+            let tools = self.data.get_pinned_tools(candidate);
+
+            if tools.is_empty() {
+                return Ok(());
+            }
+
+            let config = self.tool.proto.load_config()?;
+
+            for id in tools {
+                if id == self.tool.context.id || self.data.pins.contains_key(id.as_str()) {
+                    continue;
+                }
+
+                let Some((context, pinned_spec)) =
+                    config.versions.iter().find(|(context, _)| context.id == id)
+                else {
+                    continue;
+                };
+
+                // Pinned versions that are also pin expressions may reference each other
+                if matches!(
+                    AliasExpression::from_spec(&pinned_spec.req),
+                    Some(AliasExpression::Pin { .. })
+                ) {
+                    continue;
+                }
+
+                debug!(
+                    tool = self.tool.context.as_str(),
+                    pinned_tool = context.as_str(),
+                    spec = pinned_spec.to_string(),
+                    "Resolving the version pinned for another tool",
+                );
+
+                let pinned_tool = load_tool(context, &self.tool.proto).await?;
+                let mut pinned_spec = pinned_spec.to_owned();
+                let mut resolver = Resolver::new(&pinned_tool);
+                resolver.follow_pins = false;
+
+                let version = resolver.resolve_version(&mut pinned_spec, false).await?;
+
+                self.data
+                    .with_pins([(id.to_string(), version.to_unresolved_spec())]);
+            }
+
+            Ok(())
+        })
     }
 }
@@ -4,6 +4,7 @@ use crate::config_error::ProtoConfigError;
 use crate::flow::lock::ProtoLockError;
 use crate::id::IdError;
 use crate::layout::ProtoLayoutError;
+use crate::loader_error::ProtoLoaderError;
 use starbase_styles::{Style, Stylize};
 use starbase_utils::fs::FsError;
 use std::path::PathBuf;
@@ -32,6 +33,10 @@ pub enum ProtoResolveError {
     #[error(transparent)]
     Layout(#[from] Box<ProtoLayoutError>),
 
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Loader(#[from] Box<ProtoLoaderError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Lock(#[from] Box<ProtoLockError>),
@@ -75,6 +80,79 @@ pub enum ProtoResolveError {
         .version.style(Style::Hash),
     )]
     FailedVersionResolve { tool: String, version: String },
+
+    #[diagnostic(
+        code(proto::resolve::yanked_version),
+        help = "Pin the version explicitly to use it anyway, or pin another version."
+    )]
+    #[error(
+        "Alias {} resolved to version {}, which has been yanked.",
+        .alias.style(Style::Id),
+        .version.style(Style::Hash),
+    )]
+    YankedAliasVersion { alias: String, version: String },
+
+    #[diagnostic(
+        code(proto::resolve::missing_lts_metadata),
+        help = "The tool's plugin must mark LTS versions in its version metadata."
+    )]
+    #[error(
+        "Unable to resolve {}, as the tool does not provide long-term support (LTS) metadata for its versions.",
+        .expression.style(Style::Id),
+    )]
+    MissingLtsMetadata { expression: String },
+
+    #[diagnostic(
+        code(proto::resolve::excluded_version),
+        help = "Remove the version from the tool's exclude list, or pin another version."
+    )]
+    #[error(
+        "Version {} of {tool} has been excluded by the {} setting.",
+        .version.style(Style::Hash),
+        "exclude".style(Style::Property),
+    )]
+    ExcludedVersion { tool: String, version: String },
+
+    #[diagnostic(
+        code(proto::resolve::disallowed_version),
+        help = "Pin a version that satisfies the allowed range."
+    )]
+    #[error(
+        "Version {} of {tool} is not allowed, as it does not satisfy the {} range {}.",
+        .version.style(Style::Hash),
+        "allow".style(Style::Property),
+        .allow.style(Style::Hash),
+    )]
+    DisallowedVersion {
+        tool: String,
+        version: String,
+        allow: String,
+    },
+
+    #[diagnostic(
+        code(proto::resolve::missing_policy_function),
+        help = "Policy plugins must implement this function, or be removed from [plugins.policies]."
+    )]
+    #[error(
+        "Policy plugin {} does not implement the {} function, so operations can't be checked against it.",
+        .policy.style(Style::Id),
+        .func.style(Style::Property),
+    )]
+    MissingPolicyFunction { func: String, policy: String },
+
+    #[diagnostic(code(proto::resolve::denied_by_policy))]
+    #[error(
+        "Unable to {operation} {tool} {}, as it was denied by the {} policy: {message}",
+        .version.style(Style::Hash),
+        .policy.style(Style::Id),
+    )]
+    DeniedByPolicy {
+        operation: String,
+        policy: String,
+        tool: String,
+        version: String,
+        message: String,
+    },
 }
 
 impl From<WarpgateHttpClientError> for ProtoResolveError {
@@ -101,6 +179,12 @@ impl From<ProtoLayoutError> for ProtoResolveError {
     }
 }
 
+impl From<ProtoLoaderError> for ProtoResolveError {
+    fn from(e: ProtoLoaderError) -> ProtoResolveError {
+        ProtoResolveError::Loader(Box::new(e))
+    }
+}
+
 impl From<ProtoLockError> for ProtoResolveError {
     fn from(e: ProtoLockError) -> ProtoResolveError {
         ProtoResolveError::Lock(Box::new(e))
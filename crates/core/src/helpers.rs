@@ -1,3 +1,4 @@
+use crate::layout::is_linked;
 use ai_env::AiNetworkPolicy;
 use regex::Regex;
 use serde::Serialize;
@@ -5,7 +6,7 @@ use serde::de::DeserializeOwned;
 use starbase_archive::is_supported_archive_extension;
 use starbase_utils::{
     envx::{self, bool_var},
-    fs,
+    fs::{self, FsError},
     json::{self, JsonError},
     net,
 };
@@ -126,6 +127,13 @@ pub fn now() -> u128 {
         .unwrap_or(0)
 }
 
+/// Format the timestamp as an ISO 8601 calendar date (`YYYY-MM-DD`) in UTC.
+pub fn format_iso_date(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
 pub fn read_json_file_with_lock<T: DeserializeOwned>(
     path: impl AsRef<Path>,
 ) -> Result<T, JsonError> {
@@ -173,8 +181,6 @@ pub fn write_json_file_atomic<T: Serialize>(
     path: impl AsRef<Path>,
     data: &T,
 ) -> Result<(), JsonError> {
-    static TEMP_COUNT: AtomicU64 = AtomicU64::new(0);
-
     let path = path.as_ref();
 
     let data = json::serde_json::to_string_pretty(data).map_err(|error| JsonError::WriteFile {
@@ -182,6 +188,17 @@ pub fn write_json_file_atomic<T: Serialize>(
         error: Box::new(error),
     })?;
 
+    write_file_atomic(path, data)?;
+
+    Ok(())
+}
+
+/// Write a file by first writing to a temporary file in the same directory,
+/// then atomically renaming it over the destination.
+pub fn write_file_atomic(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> Result<(), FsError> {
+    static TEMP_COUNT: AtomicU64 = AtomicU64::new(0);
+
+    let path = path.as_ref();
     let temp_path = path.with_extension(format!(
         "{}-{}.tmp",
         std::process::id(),
@@ -193,12 +210,122 @@ pub fn write_json_file_atomic<T: Serialize>(
     if let Err(error) = fs::rename(&temp_path, path) {
         let _ = fs::remove_file(&temp_path);
 
-        return Err(error.into());
+        return Err(error);
     }
 
     Ok(())
 }
 
+/// Recursively remove (or restore) write permissions for all files and
+/// directories within the provided directory. The directory itself is
+/// left untouched, so that proto can continue to write metadata into it.
+/// Symlinks are not followed, and write permissions are not restored for
+/// files linked through the content index, as they are shared with other
+/// versions, and must be detached instead.
+pub fn set_read_only_recursive(dir: impl AsRef<Path>, read_only: bool) -> Result<(), FsError> {
+    let dir = dir.as_ref();
+
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_symlink() {
+            continue;
+        }
+
+        // Directories must be writable before their children can be
+        // modified, and read-only after their children have been
+        if file_type.is_dir() {
+            if read_only {
+                set_read_only_recursive(&path, read_only)?;
+                set_read_only(&path, read_only)?;
+            } else {
+                set_read_only(&path, read_only)?;
+                set_read_only_recursive(&path, read_only)?;
+            }
+        } else if read_only || !is_linked(&path).unwrap_or_default() {
+            set_read_only(&path, read_only)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_read_only(path: &Path, read_only: bool) -> Result<(), FsError> {
+    let handle_error = |error: std::io::Error| FsError::Perms {
+        path: path.to_path_buf(),
+        error: Box::new(error),
+    };
+
+    let mut perms = std::fs::symlink_metadata(path)
+        .map_err(handle_error)?
+        .permissions();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = perms.mode();
+
+        perms.set_mode(if read_only {
+            mode & !0o222
+        } else {
+            mode | 0o200
+        });
+    }
+
+    #[cfg(windows)]
+    {
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(read_only);
+    }
+
+    std::fs::set_permissions(path, perms).map_err(handle_error)
+}
+
+/// Replace all occurrences of the `from` path within the provided string with
+/// the `to` path. An occurrence is only replaced when it's not followed by
+/// another path name character, so that `/a/.proto` does not match `/a/.proto2`.
+/// Returns `None` if nothing was replaced.
+pub fn replace_path_in_string(value: &str, from: &str, to: &str) -> Option<String> {
+    if from.is_empty() || !value.contains(from) {
+        return None;
+    }
+
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut replaced = false;
+
+    while let Some(index) = rest.find(from) {
+        let after = &rest[index + from.len()..];
+
+        result.push_str(&rest[..index]);
+
+        if after
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_' || ch == '.')
+        {
+            result.push_str(from);
+        } else {
+            result.push_str(to);
+            replaced = true;
+        }
+
+        rest = after;
+    }
+
+    result.push_str(rest);
+
+    replaced.then_some(result)
+}
+
 /// Cloning an entire map, like `IndexMap`, is very costly as it clones the entire structure.
 /// This helper allows you to clone just the keys and values, which is much faster if you
 /// don't need the map features.
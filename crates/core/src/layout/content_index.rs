@@ -0,0 +1,300 @@
+use super::layout_error::ProtoLayoutError;
+use crate::config::DedupeMode;
+use crate::helpers::set_read_only;
+use serde::Serialize;
+use starbase_utils::fs;
+use starbase_utils::hash;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, trace};
+
+// Linking small files saves very little space,
+// and isn't worth the cost of hashing them
+const MIN_FILE_SIZE: u64 = 1024;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DedupeStats {
+    /// Number of files that were linked to the content index.
+    pub files: usize,
+    /// Total size of the linked files, in bytes.
+    pub size: u64,
+}
+
+impl DedupeStats {
+    pub fn merge(&mut self, other: DedupeStats) {
+        self.files += other.files;
+        self.size += other.size;
+    }
+}
+
+/// A content-addressable index of files within product directories.
+/// Every entry is a hard link to the first file that was indexed with
+/// that content, and identical files in other product directories are
+/// then hard linked (or reflinked) to the entry. Since the entry is a
+/// link itself, uninstalling a product does not affect its siblings.
+///
+/// Hard linked files share their contents with every sibling, so they are
+/// made read-only, and must be detached (copied) before being written to.
+pub struct ContentIndex {
+    pub dir: PathBuf,
+}
+
+impl ContentIndex {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Deduplicate all files within the provided directory against the index.
+    #[instrument(skip(self))]
+    pub fn dedupe_dir<P: AsRef<Path> + Debug>(
+        &self,
+        dir: P,
+        mode: DedupeMode,
+    ) -> Result<DedupeStats, ProtoLayoutError> {
+        let dir = dir.as_ref();
+        let mut stats = DedupeStats::default();
+
+        if matches!(mode, DedupeMode::Off) || !dir.is_dir() {
+            return Ok(stats);
+        }
+
+        // Linked files can't be detected on Windows with stable Rust,
+        // so they could never be detached or pruned, and the store
+        // size would be over-counted
+        if cfg!(not(unix)) {
+            debug!(dir = ?dir, "Deduplication is not supported on this platform, skipping");
+
+            return Ok(stats);
+        }
+
+        debug!(dir = ?dir, mode = ?mode, "Deduplicating files");
+
+        self.dedupe_dir_recursive(dir, mode, &mut stats)?;
+
+        debug!(
+            dir = ?dir,
+            files = stats.files,
+            size = stats.size,
+            "Deduplicated files"
+        );
+
+        Ok(stats)
+    }
+
+    /// Replace all files within the provided directory that are linked to
+    /// another file with a copy, so that the directory can be modified in
+    /// place without affecting other product directories.
+    #[instrument(skip(self))]
+    pub fn detach_dir<P: AsRef<Path> + Debug>(&self, dir: P) -> Result<(), ProtoLayoutError> {
+        let dir = dir.as_ref();
+
+        if !dir.is_dir() {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                self.detach_dir(&path)?;
+            } else if file_type.is_file() && is_linked(&path)? {
+                trace!(file = ?path, "Detaching file from content index");
+
+                let temp_path = create_temp_path(&path);
+
+                fs::copy_file(&path, &temp_path)?;
+                fs::rename(&temp_path, &path)?;
+
+                // The copy inherits the read-only permissions of the link
+                set_read_only(&path, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove entries from the index that are no longer linked
+    /// to a file in any product directory. Returns the removed entries.
+    #[instrument(skip(self))]
+    pub fn prune(&self) -> Result<Vec<PathBuf>, ProtoLayoutError> {
+        let mut pruned = vec![];
+
+        if !self.dir.exists() {
+            return Ok(pruned);
+        }
+
+        for bucket in fs::read_dir(&self.dir)? {
+            let bucket_dir = bucket.path();
+
+            if !bucket_dir.is_dir() {
+                continue;
+            }
+
+            for entry in fs::read_dir(&bucket_dir)? {
+                let path = entry.path();
+
+                if path.is_file() && !is_linked(&path)? {
+                    trace!(file = ?path, "Removing unlinked content index entry");
+
+                    fs::remove_file(&path)?;
+                    pruned.push(path);
+                }
+            }
+
+            // Ignore failures, as the bucket may not be empty
+            let _ = std::fs::remove_dir(&bucket_dir);
+        }
+
+        Ok(pruned)
+    }
+
+    fn dedupe_dir_recursive(
+        &self,
+        dir: &Path,
+        mode: DedupeMode,
+        stats: &mut DedupeStats,
+    ) -> Result<(), ProtoLayoutError> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                self.dedupe_dir_recursive(&path, mode, stats)?;
+            } else if file_type.is_file() {
+                let size = fs::metadata(&path)?.len();
+
+                if size >= MIN_FILE_SIZE && self.dedupe_file(&path, mode)? {
+                    stats.files += 1;
+                    stats.size += size;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dedupe_file(&self, path: &Path, mode: DedupeMode) -> Result<bool, ProtoLayoutError> {
+        let hash = hash_file(path)?;
+
+        // Include the executable bit, as hard links share permissions
+        let key = if fs::is_executable(path) {
+            format!("{hash}x")
+        } else {
+            hash.clone()
+        };
+        let entry_path = self.dir.join(&key[0..2]).join(&key);
+
+        // First occurrence, so index this file
+        if !entry_path.exists() {
+            fs::create_dir_all(entry_path.parent().unwrap())?;
+
+            if let Err(error) = std::fs::hard_link(path, &entry_path) {
+                debug!(
+                    file = ?path,
+                    "Failed to add file to content index, skipping: {error}"
+                );
+            }
+
+            return Ok(false);
+        }
+
+        if is_same_file(path, &entry_path)? {
+            return Ok(false);
+        }
+
+        // The entry may have been modified in place through another link,
+        // so ensure the content still matches, otherwise index this file
+        if hash_file(&entry_path)? != hash {
+            debug!(
+                file = ?path,
+                entry = ?entry_path,
+                "Content index entry has been modified, replacing"
+            );
+
+            let temp_path = create_temp_path(&entry_path);
+
+            if std::fs::hard_link(path, &temp_path).is_ok() {
+                fs::rename(&temp_path, &entry_path)?;
+            }
+
+            return Ok(false);
+        }
+
+        // Link to a temporary file first, and then rename over the
+        // original, so that the file is never missing or partial
+        let temp_path = create_temp_path(path);
+        let _ = std::fs::remove_file(&temp_path);
+
+        let result = match mode {
+            DedupeMode::Hardlink => std::fs::hard_link(&entry_path, &temp_path),
+            DedupeMode::Reflink => reflink_copy::reflink(&entry_path, &temp_path).and_then(|_| {
+                std::fs::set_permissions(&temp_path, std::fs::metadata(path)?.permissions())
+            }),
+            DedupeMode::Off => return Ok(false),
+        };
+
+        if let Err(error) = result {
+            debug!(
+                file = ?path,
+                "Failed to link file to content index, skipping: {error}"
+            );
+
+            let _ = std::fs::remove_file(&temp_path);
+
+            return Ok(false);
+        }
+
+        fs::rename(&temp_path, path)?;
+
+        // Writing to a hard link would change every sibling,
+        // so remove write permissions from the shared file
+        if matches!(mode, DedupeMode::Hardlink) {
+            set_read_only(path, true)?;
+        }
+
+        Ok(true)
+    }
+}
+
+fn create_temp_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!(".{}.dedupe", fs::file_name(path)))
+}
+
+fn hash_file(path: &Path) -> Result<String, ProtoLayoutError> {
+    Ok(hash::sha256::from_file(path)?)
+}
+
+#[cfg(unix)]
+pub(crate) fn is_linked(path: &Path) -> Result<bool, ProtoLayoutError> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(fs::metadata(path)?.nlink() > 1)
+}
+
+// Files are never deduplicated on Windows, so they are never linked
+#[cfg(not(unix))]
+pub(crate) fn is_linked(_path: &Path) -> Result<bool, ProtoLayoutError> {
+    Ok(false)
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> Result<bool, ProtoLayoutError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let a = fs::metadata(a)?;
+    let b = fs::metadata(b)?;
+
+    Ok(a.dev() == b.dev() && a.ino() == b.ino())
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_a: &Path, _b: &Path) -> Result<bool, ProtoLayoutError> {
+    Ok(false)
+}
@@ -1,3 +1,4 @@
+use super::bin_manager::BinManager;
 use super::layout_error::ProtoLayoutError;
 use super::product::Product;
 use crate::helpers::{is_cache_enabled, is_offline};
@@ -17,10 +18,28 @@ pub struct Inventory {
     pub dir: PathBuf,
     pub dir_original: Option<PathBuf>,
     pub manifest: ToolManifest,
+    pub system_dir: Option<PathBuf>,
+    pub system_manifest: Option<ToolManifest>,
     pub temp_dir: PathBuf,
 }
 
 impl Inventory {
+    /// Create a bin manager seeded with the versions installed in both
+    /// the user store and the system store.
+    pub fn create_bin_manager(&self) -> BinManager {
+        let mut manager = BinManager::from_manifest(&self.manifest);
+
+        if let Some(manifest) = &self.system_manifest {
+            for version in &manifest.installed_versions {
+                if self.get_system_product_dir(version).is_some() {
+                    manager.add_version(version);
+                }
+            }
+        }
+
+        manager
+    }
+
     pub fn create_product(&self, version: &VersionSpec) -> Product {
         Product {
             dir: self.get_product_dir(version),
@@ -36,13 +55,41 @@ impl Inventory {
     }
 
     pub fn get_product_dir(&self, version: &VersionSpec) -> PathBuf {
+        self.dir.join(self.get_product_dir_name(version))
+    }
+
+    /// Return the product directory within the system store,
+    /// but only if the version has been installed there.
+    pub fn get_system_product_dir(&self, version: &VersionSpec) -> Option<PathBuf> {
+        let (Some(dir), Some(manifest)) = (&self.system_dir, &self.system_manifest) else {
+            return None;
+        };
+
+        if !manifest.installed_versions.contains(version) {
+            return None;
+        }
+
+        let product_dir = dir.join(self.get_product_dir_name(version));
+
+        product_dir.exists().then_some(product_dir)
+    }
+
+    /// Return the product directory of an installed version, preferring
+    /// the system store over the user store. Use [`Inventory::get_product_dir`]
+    /// when writing to the directory, as the system store is read-only.
+    pub fn locate_product_dir(&self, version: &VersionSpec) -> PathBuf {
+        self.get_system_product_dir(version)
+            .unwrap_or_else(|| self.get_product_dir(version))
+    }
+
+    fn get_product_dir_name(&self, version: &VersionSpec) -> String {
         let mut name = version.to_string();
 
         if let Some(suffix) = &self.config.version_suffix {
             name = format!("{name}{suffix}");
         }
 
-        self.dir.join(path::encode_component(name))
+        path::encode_component(name)
     }
 
     #[instrument(skip(self))]
@@ -83,6 +130,22 @@ impl Inventory {
         Ok(None)
     }
 
+    /// Load the cached remote versions regardless of their age,
+    /// or return `None` if they have never been cached.
+    #[instrument(skip(self))]
+    pub fn load_cached_remote_versions(
+        &self,
+        scope: Option<&str>,
+    ) -> Result<Option<LoadVersionsOutput>, ProtoLayoutError> {
+        let cache_path = self.get_remote_versions_cache_path(scope);
+
+        if cache_path.exists() {
+            return Ok(Some(json::read_file(&cache_path)?));
+        }
+
+        Ok(None)
+    }
+
     #[instrument(skip_all)]
     pub fn save_remote_versions(
         &self,
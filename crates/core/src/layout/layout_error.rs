@@ -1,5 +1,6 @@
 use starbase_styles::{Style, Stylize};
 use starbase_utils::fs::FsError;
+use starbase_utils::hash::HashError;
 use starbase_utils::json::JsonError;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -10,6 +11,10 @@ pub enum ProtoLayoutError {
     #[error(transparent)]
     Fs(#[from] Box<FsError>),
 
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    Hash(#[from] Box<HashError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Json(#[from] Box<JsonError>),
@@ -38,6 +43,12 @@ impl From<FsError> for ProtoLayoutError {
     }
 }
 
+impl From<HashError> for ProtoLayoutError {
+    fn from(e: HashError) -> ProtoLayoutError {
+        ProtoLayoutError::Hash(Box::new(e))
+    }
+}
+
 impl From<JsonError> for ProtoLayoutError {
     fn from(e: JsonError) -> ProtoLayoutError {
         ProtoLayoutError::Json(Box::new(e))
@@ -1,13 +1,19 @@
 mod bin_manager;
+mod content_index;
 mod inventory;
 mod layout_error;
 mod product;
+mod project_registry;
+mod shim_cache;
 mod shim_registry;
 mod store;
 
 pub use bin_manager::*;
+pub use content_index::*;
 pub use inventory::*;
 pub use layout_error::*;
 pub use product::*;
+pub use project_registry::*;
+pub use shim_cache::*;
 pub use shim_registry::*;
 pub use store::*;
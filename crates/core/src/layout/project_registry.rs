@@ -0,0 +1,153 @@
+use super::layout_error::ProtoLayoutError;
+use crate::config::PROTO_CONFIG_NAME;
+use crate::helpers::write_json_file_atomic;
+use crate::lockfile::PROTO_LOCK_NAME;
+use serde::{Deserialize, Serialize};
+use starbase_utils::fs;
+use starbase_utils::json::{self, JsonError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+// Only refresh the last seen timestamp once a day,
+// so that we're not writing the file on every run
+const REFRESH_INTERVAL: u128 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Project {
+    pub last_seen_at: u128,
+}
+
+pub type ProjectsMap = BTreeMap<PathBuf, Project>;
+
+/// A registry of project directories that contain a `.prototools` or
+/// `.protolock` file that proto has loaded. Used for determining which
+/// tool versions are still referenced when cleaning the store.
+pub struct ProjectRegistry {
+    pub projects: ProjectsMap,
+
+    changed: ProjectsMap,
+    removed: BTreeSet<PathBuf>,
+    path: PathBuf,
+}
+
+impl ProjectRegistry {
+    pub fn load_from<P: AsRef<Path>>(dir: P) -> Result<Self, ProtoLayoutError> {
+        Self::load(dir.as_ref().join("projects.json"))
+    }
+
+    #[instrument(name = "load_project_registry")]
+    pub fn load<P: AsRef<Path> + Debug>(path: P) -> Result<Self, ProtoLayoutError> {
+        let path = path.as_ref();
+
+        debug!(file = ?path, "Loading projects registry");
+
+        Ok(Self {
+            projects: read_projects_map(path)?,
+            changed: ProjectsMap::default(),
+            removed: BTreeSet::default(),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Register a project directory. Returns true if the registry
+    /// was modified and needs to be saved.
+    pub fn register(&mut self, dir: &Path, now: u128) -> bool {
+        if self
+            .projects
+            .get(dir)
+            .is_some_and(|project| now.saturating_sub(project.last_seen_at) < REFRESH_INTERVAL)
+        {
+            return false;
+        }
+
+        let project = Project { last_seen_at: now };
+
+        self.projects.insert(dir.to_path_buf(), project.clone());
+        self.changed.insert(dir.to_path_buf(), project);
+        self.removed.remove(dir);
+
+        true
+    }
+
+    /// Remove projects that no longer exist, or no longer contain
+    /// a `.prototools` or `.protolock` file. Returns the removed directories.
+    pub fn prune(&mut self) -> Vec<PathBuf> {
+        let stale = self
+            .projects
+            .keys()
+            .filter(|dir| !is_project_dir(dir))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for dir in &stale {
+            debug!(dir = ?dir, "Project no longer exists, removing from registry");
+
+            self.projects.remove(dir);
+            self.changed.remove(dir);
+            self.removed.insert(dir.to_owned());
+        }
+
+        stale
+    }
+
+    #[instrument(name = "save_project_registry", skip(self))]
+    pub fn save(&mut self) -> Result<(), ProtoLayoutError> {
+        if self.changed.is_empty() && self.removed.is_empty() {
+            return Ok(());
+        }
+
+        debug!(file = ?self.path, "Saving projects registry");
+
+        // Multiple proto processes may be running at once, so merge
+        // our changes into a fresh read of the file while locked
+        let _lock = fs::lock_file(self.path.with_extension("lock"))?;
+
+        let mut projects = read_projects_map(&self.path)?;
+
+        for dir in &self.removed {
+            projects.remove(dir);
+        }
+
+        projects.extend(self.changed.clone());
+
+        write_json_file_atomic(&self.path, &projects)?;
+
+        self.projects = projects;
+        self.changed.clear();
+        self.removed.clear();
+
+        Ok(())
+    }
+}
+
+pub fn is_project_dir(dir: &Path) -> bool {
+    dir.join(PROTO_CONFIG_NAME).exists() || dir.join(PROTO_LOCK_NAME).exists()
+}
+
+fn read_projects_map(path: &Path) -> Result<ProjectsMap, ProtoLayoutError> {
+    if !path.exists() {
+        return Ok(ProjectsMap::default());
+    }
+
+    let content = fs::read_file(path)?;
+
+    if content.trim().is_empty() {
+        warn!(
+            file = ?path,
+            "Projects registry file is unexpectedly empty, treating it as an empty registry"
+        );
+
+        return Ok(ProjectsMap::default());
+    }
+
+    let projects: ProjectsMap =
+        json::serde_json::from_str(&content).map_err(|error| JsonError::ReadFile {
+            path: path.to_path_buf(),
+            error: Box::new(error),
+        })?;
+
+    Ok(projects)
+}
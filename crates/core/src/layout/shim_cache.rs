@@ -0,0 +1,144 @@
+use super::layout_error::ProtoLayoutError;
+use crate::helpers::write_json_file_atomic;
+use proto_shim::{find_changed_cache_input, get_cache_file_name, get_modified_time};
+use serde::{Deserialize, Serialize};
+use starbase_utils::fs;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument};
+
+/// A precomputed resolution for a shim, that allows `proto-shim` to
+/// execute the tool directly, without loading configs or plugins.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ShimCacheEntry {
+    /// Arguments appended after the arguments passed to the shim.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after_args: Vec<String>,
+
+    /// Arguments prepended before the arguments passed to the shim.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before_args: Vec<String>,
+
+    /// Environment variables to set (or remove when `null`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, Option<String>>,
+
+    /// The executable to run.
+    pub exe: PathBuf,
+
+    /// Files that were used for resolution, mapped to their last modified
+    /// time, or `null` if they did not exist. If any of these change, the
+    /// entry is stale.
+    pub files: BTreeMap<PathBuf, Option<u64>>,
+
+    /// Directories to prepend to `PATH`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PathBuf>,
+
+    /// Tool directories to mark as used when executed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub products: Vec<PathBuf>,
+
+    /// Argument sequences that trigger the tool's native self-upgrade,
+    /// which must always go through `proto run` to be blocked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub self_upgrade_args: Vec<Vec<String>>,
+
+    /// Environment variables that were used for resolution, mapped to their
+    /// value, or `null` if they were not set. If any of these change, the
+    /// entry is stale.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, Option<String>>,
+}
+
+impl ShimCacheEntry {
+    /// Track the provided file, and its current modified time.
+    pub fn track_file(&mut self, path: PathBuf) {
+        let time = get_modified_time(&path);
+
+        self.files.insert(path, time);
+    }
+
+    /// Track the provided environment variable, and its current value.
+    pub fn track_var(&mut self, name: impl AsRef<str>) {
+        let name = name.as_ref();
+        let value = env::var(name).ok().filter(|value| !value.is_empty());
+
+        self.vars.insert(name.to_owned(), value);
+    }
+
+    /// Return true if the tracked files and environment variables have
+    /// not changed, and the executable (if not located on `PATH`) exists.
+    /// This is the same check that the shim performs.
+    pub fn is_fresh(&self) -> bool {
+        find_changed_cache_input(
+            &self.exe,
+            self.files
+                .iter()
+                .map(|(path, time)| (path.as_path(), *time)),
+            self.vars
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_deref())),
+        )
+        .is_none()
+    }
+}
+
+pub type ShimCacheMap = BTreeMap<String, ShimCacheEntry>;
+
+/// Resolution cache for shims executed within a specific directory.
+pub struct ShimCache {
+    pub entries: ShimCacheMap,
+    pub path: PathBuf,
+}
+
+impl ShimCache {
+    pub fn load_from<P: AsRef<Path>>(cache_dir: P, working_dir: &Path) -> Self {
+        Self::load(
+            cache_dir
+                .as_ref()
+                .join("shims")
+                .join(get_cache_file_name(working_dir)),
+        )
+    }
+
+    #[instrument(name = "load_shim_cache")]
+    pub fn load<P: AsRef<Path> + Debug>(path: P) -> Self {
+        let path = path.as_ref();
+
+        // This is only a cache, so treat missing or
+        // malformed files as an empty cache
+        let entries = fs::read_file(path)
+            .ok()
+            .and_then(|content| json::serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            entries,
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ShimCacheEntry> {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: impl AsRef<str>, entry: ShimCacheEntry) {
+        self.entries.insert(key.as_ref().to_owned(), entry);
+    }
+
+    /// Write the cache atomically. Concurrent writers may overwrite each
+    /// other's entries, which is fine, as they're recomputed on the next miss.
+    #[instrument(name = "save_shim_cache", skip(self))]
+    pub fn save(&self) -> Result<(), ProtoLayoutError> {
+        debug!(file = ?self.path, "Saving shim cache");
+
+        write_json_file_atomic(&self.path, &self.entries)?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use super::layout_error::ProtoLayoutError;
-use crate::helpers::write_json_file_atomic;
+use crate::helpers::{replace_path_in_string, write_json_file_atomic};
 use crate::tool_context::ToolContext;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -26,6 +26,10 @@ pub struct Shim {
     #[serde(skip_serializing_if = "Option::is_none", alias = "parent")]
     pub context: Option<ToolContext>,
 
+    /// Defined in `.prototools` with `[shims.*]`, instead of by a plugin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<bool>,
+
     #[serde(skip_serializing_if = "FxHashMap::is_empty")]
     pub env_vars: FxHashMap<String, String>,
 }
@@ -38,6 +42,7 @@ pub struct ShimRegistry {
 
     changed: ShimsMap,
     path: PathBuf,
+    removed: ShimsMap,
 }
 
 impl ShimRegistry {
@@ -55,6 +60,7 @@ impl ShimRegistry {
             shims: read_shims_map(path)?,
             path: path.to_path_buf(),
             changed: ShimsMap::default(),
+            removed: ShimsMap::default(),
         })
     }
 
@@ -62,18 +68,76 @@ impl ShimRegistry {
         self.changed.get(key).or_else(|| self.shims.get(key))
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Shim)> {
+        self.shims.iter()
+    }
+
     #[instrument(name = "update_shim_registry", skip(self))]
     pub fn update(&mut self, key: String, value: Shim) -> Result<(), ProtoLayoutError> {
         if apply_shim_update(&mut self.shims, &key, &value) {
+            self.removed.remove(&key);
             self.changed.insert(key, value);
         }
 
         Ok(())
     }
 
+    /// Remove a shim from the registry. When saved, the entry is only removed
+    /// from the file if it hasn't been changed by another process since.
+    #[instrument(name = "remove_from_shim_registry", skip(self))]
+    pub fn remove(&mut self, key: &str) -> Option<Shim> {
+        let shim = self.shims.remove(key)?;
+
+        self.changed.remove(key);
+        self.removed.insert(key.to_owned(), shim.clone());
+
+        Some(shim)
+    }
+
+    /// Replace the `from` path with the `to` path in the arguments and
+    /// environment variables of all shims, for example, when the store
+    /// has been relocated. Returns the names of the shims that changed.
+    #[instrument(name = "rewrite_shim_registry_paths", skip(self))]
+    pub fn replace_path(
+        &mut self,
+        from: &Path,
+        to: &Path,
+    ) -> Result<Vec<String>, ProtoLayoutError> {
+        let from = from.to_string_lossy();
+        let to = to.to_string_lossy();
+        let mut changed = vec![];
+
+        for (key, shim) in self.shims.clone() {
+            let mut next = shim.clone();
+
+            for arg in next
+                .before_args
+                .iter_mut()
+                .chain(next.after_args.iter_mut())
+            {
+                if let Some(value) = replace_path_in_string(arg, &from, &to) {
+                    *arg = value;
+                }
+            }
+
+            for value in next.env_vars.values_mut() {
+                if let Some(new_value) = replace_path_in_string(value, &from, &to) {
+                    *value = new_value;
+                }
+            }
+
+            if next != shim {
+                self.update(key.clone(), next)?;
+                changed.push(key);
+            }
+        }
+
+        Ok(changed)
+    }
+
     #[instrument(name = "save_shim_registry", skip(self))]
     pub fn save(&mut self) -> Result<(), ProtoLayoutError> {
-        if self.changed.is_empty() {
+        if self.changed.is_empty() && self.removed.is_empty() {
             return Ok(());
         }
 
@@ -96,12 +160,20 @@ impl ShimRegistry {
             }
         }
 
+        for (key, value) in &self.removed {
+            if shims.get(key).is_some_and(|current| current == value) {
+                shims.remove(key);
+                dirty = true;
+            }
+        }
+
         if dirty {
             write_json_file_atomic(&self.path, &shims)?;
         }
 
         self.shims = shims;
         self.changed.clear();
+        self.removed.clear();
 
         Ok(())
     }
@@ -1,7 +1,7 @@
 use super::inventory::Inventory;
 use super::layout_error::ProtoLayoutError;
 use crate::id::Id;
-use crate::layout::ShimRegistry;
+use crate::layout::{ProjectRegistry, ShimCache, ShimRegistry};
 use crate::tool_manifest::ToolManifest;
 use once_cell::sync::OnceCell;
 use proto_pdk_api::ToolInventoryOptions;
@@ -18,21 +18,27 @@ use tracing::{debug, instrument};
 pub struct Store {
     pub dir: PathBuf,
     pub backends_dir: PathBuf,
+    pub backups_dir: PathBuf,
     pub bin_dir: PathBuf,
     pub builders_dir: PathBuf,
     pub cache_dir: PathBuf,
+    pub dedupe_dir: PathBuf,
     pub inventory_dir: PathBuf,
     pub plugins_dir: PathBuf,
     pub shims_dir: PathBuf,
     pub temp_dir: PathBuf,
 
+    /// A read-only store, typically provisioned by an administrator,
+    /// that is checked for installed tools before this store.
+    pub system_dir: Option<PathBuf>,
+
     #[serde(skip)]
     shim_binary: Arc<OnceCell<Vec<u8>>>,
 }
 
 impl Store {
     #[instrument(name = "create_store")]
-    pub fn new(dir: &Path) -> Self {
+    pub fn new(dir: &Path, system_dir: Option<PathBuf>) -> Self {
         let temp_dir = match envx::path_var("PROTO_TEMP_DIR") {
             Some(custom) => {
                 debug!(
@@ -49,13 +55,16 @@ impl Store {
         Self {
             dir: dir.to_path_buf(),
             backends_dir: dir.join("backends"),
+            backups_dir: dir.join("backups"),
             bin_dir: dir.join("bin"),
             builders_dir: dir.join("builders"),
             cache_dir: dir.join("cache"),
+            dedupe_dir: dir.join("dedupe"),
             inventory_dir: dir.join("tools"),
             plugins_dir: dir.join("plugins"),
             shims_dir: dir.join("shims"),
             temp_dir,
+            system_dir,
             shim_binary: Arc::new(OnceCell::new()),
         }
     }
@@ -66,9 +75,20 @@ impl Store {
         config: &ToolInventoryOptions,
     ) -> Result<Inventory, ProtoLayoutError> {
         let dir = self.inventory_dir.join(path::encode_component(id));
+        let system_dir = self
+            .system_dir
+            .as_ref()
+            .map(|system_dir| system_dir.join("tools").join(path::encode_component(id)));
 
         Ok(Inventory {
             manifest: ToolManifest::load_from(&dir)?,
+            system_manifest: match &system_dir {
+                Some(system_dir) if system_dir.exists() => {
+                    Some(ToolManifest::load_from(system_dir)?)
+                }
+                _ => None,
+            },
+            system_dir,
             dir,
             dir_original: None,
             temp_dir: self.temp_dir.join(path::encode_component(id)),
@@ -116,6 +136,16 @@ impl Store {
         Ok(value.as_ref())
     }
 
+    #[instrument(skip(self))]
+    pub fn load_project_registry(&self) -> Result<ProjectRegistry, ProtoLayoutError> {
+        ProjectRegistry::load_from(&self.dir)
+    }
+
+    #[instrument(skip(self))]
+    pub fn load_shim_cache(&self, working_dir: &Path) -> ShimCache {
+        ShimCache::load_from(&self.cache_dir, working_dir)
+    }
+
     #[instrument(skip(self))]
     pub fn load_shims_registry(&self) -> Result<ShimRegistry, ProtoLayoutError> {
         ShimRegistry::load_from(&self.shims_dir)
@@ -193,10 +223,12 @@ impl fmt::Debug for Store {
             .field("dir", &self.dir)
             .field("bin_dir", &self.bin_dir)
             .field("cache_dir", &self.cache_dir)
+            .field("dedupe_dir", &self.dedupe_dir)
             .field("inventory_dir", &self.inventory_dir)
             .field("plugins_dir", &self.plugins_dir)
             .field("shims_dir", &self.shims_dir)
             .field("temp_dir", &self.temp_dir)
+            .field("system_dir", &self.system_dir)
             .finish()
     }
 }
@@ -1,3 +1,4 @@
+mod alias_expression;
 pub mod checksum;
 mod config;
 mod config_error;
@@ -25,6 +26,7 @@ mod tool_spec;
 pub mod utils;
 mod version_resolver;
 
+pub use alias_expression::*;
 pub use config::*;
 pub use config_error::*;
 pub use env::*;
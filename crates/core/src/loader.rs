@@ -9,8 +9,12 @@ use rustc_hash::FxHashSet;
 use starbase_utils::{json, toml, yaml};
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{debug, instrument, trace, warn};
-use warpgate::{PluginLocator, PluginManifest, Wasm, inject_default_manifest_config};
+use warpgate::host::{HostData, create_host_functions};
+use warpgate::{
+    PluginContainer, PluginLocator, PluginManifest, Wasm, inject_default_manifest_config,
+};
 
 #[instrument(skip(manifest))]
 pub fn inject_proto_manifest_config(
@@ -297,3 +301,76 @@ pub async fn load_tool(
 
     Ok(tool)
 }
+
+pub type PolicyPlugins = Vec<(Id, Arc<PluginContainer>)>;
+
+/// Load all configured policy plugins. Plugins are only loaded once
+/// and are shared by all clones of the provided environment.
+pub async fn load_policy_plugins(
+    proto: &ProtoEnvironment,
+) -> Result<&PolicyPlugins, ProtoLoaderError> {
+    let plugins = proto
+        .policy_plugins
+        .get_or_try_init(|| async {
+            let mut plugins = vec![];
+
+            for (id, locator) in &proto.load_config()?.plugins.policies {
+                plugins.push((
+                    id.to_owned(),
+                    Arc::new(load_policy_plugin(id, proto, locator).await?),
+                ));
+            }
+
+            Ok::<_, ProtoLoaderError>(plugins)
+        })
+        .await?;
+
+    Ok(plugins)
+}
+
+#[instrument]
+pub async fn load_policy_plugin(
+    id: &Id,
+    proto: impl AsRef<ProtoEnvironment> + Debug,
+    locator: impl AsRef<PluginLocator> + Debug,
+) -> Result<PluginContainer, ProtoLoaderError> {
+    let proto = proto.as_ref();
+    let config = proto.load_config()?;
+    let mut locator = locator.as_ref().to_owned();
+
+    // Rewrite if a URL
+    if let PluginLocator::Url(inner) = &mut locator {
+        inner.url = config.rewrite_url(&inner.url);
+    }
+
+    let plugin_loaded = proto
+        .get_plugin_loader()?
+        .load_plugin_with_metadata(id, locator)
+        .await?;
+    let plugin_path = plugin_loaded.path;
+
+    // Policies are code, so they can't be declared with a schema
+    if plugin_path.extension().and_then(|ext| ext.to_str()) != Some("wasm") {
+        return Err(ProtoLoaderError::UnsupportedPolicyPlugin {
+            id: id.to_owned(),
+            path: plugin_path,
+        });
+    }
+
+    debug!(policy = id.as_str(), source = ?plugin_path, "Loading policy plugin");
+
+    let mut manifest = Tool::create_plugin_manifest(proto, Wasm::file(plugin_path))?;
+
+    inject_default_manifest_config(id, &proto.home_dir, &mut manifest)?;
+
+    Ok(PluginContainer::new(
+        id.to_owned(),
+        manifest,
+        create_host_functions(HostData {
+            cache_dir: proto.store.cache_dir.clone(),
+            http_client: Arc::clone(proto.get_plugin_loader()?.get_http_client()?),
+            virtual_paths: proto.get_virtual_paths(),
+            working_dir: proto.working_dir.clone(),
+        }),
+    )?)
+}
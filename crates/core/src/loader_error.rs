@@ -1,15 +1,17 @@
 use crate::config::PROTO_CONFIG_NAME;
 use crate::config_error::ProtoConfigError;
 use crate::flow::resolve::ProtoResolveError;
+use crate::id::Id;
 use crate::tool_context::ToolContext;
 use crate::tool_error::ProtoToolError;
 use starbase_styles::{Style, Stylize};
 use starbase_utils::json::JsonError;
 use starbase_utils::toml::TomlError;
 use starbase_utils::yaml::YamlError;
+use std::path::PathBuf;
 use thiserror::Error;
 use tokio::task::JoinError;
-use warpgate::{IdError, WarpgateLoaderError, WarpgatePluginError};
+use warpgate::{IdError, WarpgateHttpClientError, WarpgateLoaderError, WarpgatePluginError};
 
 #[derive(Error, Debug, miette::Diagnostic)]
 pub enum ProtoLoaderError {
@@ -17,6 +19,10 @@ pub enum ProtoLoaderError {
     #[error(transparent)]
     Config(#[from] Box<ProtoConfigError>),
 
+    #[diagnostic(transparent)]
+    #[error(transparent)]
+    HttpClient(#[from] Box<WarpgateHttpClientError>),
+
     #[diagnostic(transparent)]
     #[error(transparent)]
     Id(#[from] Box<IdError>),
@@ -60,6 +66,17 @@ pub enum ProtoLoaderError {
     )]
     UnknownTool { context: ToolContext },
 
+    #[diagnostic(
+        code(proto::loader::unsupported_policy),
+        help = "Policy plugins must be WASM plugins."
+    )]
+    #[error(
+        "Unable to load policy {}, as {} is not a WASM plugin.",
+        .id.to_string().style(Style::Id),
+        .path.style(Style::Path),
+    )]
+    UnsupportedPolicyPlugin { id: Id, path: PathBuf },
+
     #[diagnostic(code(proto::loader::failed_join))]
     #[error("Failed to load a tool in the background.")]
     FailedJoin {
@@ -74,6 +91,12 @@ impl From<ProtoConfigError> for ProtoLoaderError {
     }
 }
 
+impl From<WarpgateHttpClientError> for ProtoLoaderError {
+    fn from(e: WarpgateHttpClientError) -> ProtoLoaderError {
+        ProtoLoaderError::HttpClient(Box::new(e))
+    }
+}
+
 impl From<IdError> for ProtoLoaderError {
     fn from(e: IdError) -> ProtoLoaderError {
         ProtoLoaderError::Id(Box::new(e))
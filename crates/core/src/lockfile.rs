@@ -1,3 +1,4 @@
+use crate::helpers::write_file_atomic;
 use crate::id::Id;
 use proto_pdk_api::{Checksum, ToolLockOptions};
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,10 @@ pub struct LockRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checksum: Option<Checksum>,
 
+    // Fingerprint of the installed files, see `fingerprint_dir`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
 }
@@ -43,12 +48,19 @@ impl LockRecord {
         let mut record = self.clone();
         record.spec = None;
         record.version = None;
+        // Stored on the manifest version instead
+        record.fingerprint = None;
         record
     }
 
     pub fn for_lockfile(&self) -> Self {
         let mut record = self.clone();
         record.source = None;
+        // Builds from source and native installs have no checksum,
+        // and their files differ between machines
+        if record.checksum.is_none() {
+            record.fingerprint = None;
+        }
         record
     }
 
@@ -141,7 +153,9 @@ impl ProtoLock {
 
         let content = toml::format(self, true)?;
 
-        fs::write_file(
+        // Write atomically so that concurrent processes (or an interrupted
+        // update) never observe a partially written lock file
+        write_file_atomic(
             &self.path,
             format!("# Generated by proto. Do not modify!\n\n{content}"),
         )?;
@@ -4,12 +4,14 @@ mod backends;
 mod plugins;
 mod settings;
 mod shell;
+mod shims;
 mod tools;
 
 pub use backends::*;
 pub use plugins::*;
 pub use settings::*;
 pub use shell::*;
+pub use shims::*;
 pub use tools::*;
 
 use schematic::{Config, ConfigEnum, MergeError, MergeResult, PartialConfig, derive_enum};
@@ -21,6 +23,7 @@ derive_enum!(
     #[derive(ConfigEnum, Copy, Default)]
     pub enum PluginType {
         Backend,
+        Policy,
         #[default]
         Tool,
     }
@@ -54,6 +57,26 @@ impl ConfigMode {
     }
 }
 
+derive_enum!(
+    #[derive(Copy, ConfigEnum, Default)]
+    pub enum BuildSandbox {
+        #[default]
+        Off,
+        Env,
+        Bubblewrap,
+    }
+);
+
+derive_enum!(
+    #[derive(Copy, ConfigEnum, Default)]
+    pub enum DedupeMode {
+        #[default]
+        Off,
+        Hardlink,
+        Reflink,
+    }
+);
+
 derive_enum!(
     #[derive(ConfigEnum, Default)]
     pub enum DetectStrategy {
@@ -81,6 +104,16 @@ derive_enum!(
     }
 );
 
+derive_enum!(
+    #[derive(Copy, ConfigEnum, Default)]
+    #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+    pub enum UpgradeChannel {
+        Canary,
+        #[default]
+        Stable,
+    }
+);
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct EnvFile {
     pub path: PathBuf,
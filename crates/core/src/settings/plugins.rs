@@ -29,6 +29,10 @@ pub struct ProtoPluginsConfig {
     #[setting(merge = merge_iter, validate = validate_reserved_words)]
     pub backends: BTreeMap<Id, PluginLocator>,
 
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[setting(merge = merge_iter, validate = validate_reserved_words)]
+    pub policies: BTreeMap<Id, PluginLocator>,
+
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[setting(merge = merge_iter, validate = validate_reserved_words)]
     pub tools: BTreeMap<Id, PluginLocator>,
@@ -41,10 +45,10 @@ pub struct ProtoPluginsConfig {
 
 impl ProtoPluginsConfig {
     pub fn get(&self, id: &Id, ty: PluginType) -> Option<&PluginLocator> {
-        if ty == PluginType::Backend {
-            self.backends.get(id)
-        } else {
-            self.tools.get(id).or_else(|| self.legacy.get(id))
+        match ty {
+            PluginType::Backend => self.backends.get(id),
+            PluginType::Policy => self.policies.get(id),
+            PluginType::Tool => self.tools.get(id).or_else(|| self.legacy.get(id)),
         }
     }
 }
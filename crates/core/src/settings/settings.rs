@@ -1,4 +1,4 @@
-use super::{DetectStrategy, PinLocation, merge_iter};
+use super::{BuildSandbox, DedupeMode, DetectStrategy, PinLocation, UpgradeChannel, merge_iter};
 use crate::helpers::get_builtin_registry;
 use indexmap::{IndexMap, IndexSet};
 use rustc_hash::FxHashMap;
@@ -7,10 +7,43 @@ use serde::{Deserialize, Serialize};
 use system_env::{SystemOS, SystemPackageManager};
 use warpgate::{HttpOptions, RegistryConfig};
 
+// `[settings.build.cache]`
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoBuildCacheConfig {
+    #[setting(env = "PROTO_BUILD_CACHE_LOCAL", parse_env = env::parse_bool)]
+    pub local: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[setting(env = "PROTO_BUILD_CACHE_REMOTE")]
+    pub remote: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[setting(env = "PROTO_BUILD_CACHE_S3_ENDPOINT")]
+    pub s3_endpoint: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[setting(env = "PROTO_BUILD_CACHE_S3_REGION")]
+    pub s3_region: Option<String>,
+
+    #[setting(env = "PROTO_BUILD_CACHE_S3_UNLISTABLE", parse_env = env::parse_bool)]
+    pub s3_unlistable: bool,
+
+    #[serde(skip_serializing)]
+    #[setting(env = "PROTO_BUILD_CACHE_SIGNING_KEY")]
+    pub signing_key: Option<String>,
+
+    #[setting(env = "PROTO_BUILD_CACHE_UPLOAD", parse_env = env::parse_bool)]
+    pub upload: bool,
+}
+
 // `[settings.build]`
 #[derive(Clone, Config, Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ProtoBuildConfig {
+    #[setting(nested)]
+    pub cache: ProtoBuildCacheConfig,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[setting(env = "PROTO_BUILD_EXCLUDE_PACKAGES", parse_env = env::split_comma)]
     pub exclude_packages: Vec<String>,
@@ -22,6 +55,9 @@ pub struct ProtoBuildConfig {
     )]
     pub install_system_packages: bool,
 
+    #[setting(env = "PROTO_BUILD_SANDBOX")]
+    pub sandbox: BuildSandbox,
+
     #[serde(skip_serializing_if = "FxHashMap::is_empty")]
     pub system_package_manager: FxHashMap<SystemOS, Option<SystemPackageManager>>,
 
@@ -44,6 +80,17 @@ pub struct ProtoOfflineConfig {
     pub timeout: u64,
 }
 
+// `[settings.upgrade]`
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoUpgradeConfig {
+    #[setting(env = "PROTO_UPGRADE_CHANNEL")]
+    pub channel: UpgradeChannel,
+
+    #[setting(default = 3, env = "PROTO_UPGRADE_KEEP_PREVIOUS")]
+    pub keep_previous: usize,
+}
+
 #[derive(Clone, Config, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum BuiltinPlugins {
@@ -92,6 +139,9 @@ pub struct ProtoSettingsConfig {
     #[setting(env = "PROTO_CACHE_DURATION")]
     pub cache_duration: Option<u64>,
 
+    #[setting(env = "PROTO_DEDUPE")]
+    pub dedupe: DedupeMode,
+
     #[setting(env = "PROTO_DETECT_STRATEGY")]
     pub detect_strategy: DetectStrategy,
 
@@ -100,6 +150,10 @@ pub struct ProtoSettingsConfig {
     #[serde(alias = "unstable-lockfile")]
     pub lockfile: bool,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[setting(env = "PROTO_MINIMUM_RELEASE_AGE")]
+    pub minimum_release_age: Option<u32>,
+
     #[setting(nested)]
     pub offline: ProtoOfflineConfig,
 
@@ -107,6 +161,9 @@ pub struct ProtoSettingsConfig {
     #[setting(env = "PROTO_PIN_LATEST")]
     pub pin_latest: Option<PinLocation>,
 
+    #[setting(env = "PROTO_READ_ONLY_TOOLS", parse_env = env::parse_bool)]
+    pub read_only_tools: bool,
+
     #[serde(
         alias = "unstable-registries",
         skip_serializing_if = "IndexSet::is_empty"
@@ -117,6 +174,13 @@ pub struct ProtoSettingsConfig {
     #[setting(default = true, env = "PROTO_TELEMETRY", parse_env = env::parse_bool)]
     pub telemetry: bool,
 
+    #[setting(nested)]
+    pub upgrade: ProtoUpgradeConfig,
+
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    #[setting(merge = merge_iter)]
+    pub url_mirrors: IndexMap<RegexSetting, Vec<String>>,
+
     #[serde(skip_serializing_if = "IndexMap::is_empty")]
     #[setting(merge = merge_iter)]
     pub url_rewrites: IndexMap<RegexSetting, String>,
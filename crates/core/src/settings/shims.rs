@@ -0,0 +1,47 @@
+use super::merge_iter;
+use crate::id::Id;
+use crate::tool_context::ToolContext;
+use rustc_hash::FxHashMap;
+use schematic::{Config, ValidateError, ValidateResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Names are used as file names in the shims directory,
+// so they must be a single path component
+pub(crate) fn validate_shim_names<T>(
+    value: &BTreeMap<String, PartialProtoShimConfig>,
+    _partial: &T,
+    _context: &(),
+    _finalize: bool,
+) -> ValidateResult {
+    for name in value.keys() {
+        if Id::new(name).is_err() || name.contains('/') || name == "." || name == ".." {
+            return Err(ValidateError::new(format!(
+                "invalid shim name `{name}`, expected a file name without path separators"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// `[shims.name]`
+#[derive(Clone, Config, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoShimConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after_args: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_exe: Option<bool>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before_args: Vec<String>,
+
+    #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+    #[setting(merge = merge_iter)]
+    pub env: FxHashMap<String, String>,
+
+    #[setting(required)]
+    pub tool: Option<ToolContext>,
+}
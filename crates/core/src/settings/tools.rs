@@ -1,13 +1,63 @@
-use super::{EnvFile, EnvVar, merge_iter};
+use super::{EnvFile, EnvVar, merge_iter, merge_partials_iter};
 use crate::tool_spec::ToolSpec;
 use indexmap::IndexMap;
 use rustc_hash::FxHashMap;
-use schematic::{Config, merge};
+use schematic::{Config, ValidateError, ValidateResult, merge};
 use serde::Serialize;
 use starbase_utils::json::JsonValue;
 use std::collections::BTreeMap;
+use system_env::{SystemArch, SystemOS};
+use version_spec::{MatchesVersion, UnresolvedVersionSpec, VersionSpec};
 use warpgate::PluginLocator;
 
+fn validate_platform_keys<T>(
+    value: &BTreeMap<String, PartialProtoToolPlatformConfig>,
+    _partial: &T,
+    _context: &(),
+    _finalize: bool,
+) -> ValidateResult {
+    for key in value.keys() {
+        let (os, arch) = match key.split_once('-') {
+            Some((os, arch)) => (os, Some(arch)),
+            None => (key.as_str(), None),
+        };
+
+        let os = serde_json::from_value::<SystemOS>(JsonValue::String(os.into()));
+        let arch = arch
+            .map(|arch| serde_json::from_value::<SystemArch>(JsonValue::String(arch.into())))
+            .transpose();
+
+        let (Ok(os), Ok(arch)) = (os, arch) else {
+            return Err(ValidateError::new(format!(
+                "invalid platform `{key}`, expected an operating system with an optional architecture, for example `linux` or `linux-arm64`"
+            )));
+        };
+
+        // Platforms are looked up by their display names, so aliases
+        // (like `mac` or `x86_64`) would silently never match
+        let name = match arch {
+            Some(arch) => format!("{os}-{arch}"),
+            None => os.to_string(),
+        };
+
+        if *key != name {
+            return Err(ValidateError::new(format!(
+                "invalid platform `{key}`, use `{name}` instead"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// `[tools.id.platform.os-arch]`
+#[derive(Clone, Config, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProtoToolPlatformConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<UnresolvedVersionSpec>,
+}
+
 // `[tools.id]`
 // `[tools."backend:tool"]`
 #[derive(Clone, Config, Debug, Serialize)]
@@ -18,10 +68,24 @@ pub struct ProtoToolConfig {
     #[setting(merge = merge_iter)]
     pub aliases: BTreeMap<String, ToolSpec>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow: Option<UnresolvedVersionSpec>,
+
     #[serde(skip_serializing_if = "IndexMap::is_empty")]
     #[setting(nested, merge = merge_iter)]
     pub env: IndexMap<String, EnvVar>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[setting(merge = merge::append_vec)]
+    pub exclude: Vec<UnresolvedVersionSpec>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_release_age: Option<u32>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[setting(nested, merge = merge_partials_iter, validate = validate_platform_keys)]
+    pub platform: BTreeMap<String, ProtoToolPlatformConfig>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plugin: Option<PluginLocator>,
 
@@ -30,7 +94,68 @@ pub struct ProtoToolConfig {
     #[setting(merge = merge_iter)]
     pub config: FxHashMap<String, JsonValue>,
 
+    #[serde(skip)]
+    #[setting(exclude, merge = merge::append_vec)]
+    pub(crate) _allow_layers: Vec<UnresolvedVersionSpec>,
+
     #[serde(skip)]
     #[setting(exclude, merge = merge::append_vec)]
     pub(crate) _env_files: Vec<EnvFile>,
 }
+
+impl ProtoToolConfig {
+    /// Return the platform specific version for the provided operating
+    /// system and architecture, with `os-arch` taking precedence over `os`.
+    pub fn get_platform_version(
+        &self,
+        os: SystemOS,
+        arch: SystemArch,
+    ) -> Option<&UnresolvedVersionSpec> {
+        get_platform_config(&self.platform, os, arch)
+            .and_then(|(_, config)| config.version.as_ref())
+    }
+
+    /// Return the `allow` requirements from every configuration layer. Unlike
+    /// other settings, a layer cannot override (and loosen) the `allow` of
+    /// another layer, so all of them must be satisfied.
+    pub fn get_allow_requirements(&self) -> Vec<&UnresolvedVersionSpec> {
+        if self._allow_layers.is_empty() {
+            self.allow.iter().collect()
+        } else {
+            self._allow_layers.iter().collect()
+        }
+    }
+
+    /// Return true if the version satisfies the `allow` requirements or ranges
+    /// of all configuration layers. Aliases and canary are always allowed.
+    pub fn is_version_allowed(&self, version: &VersionSpec) -> bool {
+        version.as_version().is_none()
+            || self
+                .get_allow_requirements()
+                .into_iter()
+                .all(|allow| matches_spec(allow, version))
+    }
+
+    /// Return true if the version matches an entry in the `exclude` list.
+    pub fn is_version_excluded(&self, version: &VersionSpec) -> bool {
+        self.exclude.iter().any(|spec| matches_spec(spec, version))
+    }
+}
+
+fn matches_spec(spec: &UnresolvedVersionSpec, version: &VersionSpec) -> bool {
+    match (spec, version.as_version()) {
+        (UnresolvedVersionSpec::Range(range), Some(inner)) => range.matches(inner),
+        (UnresolvedVersionSpec::Requirement(req), Some(inner)) => req.matches(inner),
+        _ => spec == version,
+    }
+}
+
+pub(crate) fn get_platform_config<T>(
+    platform: &BTreeMap<String, T>,
+    os: SystemOS,
+    arch: SystemArch,
+) -> Option<(&String, &T)> {
+    platform
+        .get_key_value(&format!("{os}-{arch}"))
+        .or_else(|| platform.get_key_value(&os.to_string()))
+}
@@ -109,6 +109,14 @@ pub fn load_config<T: AsRef<Path>>(dir: T) -> ProtoConfig {
     config.to_owned()
 }
 
+/// Return the locator of the mocked test plugin, which can also be
+/// configured as a policy plugin.
+pub fn get_mocked_plugin_locator() -> String {
+    warpgate::find_debug_locator("proto_mocked_tool")
+        .expect("Test plugins not available. Run `just build-wasm` to build them!")
+        .to_string()
+}
+
 pub fn create_shim_command<T: AsRef<Path>>(path: T, name: &str) -> assert_cmd::Command {
     let mut cmd = assert_cmd::Command::from_std(create_shim_command_std(path, name));
     cmd.timeout(std::time::Duration::from_secs(240));
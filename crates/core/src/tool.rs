@@ -211,6 +211,22 @@ impl Tool {
         }
     }
 
+    /// Return an absolute path to the tool's install directory within the system
+    /// store, if the currently resolved version has been installed there.
+    pub fn get_system_product_dir(&self, spec: &ToolSpec) -> Option<PathBuf> {
+        spec.version
+            .as_ref()
+            .and_then(|version| self.inventory.get_system_product_dir(version))
+    }
+
+    /// Return an absolute path to the tool's install directory for the currently resolved
+    /// version, preferring the system store over the user store. Use [`Tool::get_product_dir`]
+    /// when writing to the directory, as the system store is read-only.
+    pub fn locate_product_dir(&self, spec: &ToolSpec) -> PathBuf {
+        self.get_system_product_dir(spec)
+            .unwrap_or_else(|| self.get_product_dir(spec))
+    }
+
     /// Return true if this tool instance is a backend plugin.
     pub fn is_backend_plugin(&self) -> bool {
         self.ty == PluginType::Backend
@@ -219,6 +235,16 @@ impl Tool {
     /// Return true if the tool has been installed. This *requires* the spec to
     /// have been resolved before hand.
     pub fn is_installed(&self, spec: &ToolSpec) -> bool {
+        if let Some(dir) = self.get_system_product_dir(spec) {
+            debug!(
+                tool = self.context.as_str(),
+                install_dir = ?dir,
+                "Tool has already been installed in the system store",
+            );
+
+            return true;
+        }
+
         let dir = self.get_product_dir(spec);
         let lock_dir = self.get_version_temp_dir(spec);
 
@@ -260,12 +286,24 @@ impl Tool {
 // APIs
 
 impl Tool {
-    /// Return contextual information to pass to WASM plugin functions.
+    /// Return contextual information to pass to WASM plugin functions,
+    /// with the tool directory being the install target in the user store.
     pub fn create_plugin_context(&self, spec: &ToolSpec) -> PluginContext {
+        self.create_plugin_context_for_dir(spec, self.get_product_dir(spec))
+    }
+
+    /// Return contextual information to pass to WASM plugin functions when
+    /// locating or running the tool, with the tool directory being where the
+    /// tool is installed, which may be the read-only system store.
+    pub fn create_plugin_locate_context(&self, spec: &ToolSpec) -> PluginContext {
+        self.create_plugin_context_for_dir(spec, self.locate_product_dir(spec))
+    }
+
+    fn create_plugin_context_for_dir(&self, spec: &ToolSpec, tool_dir: PathBuf) -> PluginContext {
         PluginContext {
             proto_version: Some(get_proto_version().to_owned()),
             temp_dir: self.to_virtual_path(self.get_temp_dir()),
-            tool_dir: self.to_virtual_path(self.get_product_dir(spec)),
+            tool_dir: self.to_virtual_path(tool_dir),
             version: spec.get_resolved_version(),
             working_dir: self.to_virtual_path(&self.proto.working_dir),
         }
@@ -300,7 +338,7 @@ impl Tool {
     /// Register the tool by loading initial metadata and persisting it.
     #[instrument(skip(self))]
     pub async fn register_tool(&mut self) -> Result<(), ProtoToolError> {
-        let metadata: RegisterToolOutput = self
+        let mut metadata: RegisterToolOutput = self
             .plugin
             .cache_func_with(
                 PluginFunction::RegisterTool,
@@ -368,6 +406,22 @@ impl Tool {
 
             inventory.dir_original = Some(inventory.dir);
             inventory.dir = override_dir_path.to_path_buf();
+
+            // Tools that manage their own directory can't be layered
+            inventory.system_dir = None;
+            inventory.system_manifest = None;
+        }
+
+        // Platform specific versions are resolved differently per machine,
+        // so their lock records must always be scoped to an os/arch
+        if metadata.lock_options.ignore_os_arch
+            && self
+                .proto
+                .load_config()?
+                .get_tool_config(&self.context)
+                .is_some_and(|config| !config.platform.is_empty())
+        {
+            metadata.lock_options.ignore_os_arch = false;
         }
 
         self.inventory = inventory;
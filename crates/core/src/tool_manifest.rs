@@ -20,9 +20,16 @@ pub struct ToolManifestVersion {
 
     pub installed_at: u128,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lock: Option<LockRecord>,
 
+    // Sizes and modified times of the installed files, see `stamp_dir`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stamp: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suffix: Option<String>,
 }
@@ -32,7 +39,9 @@ impl Default for ToolManifestVersion {
         Self {
             no_clean: envx::bool_var("PROTO_NO_CLEAN"),
             installed_at: now(),
+            fingerprint: None,
             lock: None,
+            stamp: None,
             suffix: None,
         }
     }
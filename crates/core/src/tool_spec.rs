@@ -18,6 +18,9 @@ pub struct ToolSpec {
     /// Resolved version metadata from a lockfile.
     pub version_locked: Option<LockRecord>,
 
+    /// Newer version that was held back by the minimum release age.
+    pub version_held_back: Option<VersionSpec>,
+
     /// Resolve a version from the lockfile?
     pub resolve_from_lockfile: bool,
 
@@ -81,6 +84,7 @@ impl Default for ToolSpec {
             req: UnresolvedVersionSpec::default(),
             version: None,
             version_locked: None,
+            version_held_back: None,
             resolve_from_lockfile: true,
             resolve_from_manifest: true,
             update_lockfile: true,
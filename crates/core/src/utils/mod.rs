@@ -2,3 +2,4 @@ pub(crate) mod archive;
 pub(crate) mod git;
 pub mod log;
 pub(crate) mod process;
+pub(crate) mod sandbox;
@@ -0,0 +1,251 @@
+use crate::config::BuildSandbox;
+use rustc_hash::FxHashMap;
+use starbase_utils::fs::{self, FsError};
+use std::env;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+// Environment variables that are inherited from the host
+// when running commands in a sandbox, everything else is removed
+const INHERITED_ENV_VARS: &[&str] = &[
+    "PATH",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TERM",
+    "TZ",
+    "LANG",
+    "LANGUAGE",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+    "PROTO_ENV",
+    "PROTO_HOME",
+    "PROTO_LOG",
+    "PROTO_OFFLINE",
+    "PROTO_SYSTEM_HOME",
+];
+
+const INHERITED_ENV_PREFIXES: &[&str] = &["LC_"];
+
+// Pinned tool versions (`PROTO_<TOOL>_VERSION`), so that shims
+// executed by the build resolve the same versions as the host
+fn is_inherited_version_var(key: &str) -> bool {
+    key.starts_with("PROTO_") && key.ends_with("_VERSION")
+}
+
+/// Isolates commands that are executed while building from source.
+/// The sandbox only allows writing to the provided writable directories,
+/// and uses a private home and temporary directory.
+pub struct BuildSandboxRunner {
+    pub mode: BuildSandbox,
+    pub home_dir: PathBuf,
+    pub tmp_dir: PathBuf,
+    pub writable_dirs: Vec<PathBuf>,
+
+    env: FxHashMap<String, String>,
+}
+
+impl BuildSandboxRunner {
+    pub fn new(mode: BuildSandbox, sandbox_dir: &Path, writable_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            mode,
+            home_dir: sandbox_dir.join("home"),
+            tmp_dir: sandbox_dir.join("tmp"),
+            writable_dirs,
+            env: FxHashMap::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.mode, BuildSandbox::Off)
+    }
+
+    /// Create the private home and temporary directories.
+    pub fn prepare(&self) -> Result<(), FsError> {
+        if self.is_enabled() {
+            fs::create_dir_all(&self.home_dir)?;
+            fs::create_dir_all(&self.tmp_dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Persist an environment variable across all subsequent commands,
+    /// without modifying the environment of the host process.
+    pub fn set_env_var(&mut self, key: impl AsRef<str>, value: impl AsRef<str>) {
+        self.env
+            .insert(key.as_ref().to_owned(), value.as_ref().to_owned());
+    }
+
+    /// Create a command for the provided executable, that runs within the sandbox.
+    pub fn create_command(
+        &self,
+        exe: &Path,
+        args: &[String],
+        env: &FxHashMap<String, String>,
+        cwd: &Path,
+    ) -> Command {
+        let mut command = match self.mode {
+            BuildSandbox::Bubblewrap => {
+                let mut command = Command::new("bwrap");
+                command.args(["--die-with-parent", "--unshare-all", "--share-net"]);
+                command.args(["--ro-bind", "/", "/"]);
+                command.args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+
+                for dir in [&self.home_dir, &self.tmp_dir]
+                    .into_iter()
+                    .chain(&self.writable_dirs)
+                {
+                    command.arg("--bind").arg(dir).arg(dir);
+                }
+
+                command.arg("--chdir").arg(cwd);
+                command.arg("--").arg(exe).args(args);
+                command
+            }
+            _ => {
+                let mut command = Command::new(exe);
+                command.args(args);
+                command
+            }
+        };
+
+        if self.is_enabled() {
+            command.env_clear();
+
+            for (key, value) in env::vars() {
+                if INHERITED_ENV_VARS.contains(&key.as_str())
+                    || INHERITED_ENV_PREFIXES
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+                    || is_inherited_version_var(&key)
+                {
+                    command.env(key, value);
+                }
+            }
+
+            command.env("HOME", &self.home_dir);
+
+            for key in ["TMPDIR", "TMP", "TEMP"] {
+                command.env(key, &self.tmp_dir);
+            }
+        }
+
+        command.envs(&self.env);
+        command.envs(env);
+        command.current_dir(cwd);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn create_runner(mode: BuildSandbox) -> BuildSandboxRunner {
+        BuildSandboxRunner::new(
+            mode,
+            Path::new("/temp/sandbox"),
+            vec![PathBuf::from("/temp"), PathBuf::from("/install")],
+        )
+    }
+
+    fn get_env<'a>(command: &'a Command, key: &str) -> Option<&'a OsStr> {
+        command
+            .as_std()
+            .get_envs()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v)
+    }
+
+    #[test]
+    fn runs_on_host_when_off() {
+        let runner = create_runner(BuildSandbox::Off);
+        let command = runner.create_command(
+            Path::new("make"),
+            &["install".into()],
+            &FxHashMap::from_iter([("KEY".into(), "value".into())]),
+            Path::new("/install"),
+        );
+        let inner = command.as_std();
+
+        assert_eq!(inner.get_program(), "make");
+        assert_eq!(inner.get_args().collect::<Vec<_>>(), ["install"]);
+        assert_eq!(get_env(&command, "KEY").unwrap(), "value");
+        assert!(get_env(&command, "HOME").is_none());
+    }
+
+    #[test]
+    fn persists_env_vars_when_off() {
+        let mut runner = create_runner(BuildSandbox::Off);
+        runner.set_env_var("PERSISTED", "true");
+
+        let command = runner.create_command(
+            Path::new("make"),
+            &[],
+            &FxHashMap::default(),
+            Path::new("/install"),
+        );
+
+        assert_eq!(get_env(&command, "PERSISTED").unwrap(), "true");
+        assert!(env::var("PERSISTED").is_err());
+    }
+
+    #[test]
+    fn scrubs_env_and_uses_private_dirs() {
+        let mut runner = create_runner(BuildSandbox::Env);
+        runner.set_env_var("PERSISTED", "true");
+
+        let command = runner.create_command(
+            Path::new("make"),
+            &[],
+            &FxHashMap::from_iter([("KEY".into(), "value".into())]),
+            Path::new("/install"),
+        );
+
+        assert_eq!(command.as_std().get_program(), "make");
+        assert_eq!(get_env(&command, "HOME").unwrap(), "/temp/sandbox/home");
+        assert_eq!(get_env(&command, "TMPDIR").unwrap(), "/temp/sandbox/tmp");
+        assert_eq!(get_env(&command, "PERSISTED").unwrap(), "true");
+        assert_eq!(get_env(&command, "KEY").unwrap(), "value");
+    }
+
+    #[test]
+    fn only_inherits_allowed_proto_vars() {
+        assert!(is_inherited_version_var("PROTO_NODE_VERSION"));
+        assert!(!is_inherited_version_var("PROTO_GITHUB_TOKEN"));
+        assert!(!is_inherited_version_var("NODE_VERSION"));
+        assert!(INHERITED_ENV_VARS.contains(&"PROTO_HOME"));
+        assert!(!INHERITED_ENV_VARS.contains(&"PROTO_GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn wraps_with_bubblewrap() {
+        let runner = create_runner(BuildSandbox::Bubblewrap);
+        let command = runner.create_command(
+            Path::new("make"),
+            &["install".into()],
+            &FxHashMap::default(),
+            Path::new("/install"),
+        );
+        let inner = command.as_std();
+        let args = inner
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        assert_eq!(inner.get_program(), "bwrap");
+        assert!(args.contains("--ro-bind / /"));
+        assert!(args.contains("--bind /temp /temp"));
+        assert!(args.contains("--bind /install /install"));
+        assert!(args.contains("--bind /temp/sandbox/home /temp/sandbox/home"));
+        assert!(args.ends_with("--chdir /install -- make install"));
+        assert_eq!(get_env(&command, "HOME").unwrap(), "/temp/sandbox/home");
+    }
+}
@@ -1,14 +1,83 @@
+use crate::alias_expression::AliasExpression;
 use crate::config::ProtoToolConfig;
+use crate::flow::resolve::ProtoResolveError;
+use crate::helpers::format_iso_date;
+use crate::id::Id;
 use crate::tool_manifest::ToolManifest;
-use proto_pdk_api::LoadVersionsOutput;
-use std::collections::BTreeMap;
+use proto_pdk_api::{LoadVersionsOutput, VersionMetadata};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, SystemTime};
 use tracing::trace;
 use version_spec::*;
 
+/// Criteria for skipping versions when resolving requirements, ranges, and aliases.
+/// Explicitly pinned versions are not skipped.
+#[derive(Clone, Copy, Default)]
+pub struct VersionFilter<'a> {
+    /// Release metadata, for skipping yanked versions.
+    pub metadata: Option<&'a BTreeMap<VersionSpec, VersionMetadata>>,
+
+    /// Versions resolved for other tools, keyed by tool ID,
+    /// for resolving `pin/<tool>` alias expressions. Pinned versions
+    /// that are also `pin/<tool>` expressions are not resolved.
+    pub pins: Option<&'a BTreeMap<String, UnresolvedVersionSpec>>,
+
+    /// Versions released after this date (`YYYY-MM-DD`) are held back.
+    pub release_cutoff: Option<&'a str>,
+
+    /// Tool configuration, for skipping versions with `allow` and `exclude`.
+    pub policy: Option<&'a ProtoToolConfig>,
+}
+
+impl VersionFilter<'_> {
+    /// Return true if the version was released after the cutoff date.
+    pub fn is_too_new(&self, version: &VersionSpec) -> bool {
+        self.release_cutoff.is_some_and(|cutoff| {
+            self.metadata
+                .and_then(|metadata| metadata.get(version))
+                .and_then(|meta| meta.release_date.as_ref())
+                .is_some_and(|date| date.get(0..10).unwrap_or(date) > cutoff)
+        })
+    }
+
+    /// Return true if the version is not allowed by the tool's policy.
+    pub fn is_disallowed(&self, version: &VersionSpec) -> bool {
+        self.policy.is_some_and(|policy| {
+            policy.is_version_excluded(version) || !policy.is_version_allowed(version)
+        })
+    }
+
+    /// Return true if the version is a long-term support release.
+    pub fn is_lts(&self, version: &VersionSpec) -> bool {
+        self.metadata
+            .and_then(|metadata| metadata.get(version))
+            .is_some_and(|meta| meta.lts.is_some())
+    }
+
+    /// Return true if the version is yanked.
+    pub fn is_yanked(&self, version: &VersionSpec) -> bool {
+        self.metadata
+            .and_then(|metadata| metadata.get(version))
+            .is_some_and(|meta| meta.yanked)
+    }
+
+    /// Return true if the version should be skipped entirely.
+    pub fn is_skipped(&self, version: &VersionSpec) -> bool {
+        self.is_yanked(version) || self.is_too_new(version) || self.is_disallowed(version)
+    }
+}
+
 #[derive(Default)]
 pub struct VersionResolver<'tool> {
     pub aliases: BTreeMap<String, UnresolvedVersionSpec>,
     pub versions: Vec<VersionSpec>,
+    pub metadata: BTreeMap<VersionSpec, VersionMetadata>,
+
+    /// Versions resolved for other tools, keyed by tool ID.
+    pub pins: BTreeMap<String, UnresolvedVersionSpec>,
+
+    /// Versions released after this date (`YYYY-MM-DD`) are held back.
+    pub release_cutoff: Option<String>,
 
     manifest: Option<&'tool ToolManifest>,
     config: Option<&'tool ProtoToolConfig>,
@@ -23,6 +92,7 @@ impl<'tool> VersionResolver<'tool> {
         }
 
         resolver.versions.extend(output.versions);
+        resolver.metadata.extend(output.metadata);
 
         for (alias, spec) in output.aliases {
             resolver.aliases.insert(alias, spec);
@@ -35,10 +105,22 @@ impl<'tool> VersionResolver<'tool> {
         // Sort from newest to oldest
         resolver.versions.sort_by(|a, d| d.cmp(a));
 
-        if !resolver.aliases.contains_key("latest") && !resolver.versions.is_empty() {
+        // Prefer the newest version that has not been yanked
+        if !resolver.aliases.contains_key("latest")
+            && let Some(latest) = resolver
+                .versions
+                .iter()
+                .find(|version| {
+                    !resolver
+                        .metadata
+                        .get(version)
+                        .is_some_and(|meta| meta.yanked)
+                })
+                .or(resolver.versions.first())
+        {
             resolver
                 .aliases
-                .insert("latest".into(), resolver.versions[0].to_unresolved_spec());
+                .insert("latest".into(), latest.to_unresolved_spec());
         }
 
         resolver
@@ -52,11 +134,105 @@ impl<'tool> VersionResolver<'tool> {
         self.config = Some(config);
     }
 
-    pub fn resolve(&self, candidate: &UnresolvedVersionSpec) -> Option<VersionSpec> {
-        resolve_version(
+    pub fn with_pins(&mut self, pins: impl IntoIterator<Item = (String, UnresolvedVersionSpec)>) {
+        self.pins.extend(pins);
+    }
+
+    /// Hold back versions that were released less than the provided
+    /// number of days ago. A value of 0 disables the cooldown.
+    pub fn with_minimum_release_age(&mut self, days: u32) {
+        self.release_cutoff = if days == 0 {
+            None
+        } else {
+            // Clamp to the epoch, as a large day count may underflow
+            let age = Duration::from_secs(u64::from(days) * 86400);
+
+            Some(format_iso_date(
+                SystemTime::now()
+                    .checked_sub(age)
+                    .unwrap_or(SystemTime::UNIX_EPOCH)
+                    .max(SystemTime::UNIX_EPOCH),
+            ))
+        };
+    }
+
+    pub fn get_metadata(&self, version: &VersionSpec) -> Option<&VersionMetadata> {
+        self.metadata.get(version)
+    }
+
+    /// Return the tools referenced by `pin/<tool>` expressions
+    /// that the candidate resolves through, following aliases.
+    pub fn get_pinned_tools(&self, candidate: &UnresolvedVersionSpec) -> Vec<Id> {
+        let mut tools = vec![];
+        let mut seen = BTreeSet::default();
+        let mut queue = vec![candidate.clone()];
+
+        while let Some(spec) = queue.pop() {
+            let alias = match AliasExpression::from_spec(&spec) {
+                Some(AliasExpression::Pin { tool, .. }) => {
+                    tools.push(tool);
+                    continue;
+                }
+                Some(AliasExpression::Previous { alias, .. }) => alias,
+                None => match spec {
+                    UnresolvedVersionSpec::Alias(alias) => alias.to_string(),
+                    _ => continue,
+                },
+            };
+
+            if !seen.insert(alias.clone()) {
+                continue;
+            }
+
+            if let Some(value) = self
+                .config
+                .and_then(|config| config.aliases.get(&alias))
+                .map(|spec| &spec.req)
+                .or_else(|| self.aliases.get(&alias))
+            {
+                queue.push(value.clone());
+            }
+        }
+
+        tools
+    }
+
+    pub fn get_filter(&self) -> VersionFilter<'_> {
+        VersionFilter {
+            metadata: Some(&self.metadata),
+            pins: Some(&self.pins),
+            release_cutoff: self.release_cutoff.as_deref(),
+            policy: self.config,
+        }
+    }
+
+    /// Resolve the candidate, and also return the newest version that matched it,
+    /// but was held back by the minimum release age, if it's newer than the
+    /// resolved version.
+    pub fn resolve_with_held_back(
+        &self,
+        candidate: &UnresolvedVersionSpec,
+        with_manifest: bool,
+    ) -> Result<(Option<VersionSpec>, Option<VersionSpec>), ProtoResolveError> {
+        resolve_version_with_held_back(
+            candidate,
+            &self.versions,
+            &self.aliases,
+            self.get_filter(),
+            if with_manifest { self.manifest } else { None },
+            if with_manifest { self.config } else { None },
+        )
+    }
+
+    pub fn resolve(
+        &self,
+        candidate: &UnresolvedVersionSpec,
+    ) -> Result<Option<VersionSpec>, ProtoResolveError> {
+        resolve_version_with_filter(
             candidate,
             &self.versions,
             &self.aliases,
+            self.get_filter(),
             self.manifest,
             self.config,
         )
@@ -65,8 +241,15 @@ impl<'tool> VersionResolver<'tool> {
     pub fn resolve_without_manifest(
         &self,
         candidate: &UnresolvedVersionSpec,
-    ) -> Option<VersionSpec> {
-        resolve_version(candidate, &self.versions, &self.aliases, None, None)
+    ) -> Result<Option<VersionSpec>, ProtoResolveError> {
+        resolve_version_with_filter(
+            candidate,
+            &self.versions,
+            &self.aliases,
+            self.get_filter(),
+            None,
+            None,
+        )
     }
 }
 
@@ -94,10 +277,206 @@ pub fn resolve_version(
     aliases: &BTreeMap<String, UnresolvedVersionSpec>,
     manifest: Option<&ToolManifest>,
     config: Option<&ProtoToolConfig>,
+) -> Result<Option<VersionSpec>, ProtoResolveError> {
+    resolve_version_with_filter(
+        candidate,
+        versions,
+        aliases,
+        VersionFilter::default(),
+        manifest,
+        config,
+    )
+}
+
+/// Return the highest version matching the requirement, that was
+/// only skipped because it was released after the cutoff date.
+fn find_held_back_version<T: MatchesVersion>(
+    req: &T,
+    versions: &[VersionSpec],
+    filter: VersionFilter,
 ) -> Option<VersionSpec> {
-    let remote_versions = versions.iter().collect::<Vec<_>>();
+    filter.release_cutoff?;
+
+    match_highest_version(
+        req,
+        &versions
+            .iter()
+            .filter(|spec| {
+                filter.is_too_new(spec) && !filter.is_yanked(spec) && !filter.is_disallowed(spec)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn resolve_alias_expression(
+    expression: &AliasExpression,
+    versions: &[VersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    filter: VersionFilter,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+    held_back: &mut Option<VersionSpec>,
+) -> Result<Option<VersionSpec>, ProtoResolveError> {
+    match expression {
+        AliasExpression::Previous { alias, offset } => {
+            let Some(base) = resolve_version_candidate(
+                &UnresolvedVersionSpec::Alias(alias.into()),
+                versions,
+                aliases,
+                filter,
+                manifest,
+                config,
+                held_back,
+            )?
+            else {
+                return Ok(None);
+            };
+
+            // Versions held back for the base alias are in a later release line
+            *held_back = None;
+            let Some(base_major) = base.as_version().map(|version| version.major) else {
+                return Ok(None);
+            };
+            let lts_only = filter.is_lts(&base);
+
+            // Without metadata, LTS lines can't be told apart from other major lines
+            if alias == "lts" && !lts_only {
+                return Err(ProtoResolveError::MissingLtsMetadata {
+                    expression: expression.to_string(),
+                });
+            }
+
+            // Find the highest stable version of each earlier release line
+            let mut lines = BTreeMap::<u32, &VersionSpec>::default();
+
+            for spec in versions {
+                let Some(version) = spec.as_version() else {
+                    continue;
+                };
+
+                if version.major >= base_major
+                    || version.prerelease.is_some()
+                    || filter.is_skipped(spec)
+                    || lts_only && !filter.is_lts(spec)
+                {
+                    continue;
+                }
+
+                let highest = lines.entry(version.major).or_insert(spec);
+
+                if spec > *highest {
+                    *highest = spec;
+                }
+            }
+
+            let resolved = lines.into_values().rev().nth(offset - 1).cloned();
+
+            trace!(
+                alias = alias.as_str(),
+                offset,
+                lts = lts_only,
+                resolved = ?resolved.as_ref().map(|spec| spec.to_string()),
+                "Resolved a previous release line",
+            );
+
+            Ok(resolved)
+        }
+        AliasExpression::Pin { tool, part } => {
+            let Some(pinned) = filter.pins.and_then(|pins| pins.get(tool.as_str())) else {
+                return Ok(None);
+            };
+            let Some(candidate) = AliasExpression::apply_pin(*part, pinned) else {
+                return Ok(None);
+            };
+
+            trace!(
+                tool = tool.as_str(),
+                pinned = pinned.to_string(),
+                candidate = candidate.to_string(),
+                "Resolving from the version pinned for another tool",
+            );
+
+            // Only follow pins one level deep, as pinned versions
+            // that are also pin expressions may reference each other
+            resolve_version_candidate(
+                &candidate,
+                versions,
+                aliases,
+                VersionFilter {
+                    pins: None,
+                    ..filter
+                },
+                manifest,
+                config,
+                held_back,
+            )
+        }
+    }
+}
+
+/// Resolve a version like [`resolve_version`], but skip versions that match the
+/// filter when matching against requirements, ranges, and aliases. These versions
+/// can still be resolved when pinned exactly.
+pub fn resolve_version_with_filter(
+    candidate: &UnresolvedVersionSpec,
+    versions: &[VersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    filter: VersionFilter,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+) -> Result<Option<VersionSpec>, ProtoResolveError> {
+    resolve_version_candidate(
+        candidate, versions, aliases, filter, manifest, config, &mut None,
+    )
+}
+
+/// Resolve a version like [`resolve_version_with_filter`], and also return the
+/// newest version matching the candidate that was held back by the release
+/// cutoff date, if it's newer than the resolved version.
+pub fn resolve_version_with_held_back(
+    candidate: &UnresolvedVersionSpec,
+    versions: &[VersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    filter: VersionFilter,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+) -> Result<(Option<VersionSpec>, Option<VersionSpec>), ProtoResolveError> {
+    let mut held_back = None;
+    let resolved = resolve_version_candidate(
+        candidate,
+        versions,
+        aliases,
+        filter,
+        manifest,
+        config,
+        &mut held_back,
+    )?;
+
+    let held_back =
+        held_back.filter(|newest| resolved.as_ref().is_none_or(|resolved| newest > resolved));
+
+    Ok((resolved, held_back))
+}
+
+fn resolve_version_candidate(
+    candidate: &UnresolvedVersionSpec,
+    versions: &[VersionSpec],
+    aliases: &BTreeMap<String, UnresolvedVersionSpec>,
+    filter: VersionFilter,
+    manifest: Option<&ToolManifest>,
+    config: Option<&ProtoToolConfig>,
+    held_back: &mut Option<VersionSpec>,
+) -> Result<Option<VersionSpec>, ProtoResolveError> {
+    let remote_versions = versions
+        .iter()
+        .filter(|spec| !filter.is_skipped(spec))
+        .collect::<Vec<_>>();
     let installed_versions = if let Some(manifest) = manifest {
-        Vec::from_iter(&manifest.installed_versions)
+        manifest
+            .installed_versions
+            .iter()
+            .filter(|spec| !filter.is_skipped(spec))
+            .collect::<Vec<_>>()
     } else {
         vec![]
     };
@@ -118,7 +497,7 @@ pub fn resolve_version(
         UnresolvedVersionSpec::Canary => {
             trace!("Resolved to canary");
 
-            return Some(VersionSpec::Canary);
+            return Ok(Some(VersionSpec::Canary));
         }
         UnresolvedVersionSpec::Alias(alias) => {
             trace!(alias = alias.as_str(), "Found an alias, resolving further");
@@ -140,7 +519,69 @@ pub fn resolve_version(
                     "Alias exists with a potential candidate"
                 );
 
-                return resolve_version(value, versions, aliases, manifest, config);
+                let Some(resolved) = resolve_version_candidate(
+                    value, versions, aliases, filter, manifest, config, held_back,
+                )?
+                else {
+                    return Ok(None);
+                };
+
+                // Yanked versions are broken or insecure, so silently
+                // using another version in their place is not safe
+                if filter.is_yanked(&resolved) {
+                    return Err(ProtoResolveError::YankedAliasVersion {
+                        alias: alias.to_string(),
+                        version: resolved.to_string(),
+                    });
+                }
+
+                if !filter.is_skipped(&resolved) {
+                    return Ok(Some(resolved));
+                }
+
+                if filter.is_too_new(&resolved) {
+                    *held_back = Some(resolved.clone());
+                }
+
+                // The alias points to a version that was released too recently,
+                // or is not allowed, so fallback to the highest stable
+                // version that came before it, within the same release line
+                let lts_only = filter.is_lts(&resolved);
+                let fallback = remote_versions
+                    .iter()
+                    .filter(|spec| {
+                        **spec < &resolved
+                            && spec
+                                .as_version()
+                                .is_none_or(|version| version.prerelease.is_none())
+                            && (!lts_only || filter.is_lts(spec))
+                    })
+                    .max()
+                    .map(|spec| (*spec).to_owned());
+
+                trace!(
+                    alias = alias.as_str(),
+                    held_back = resolved.to_string(),
+                    fallback = ?fallback.as_ref().map(|spec| spec.to_string()),
+                    "Alias resolved to a version that was skipped, using a fallback"
+                );
+
+                return Ok(fallback);
+            } else if let Some(expression) = AliasExpression::parse(alias) {
+                trace!(
+                    alias = alias.as_str(),
+                    "Alias does not exist but is an expression, computing a version"
+                );
+
+                return resolve_alias_expression(
+                    &expression,
+                    versions,
+                    aliases,
+                    filter,
+                    manifest,
+                    config,
+                    held_back,
+                );
             } else {
                 trace!(
                     alias = alias.as_str(),
@@ -154,6 +595,8 @@ pub fn resolve_version(
                 "Found a requirement, resolving further"
             );
 
+            *held_back = find_held_back_version(req, versions, filter);
+
             // Check locally installed versions first
             if !installed_versions.is_empty()
                 && let Some(version) = match_highest_version(req, &installed_versions)
@@ -163,7 +606,7 @@ pub fn resolve_version(
                     "Resolved to locally installed version"
                 );
 
-                return Some(version);
+                return Ok(Some(version));
             }
 
             // Otherwise we'll need to download from remote
@@ -173,7 +616,22 @@ pub fn resolve_version(
                     "Resolved to remote available version"
                 );
 
-                return Some(version);
+                return Ok(Some(version));
+            }
+
+            // A scoped requirement, like `lts-1`, may be a relative alias
+            if let Some(expression) = AliasExpression::from_requirement(req)
+                && let Some(version) = resolve_alias_expression(
+                    &expression,
+                    versions,
+                    aliases,
+                    filter,
+                    manifest,
+                    config,
+                    held_back,
+                )?
+            {
+                return Ok(Some(version));
             }
 
             trace!(
@@ -187,6 +645,8 @@ pub fn resolve_version(
                 "Found a range, resolving further"
             );
 
+            *held_back = find_held_back_version(range, versions, filter);
+
             // Check locally installed versions first
             if !installed_versions.is_empty()
                 && let Some(version) = match_highest_version(range, &installed_versions)
@@ -196,7 +656,7 @@ pub fn resolve_version(
                     "Resolved to locally installed version"
                 );
 
-                return Some(version);
+                return Ok(Some(version));
             }
 
             // Otherwise we'll need to download from remote
@@ -206,7 +666,7 @@ pub fn resolve_version(
                     "Resolved to remote available version"
                 );
 
-                return Some(version);
+                return Ok(Some(version));
             }
 
             trace!(
@@ -226,13 +686,14 @@ pub fn resolve_version(
             );
 
             // Check locally installed versions first
-            if installed_versions.contains(&&resolved_spec) {
+            if manifest.is_some_and(|manifest| manifest.installed_versions.contains(&resolved_spec))
+            {
                 trace!(
                     version = &version_string,
                     "Resolved to locally installed version"
                 );
 
-                return Some(resolved_spec);
+                return Ok(Some(resolved_spec));
             }
 
             // Otherwise we'll need to download from remote
@@ -243,7 +704,7 @@ pub fn resolve_version(
                         "Resolved to remote available version"
                     );
 
-                    return Some(resolved_spec);
+                    return Ok(Some(resolved_spec));
                 }
             }
 
@@ -254,5 +715,5 @@ pub fn resolve_version(
         }
     }
 
-    None
+    Ok(None)
 }
@@ -1,7 +1,8 @@
 use indexmap::IndexMap;
 use proto_core::{
-    DetectStrategy, EnvVar, PartialEnvVar, PartialProtoSettingsConfig, PinLocation, ProtoConfig,
-    ProtoConfigEnvOptions, ProtoFileManager, ToolContext, ToolSpec,
+    BuildSandbox, DetectStrategy, EnvVar, PartialEnvVar, PartialProtoSettingsConfig, PinLocation,
+    ProtoConfig, ProtoConfigEnvOptions, ProtoFileManager, ProtoToolConfig, ToolContext, ToolSpec,
+    UpgradeChannel,
 };
 use rustc_hash::FxHashMap;
 use schematic::RegexSetting;
@@ -9,7 +10,8 @@ use starbase_sandbox::create_empty_sandbox;
 use starbase_utils::json::JsonValue;
 use std::collections::BTreeMap;
 use std::env;
-use version_spec::UnresolvedVersionSpec;
+use system_env::{SystemArch, SystemOS};
+use version_spec::{UnresolvedVersionSpec, VersionSpec};
 use warpgate::{FileLocator, GitHubLocator, HttpOptions, Id, PluginLocator, UrlLocator};
 
 mod config {
@@ -322,6 +324,74 @@ root-cert = "../cert.pem"
         );
     }
 
+    #[test]
+    fn parses_build_sandbox() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.build]
+sandbox = "bubblewrap"
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(
+            config.settings.unwrap().build.unwrap().sandbox.unwrap(),
+            BuildSandbox::Bubblewrap
+        );
+    }
+
+    #[test]
+    fn parses_minimum_release_age() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings]
+minimum-release-age = 7
+
+[tools.node]
+minimum-release-age = 0
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+
+        assert_eq!(config.settings.unwrap().minimum_release_age.unwrap(), 7);
+        assert_eq!(
+            config
+                .tools
+                .unwrap()
+                .get("node")
+                .unwrap()
+                .minimum_release_age
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn parses_upgrade_settings() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            ".prototools",
+            r#"
+[settings.upgrade]
+channel = "canary"
+keep-previous = 5
+"#,
+        );
+
+        let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        let settings = config.settings.unwrap();
+        let upgrade = settings.upgrade.unwrap();
+
+        assert_eq!(upgrade.channel.unwrap(), UpgradeChannel::Canary);
+        assert_eq!(upgrade.keep_previous.unwrap(), 5);
+    }
+
     #[test]
     fn parses_plugins_table() {
         let sandbox = create_empty_sandbox();
@@ -1177,6 +1247,95 @@ bar = false
         use super::*;
         use schematic::Config;
 
+        #[test]
+        fn can_set_allow_and_exclude() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node]
+allow = ">=18 <23"
+exclude = ["20.11.0", "^21"]
+"#,
+            );
+
+            let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+            let tool_config =
+                ProtoToolConfig::from_partial(config.tools.unwrap().remove("node").unwrap());
+
+            assert!(tool_config.is_version_allowed(&VersionSpec::parse("18.0.0").unwrap()));
+            assert!(tool_config.is_version_allowed(&VersionSpec::parse("22.1.0").unwrap()));
+            assert!(!tool_config.is_version_allowed(&VersionSpec::parse("16.0.0").unwrap()));
+            assert!(!tool_config.is_version_allowed(&VersionSpec::parse("23.0.0").unwrap()));
+            assert!(tool_config.is_version_allowed(&VersionSpec::Canary));
+
+            assert!(tool_config.is_version_excluded(&VersionSpec::parse("20.11.0").unwrap()));
+            assert!(tool_config.is_version_excluded(&VersionSpec::parse("21.5.0").unwrap()));
+            assert!(!tool_config.is_version_excluded(&VersionSpec::parse("20.11.1").unwrap()));
+        }
+
+        #[test]
+        fn can_set_platform_versions() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.terraform.platform.linux-arm64]
+version = "1.5.7"
+
+[tools.terraform.platform.macos]
+version = "~1.6"
+"#,
+            );
+
+            let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+            let tool_config =
+                ProtoToolConfig::from_partial(config.tools.unwrap().remove("terraform").unwrap());
+
+            assert_eq!(
+                tool_config.get_platform_version(SystemOS::Linux, SystemArch::Arm64),
+                Some(&UnresolvedVersionSpec::parse("1.5.7").unwrap())
+            );
+            assert_eq!(
+                tool_config.get_platform_version(SystemOS::MacOS, SystemArch::Arm64),
+                Some(&UnresolvedVersionSpec::parse("~1.6").unwrap())
+            );
+            assert_eq!(
+                tool_config.get_platform_version(SystemOS::Linux, SystemArch::X64),
+                None
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "invalid platform `linux-sparc`")]
+        fn errors_for_invalid_platform() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.terraform.platform.linux-sparc]
+version = "1.5.7"
+"#,
+            );
+
+            ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "invalid platform `mac-x86_64`, use `macos-x64` instead")]
+        fn errors_for_platform_alias() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.terraform.platform.mac-x86_64]
+version = "1.5.7"
+"#,
+            );
+
+            ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        }
+
         #[test]
         fn can_set_extra_settings() {
             let sandbox = create_empty_sandbox();
@@ -1530,6 +1689,143 @@ file = ".env.tool"
         }
     }
 
+    mod shim_config {
+        use super::*;
+
+        #[test]
+        fn can_set_shims() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims.pnpx]
+tool = "pnpm"
+before-args = ["dlx"]
+
+[shims.tsc]
+tool = "npm:typescript"
+alt-exe = true
+after-args = ["--pretty"]
+env = { TSC_NONPOLLING_WATCHER = "1" }
+"#,
+            );
+
+            let config = ProtoConfig::load_from(sandbox.path(), false).unwrap();
+            let shims = config.shims.unwrap();
+
+            let pnpx = shims.get("pnpx").unwrap();
+
+            assert_eq!(pnpx.tool, Some(ToolContext::parse("pnpm").unwrap()));
+            assert_eq!(pnpx.before_args, Some(vec!["dlx".to_owned()]));
+            assert_eq!(pnpx.alt_exe, None);
+
+            let tsc = shims.get("tsc").unwrap();
+
+            assert_eq!(
+                tsc.tool,
+                Some(ToolContext::parse("npm:typescript").unwrap())
+            );
+            assert_eq!(tsc.alt_exe, Some(true));
+            assert_eq!(tsc.after_args, Some(vec!["--pretty".to_owned()]));
+            assert_eq!(
+                tsc.env,
+                Some(FxHashMap::from_iter([(
+                    "TSC_NONPOLLING_WATCHER".to_owned(),
+                    "1".to_owned()
+                )]))
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "this setting is required")]
+        fn errors_if_tool_missing() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims.pnpx]
+before-args = ["dlx"]
+"#,
+            );
+
+            ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "invalid shim name `../pnpx`")]
+        fn errors_if_name_is_a_parent_path() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims."../pnpx"]
+tool = "pnpm"
+"#,
+            );
+
+            ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "invalid shim name `bin/pnpx`")]
+        fn errors_if_name_has_separators() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims."bin/pnpx"]
+tool = "pnpm"
+"#,
+            );
+
+            ProtoConfig::load_from(sandbox.path(), false).unwrap();
+        }
+
+        #[test]
+        fn merges_shims() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                "a/.prototools",
+                r#"
+[shims.pnpx]
+tool = "pnpm"
+before-args = ["dlx", "--silent"]
+env = { CHILD = "1" }
+"#,
+            );
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[shims.pnpx]
+tool = "pnpm"
+before-args = ["dlx"]
+env = { PARENT = "1" }
+
+[shims.tsc]
+tool = "npm:typescript"
+"#,
+            );
+
+            let config = ProtoFileManager::load(sandbox.path().join("a"), None, None)
+                .unwrap()
+                .get_merged_config()
+                .unwrap()
+                .to_owned();
+
+            let pnpx = config.shims.get("pnpx").unwrap();
+
+            assert_eq!(pnpx.before_args, vec!["dlx", "--silent"]);
+            assert_eq!(
+                pnpx.env,
+                FxHashMap::from_iter([
+                    ("CHILD".to_owned(), "1".to_owned()),
+                    ("PARENT".to_owned(), "1".to_owned()),
+                ])
+            );
+            assert!(config.shims.contains_key("tsc"));
+        }
+    }
+
     mod plugin_lookup {
         use super::*;
         use proto_core::PluginType;
@@ -1634,6 +1930,36 @@ vfox = "github://moonrepo/vfox"
                 None
             );
         }
+
+        #[test]
+        fn resolves_policy_plugins() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.company]
+plugin = "github://moonrepo/company-tool"
+
+[plugins.policies]
+company = "github://moonrepo/company-policy"
+"#,
+            );
+
+            let config =
+                ProtoConfig::from_partial(ProtoConfig::load_from(sandbox.path(), false).unwrap());
+
+            assert_eq!(
+                config.get_plugin(&ctx("company"), PluginType::Policy),
+                Some(&github_locator("moonrepo/company-policy"))
+            );
+
+            // Tool plugins are not visible to policy lookups, and vice versa
+            assert_eq!(
+                config.get_plugin(&ctx("company"), PluginType::Tool),
+                Some(&github_locator("moonrepo/company-tool"))
+            );
+            assert_eq!(config.get_plugin(&ctx("other"), PluginType::Policy), None);
+        }
     }
 
     mod url_rewrites {
@@ -1687,4 +2013,96 @@ vfox = "github://moonrepo/vfox"
             );
         }
     }
+
+    mod url_mirrors {
+        use schematic::Config;
+
+        use super::*;
+
+        #[test]
+        fn returns_canonical_url_when_no_mirrors() {
+            let sandbox = create_empty_sandbox();
+            let config =
+                ProtoConfig::from_partial(ProtoConfig::load_from(sandbox.path(), false).unwrap());
+
+            assert_eq!(
+                config.get_mirror_urls("https://nodejs.org/dist/v20.0.0/node.tar.gz"),
+                vec!["https://nodejs.org/dist/v20.0.0/node.tar.gz"]
+            );
+        }
+
+        #[test]
+        fn returns_mirrors_in_order_before_canonical_url() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[settings.url-mirrors]
+"https://nodejs.org/(.*)" = ["https://mirror-a.com/$1", "https://mirror-b.com/node/$1"]
+"https://github.com/(.*)" = ["https://gh-mirror.com/$1"]
+"#,
+            );
+
+            let config =
+                ProtoConfig::from_partial(ProtoConfig::load_from(sandbox.path(), false).unwrap());
+
+            assert_eq!(
+                config.get_mirror_urls("https://nodejs.org/dist/v20.0.0/node.tar.gz"),
+                vec![
+                    "https://mirror-a.com/dist/v20.0.0/node.tar.gz",
+                    "https://mirror-b.com/node/dist/v20.0.0/node.tar.gz",
+                    "https://nodejs.org/dist/v20.0.0/node.tar.gz",
+                ]
+            );
+        }
+
+        #[test]
+        fn dedupes_mirrors() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[settings.url-mirrors]
+"https://nodejs.org/(.*)" = ["https://mirror.com/$1", "https://mirror.com/$1", "https://nodejs.org/$1"]
+"#,
+            );
+
+            let config =
+                ProtoConfig::from_partial(ProtoConfig::load_from(sandbox.path(), false).unwrap());
+
+            assert_eq!(
+                config.get_mirror_urls("https://nodejs.org/node.tar.gz"),
+                vec![
+                    "https://mirror.com/node.tar.gz",
+                    "https://nodejs.org/node.tar.gz"
+                ]
+            );
+        }
+
+        #[test]
+        fn rewrites_url_once_before_mirroring() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[settings.url-rewrites]
+"example.com/" = "example.com/dist/"
+
+[settings.url-mirrors]
+"https://example.com/(.*)" = ["https://mirror.com/$1"]
+"#,
+            );
+
+            let config =
+                ProtoConfig::from_partial(ProtoConfig::load_from(sandbox.path(), false).unwrap());
+
+            assert_eq!(
+                config.get_mirror_urls("https://example.com/file.tar.gz"),
+                vec![
+                    "https://mirror.com/dist/file.tar.gz",
+                    "https://example.com/dist/file.tar.gz"
+                ]
+            );
+        }
+    }
 }
@@ -0,0 +1,305 @@
+use proto_core::layout::ContentIndex;
+use proto_core::{DedupeMode, set_read_only_recursive};
+use starbase_sandbox::{Sandbox, create_empty_sandbox};
+
+fn create_products(sandbox: &Sandbox) {
+    let large = "x".repeat(2048);
+
+    for version in ["1.0.0", "2.0.0"] {
+        sandbox.create_file(format!("tools/{version}/lib/shared.txt"), &large);
+        sandbox.create_file(format!("tools/{version}/lib/small.txt"), "small");
+        sandbox.create_file(
+            format!("tools/{version}/lib/unique.txt"),
+            format!("{version}{large}"),
+        );
+    }
+}
+
+#[cfg(unix)]
+fn inode(path: std::path::PathBuf) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path).unwrap().ino()
+}
+
+mod content_index {
+    use super::*;
+
+    #[test]
+    fn does_nothing_when_off() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            let stats = index
+                .dedupe_dir(sandbox.path().join("tools").join(version), DedupeMode::Off)
+                .unwrap();
+
+            assert_eq!(stats.files, 0);
+        }
+
+        assert!(!index.dir.exists());
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn does_nothing_when_not_supported() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            let stats = index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+
+            assert_eq!(stats.files, 0);
+        }
+
+        assert!(!index.dir.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_identical_files() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        let stats = index
+            .dedupe_dir(sandbox.path().join("tools/1.0.0"), DedupeMode::Hardlink)
+            .unwrap();
+
+        // First occurrence is only indexed
+        assert_eq!(stats.files, 0);
+
+        let stats = index
+            .dedupe_dir(sandbox.path().join("tools/2.0.0"), DedupeMode::Hardlink)
+            .unwrap();
+
+        assert_eq!(stats.files, 1);
+        assert_eq!(stats.size, 2048);
+
+        assert_eq!(
+            inode(sandbox.path().join("tools/1.0.0/lib/shared.txt")),
+            inode(sandbox.path().join("tools/2.0.0/lib/shared.txt"))
+        );
+        assert_ne!(
+            inode(sandbox.path().join("tools/1.0.0/lib/small.txt")),
+            inode(sandbox.path().join("tools/2.0.0/lib/small.txt"))
+        );
+        assert_ne!(
+            inode(sandbox.path().join("tools/1.0.0/lib/unique.txt")),
+            inode(sandbox.path().join("tools/2.0.0/lib/unique.txt"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_idempotent() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for _ in 0..2 {
+            for version in ["1.0.0", "2.0.0"] {
+                index
+                    .dedupe_dir(
+                        sandbox.path().join("tools").join(version),
+                        DedupeMode::Hardlink,
+                    )
+                    .unwrap();
+            }
+        }
+
+        let stats = index
+            .dedupe_dir(sandbox.path().join("tools/2.0.0"), DedupeMode::Hardlink)
+            .unwrap();
+
+        assert_eq!(stats.files, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_siblings_working_when_removed() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+        }
+
+        std::fs::remove_dir_all(sandbox.path().join("tools/1.0.0")).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join("tools/2.0.0/lib/shared.txt")).unwrap(),
+            "x".repeat(2048)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detaches_linked_files() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+        }
+
+        index
+            .detach_dir(sandbox.path().join("tools/2.0.0"))
+            .unwrap();
+
+        // Writing to a detached file must not affect the other version
+        sandbox.create_file("tools/2.0.0/lib/shared.txt", "modified");
+
+        assert_eq!(
+            std::fs::read_to_string(sandbox.path().join("tools/1.0.0/lib/shared.txt")).unwrap(),
+            "x".repeat(2048)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doesnt_change_siblings_when_written_to() {
+        for mode in [DedupeMode::Hardlink, DedupeMode::Reflink] {
+            let sandbox = create_empty_sandbox();
+            create_products(&sandbox);
+
+            let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+            for version in ["1.0.0", "2.0.0"] {
+                index
+                    .dedupe_dir(sandbox.path().join("tools").join(version), mode)
+                    .unwrap();
+            }
+
+            let path = sandbox.path().join("tools/2.0.0/lib/shared.txt");
+
+            // Hard linked files are read-only, and must be detached before writing
+            if matches!(mode, DedupeMode::Hardlink) {
+                assert!(std::fs::metadata(&path).unwrap().permissions().readonly());
+
+                index
+                    .detach_dir(sandbox.path().join("tools/2.0.0"))
+                    .unwrap();
+            }
+
+            std::fs::write(&path, "modified").unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(sandbox.path().join("tools/1.0.0/lib/shared.txt")).unwrap(),
+                "x".repeat(2048)
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_linked_files_read_only_when_unlocked() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+        }
+
+        set_read_only_recursive(sandbox.path().join("tools/2.0.0"), false).unwrap();
+
+        assert!(
+            std::fs::metadata(sandbox.path().join("tools/1.0.0/lib/shared.txt"))
+                .unwrap()
+                .permissions()
+                .readonly()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prunes_unlinked_entries() {
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+        }
+
+        // Still linked to 2.0.0
+        std::fs::remove_dir_all(sandbox.path().join("tools/1.0.0")).unwrap();
+
+        assert_eq!(index.prune().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(sandbox.path().join("tools/2.0.0")).unwrap();
+
+        assert_eq!(index.prune().unwrap().len(), 2);
+        assert!(std::fs::read_dir(&index.dir).unwrap().next().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doesnt_link_files_with_different_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = create_empty_sandbox();
+        create_products(&sandbox);
+
+        std::fs::set_permissions(
+            sandbox.path().join("tools/2.0.0/lib/shared.txt"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+
+        let index = ContentIndex::new(sandbox.path().join("dedupe"));
+
+        for version in ["1.0.0", "2.0.0"] {
+            index
+                .dedupe_dir(
+                    sandbox.path().join("tools").join(version),
+                    DedupeMode::Hardlink,
+                )
+                .unwrap();
+        }
+
+        assert_ne!(
+            inode(sandbox.path().join("tools/1.0.0/lib/shared.txt")),
+            inode(sandbox.path().join("tools/2.0.0/lib/shared.txt"))
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use proto_core::{Id, LockRecord, ProtoFileManager, ToolContext};
 use starbase_sandbox::create_empty_sandbox;
 use std::collections::BTreeMap;
-use version_spec::UnresolvedVersionSpec;
+use system_env::{SystemArch, SystemOS};
+use version_spec::{UnresolvedVersionSpec, Version, VersionSpec};
 use warpgate::{FileLocator, PluginLocator};
 
 mod file_manager {
@@ -739,4 +740,402 @@ spec = "7.8.9"
             assert!(!sandbox.path().join(".protolock").exists());
         }
     }
+
+    mod allow {
+        use super::*;
+
+        #[test]
+        fn requires_all_layers_to_be_satisfied() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+[tools.node]
+allow = ">=18"
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node]
+allow = "<20"
+"#,
+            );
+
+            let manager = ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap();
+            let config = manager.get_merged_config().unwrap();
+            let tool_config = config.tools.get("node").unwrap();
+
+            assert!(tool_config.is_version_allowed(&VersionSpec::parse("19.0.0").unwrap()));
+            assert!(!tool_config.is_version_allowed(&VersionSpec::parse("17.0.0").unwrap()));
+            assert!(!tool_config.is_version_allowed(&VersionSpec::parse("20.0.0").unwrap()));
+            assert_eq!(tool_config.get_allow_requirements().len(), 2);
+        }
+    }
+
+    mod version_conflicts {
+        use super::*;
+
+        #[test]
+        fn ignores_versions_overridden_by_closer_configs() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+node = "22.1.0"
+deno = "^2"
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = "20.0.0"
+deno = ">=3"
+"#,
+            );
+
+            let manager = ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap();
+
+            assert!(manager.get_version_conflicts().is_empty());
+        }
+
+        #[test]
+        fn detects_unsatisfiable_versions_in_same_directory() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                ".prototools.ci",
+                r#"
+node = "^20"
+bun = "latest"
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = ">=22"
+bun = "1.2.3"
+"#,
+            );
+
+            let manager = ProtoFileManager::load(
+                sandbox.path(),
+                Some(sandbox.path().parent().unwrap()),
+                Some(&"ci".to_owned()),
+            )
+            .unwrap();
+            let conflicts = manager.get_version_conflicts();
+
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].context, ToolContext::parse("node").unwrap());
+            assert_eq!(
+                conflicts[0].specs,
+                vec![
+                    (
+                        sandbox.path().join(".prototools.ci"),
+                        UnresolvedVersionSpec::parse("^20").unwrap()
+                    ),
+                    (
+                        sandbox.path().join(".prototools"),
+                        UnresolvedVersionSpec::parse(">=22").unwrap()
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn detects_closest_version_outside_of_allow() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+node = "22.1.0"
+deno = "2.1.0"
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node]
+allow = "<22"
+
+[tools.deno]
+allow = "^2"
+"#,
+            );
+
+            let manager = ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap();
+            let conflicts = manager.get_version_conflicts();
+
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].context, ToolContext::parse("node").unwrap());
+            assert_eq!(
+                conflicts[0].specs,
+                vec![
+                    (
+                        sandbox.path().join("one/.prototools"),
+                        UnresolvedVersionSpec::parse("22.1.0").unwrap()
+                    ),
+                    (
+                        sandbox.path().join(".prototools"),
+                        UnresolvedVersionSpec::parse("<22").unwrap()
+                    ),
+                ]
+            );
+        }
+    }
+
+    mod platform_pins {
+        use super::*;
+
+        #[test]
+        fn replaces_versions_for_matching_platform() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+terraform = "1.6.0"
+
+[tools.terraform.platform.linux-arm64]
+version = "1.5.7"
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = "20"
+
+[tools.node.platform.macos]
+version = "18"
+"#,
+            );
+
+            let mut manager = ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap();
+
+            manager.apply_platform_pins(SystemOS::Linux, SystemArch::Arm64);
+
+            let config = manager.get_merged_config().unwrap();
+
+            assert_eq!(
+                config.versions.get("terraform").unwrap().req,
+                UnresolvedVersionSpec::parse("1.5.7").unwrap()
+            );
+            assert_eq!(
+                config.versions.get("node").unwrap().req,
+                UnresolvedVersionSpec::parse("20").unwrap()
+            );
+        }
+
+        #[test]
+        fn prefers_os_arch_over_os() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.node.platform.macos]
+version = "18"
+
+[tools.node.platform.macos-arm64]
+version = "20"
+"#,
+            );
+
+            let mut manager = ProtoFileManager::load(sandbox.path(), None, None).unwrap();
+
+            manager.apply_platform_pins(SystemOS::MacOS, SystemArch::Arm64);
+
+            assert_eq!(
+                manager
+                    .get_merged_config()
+                    .unwrap()
+                    .versions
+                    .get("node")
+                    .unwrap()
+                    .req,
+                UnresolvedVersionSpec::parse("20").unwrap()
+            );
+        }
+
+        #[test]
+        fn tracks_which_platform_replaced_the_version() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+node = "20"
+deno = "2"
+
+[tools.node.platform.macos]
+version = "18"
+"#,
+            );
+
+            let mut manager = ProtoFileManager::load(sandbox.path(), None, None).unwrap();
+
+            manager.apply_platform_pins(SystemOS::MacOS, SystemArch::Arm64);
+
+            let file = &manager.entries[0].configs[0];
+
+            assert_eq!(
+                file.platform_pins,
+                BTreeMap::from_iter([(ToolContext::parse("node").unwrap(), "macos".into())])
+            );
+        }
+    }
+
+    mod dedupe {
+        use super::*;
+
+        #[test]
+        fn allows_hardlinks_with_read_only_tools() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+[settings]
+read-only-tools = true
+"#,
+            );
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[settings]
+dedupe = "hardlink"
+"#,
+            );
+
+            let manager = ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap();
+
+            assert!(manager.get_merged_config().is_ok());
+        }
+
+        #[test]
+        fn allows_reflinks_with_read_only_tools() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[settings]
+dedupe = "reflink"
+read-only-tools = true
+"#,
+            );
+
+            let manager =
+                ProtoFileManager::load(sandbox.path(), Some(sandbox.path()), None).unwrap();
+
+            assert!(manager.get_merged_config().is_ok());
+        }
+    }
+
+    mod proto_version {
+        use super::*;
+
+        #[test]
+        fn loads_when_requirement_satisfied() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(".prototools", r#"proto-version = ">=0.1.0""#);
+
+            let manager =
+                ProtoFileManager::load(sandbox.path(), Some(sandbox.path()), None).unwrap();
+            let config = manager.get_merged_config().unwrap();
+
+            assert_eq!(
+                config.proto_version,
+                Some(UnresolvedVersionSpec::parse(">=0.1.0").unwrap())
+            );
+            assert!(config.versions.is_empty());
+        }
+
+        #[test]
+        fn ignores_aliases() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(".prototools", r#"proto-version = "latest""#);
+
+            assert!(ProtoFileManager::load(sandbox.path(), Some(sandbox.path()), None).is_ok());
+        }
+
+        #[test]
+        #[should_panic(expected = "UnsupportedProtoVersion")]
+        fn errors_when_requirement_not_satisfied() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(
+                "one/.prototools",
+                r#"
+node = "20"
+"#,
+            );
+
+            sandbox.create_file(".prototools", r#"proto-version = ">=1000.0.0""#);
+
+            ProtoFileManager::load(
+                sandbox.path().join("one"),
+                Some(sandbox.path().parent().unwrap()),
+                None,
+            )
+            .unwrap()
+            .check_proto_version(&Version::new(0, 1, 0))
+            .unwrap();
+        }
+
+        #[test]
+        fn treats_a_bare_version_as_a_minimum() {
+            let sandbox = create_empty_sandbox();
+
+            sandbox.create_file(".prototools", r#"proto-version = "0.50.0""#);
+
+            let manager =
+                ProtoFileManager::load(sandbox.path(), Some(sandbox.path()), None).unwrap();
+
+            assert!(manager.check_proto_version(&Version::new(0, 50, 0)).is_ok());
+            assert!(manager.check_proto_version(&Version::new(0, 52, 1)).is_ok());
+            assert!(
+                manager
+                    .check_proto_version(&Version::new(0, 49, 9))
+                    .is_err()
+            );
+        }
+    }
 }
@@ -0,0 +1,227 @@
+use proto_core::checksum::{fingerprint_dir, stamp_dir};
+use proto_core::set_read_only_recursive;
+use starbase_sandbox::{Sandbox, create_empty_sandbox};
+
+fn create_product(sandbox: &Sandbox) {
+    sandbox.create_file("product/bin/tool", "#!/bin/sh");
+    sandbox.create_file("product/lib/index.js", "export default {};");
+    sandbox.create_file("product/README.md", "# Tool");
+}
+
+mod fingerprint {
+    use super::*;
+
+    #[test]
+    fn is_stable() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+
+        assert_eq!(
+            fingerprint_dir(&dir).unwrap(),
+            fingerprint_dir(&dir).unwrap()
+        );
+    }
+
+    #[test]
+    fn matches_for_identical_dirs() {
+        let a = create_empty_sandbox();
+        let b = create_empty_sandbox();
+        create_product(&a);
+        create_product(&b);
+
+        assert_eq!(
+            fingerprint_dir(a.path().join("product")).unwrap(),
+            fingerprint_dir(b.path().join("product")).unwrap()
+        );
+    }
+
+    #[test]
+    fn changes_when_file_modified() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        sandbox.create_file("product/lib/index.js", "export default { tampered: true };");
+
+        assert_ne!(before, fingerprint_dir(&dir).unwrap());
+    }
+
+    #[test]
+    fn changes_when_file_added_or_removed() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        sandbox.create_file("product/lib/extra.js", "");
+
+        let added = fingerprint_dir(&dir).unwrap();
+
+        assert_ne!(before, added);
+
+        std::fs::remove_file(dir.join("lib/extra.js")).unwrap();
+        std::fs::remove_file(dir.join("README.md")).unwrap();
+
+        let removed = fingerprint_dir(&dir).unwrap();
+
+        assert_ne!(before, removed);
+        assert_ne!(added, removed);
+    }
+
+    #[test]
+    fn changes_when_file_renamed() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        std::fs::rename(dir.join("README.md"), dir.join("README.txt")).unwrap();
+
+        assert_ne!(before, fingerprint_dir(&dir).unwrap());
+    }
+
+    #[test]
+    fn ignores_last_used_file() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        sandbox.create_file("product/.last-used", "123");
+
+        assert_eq!(before, fingerprint_dir(&dir).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn changes_when_executable_bit_changes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        std::fs::set_permissions(dir.join("bin/tool"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        assert_ne!(before, fingerprint_dir(&dir).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_not_affected_by_read_only() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = fingerprint_dir(&dir).unwrap();
+
+        set_read_only_recursive(&dir, true).unwrap();
+
+        assert_eq!(before, fingerprint_dir(&dir).unwrap());
+
+        set_read_only_recursive(&dir, false).unwrap();
+    }
+}
+
+mod read_only {
+    use super::*;
+
+    #[test]
+    fn locks_and_unlocks_files() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+
+        set_read_only_recursive(&dir, true).unwrap();
+
+        for file in ["bin/tool", "lib/index.js", "README.md"] {
+            assert!(
+                std::fs::metadata(dir.join(file))
+                    .unwrap()
+                    .permissions()
+                    .readonly()
+            );
+        }
+
+        // The product directory itself remains writable
+        assert!(!std::fs::metadata(&dir).unwrap().permissions().readonly());
+
+        set_read_only_recursive(&dir, false).unwrap();
+
+        for file in ["bin/tool", "lib/index.js", "README.md"] {
+            assert!(
+                !std::fs::metadata(dir.join(file))
+                    .unwrap()
+                    .permissions()
+                    .readonly()
+            );
+        }
+
+        // And can be removed
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+mod stamp {
+    use super::*;
+
+    #[test]
+    fn is_stable() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+
+        assert_eq!(stamp_dir(&dir).unwrap(), stamp_dir(&dir).unwrap());
+    }
+
+    #[test]
+    fn changes_when_file_modified() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = stamp_dir(&dir).unwrap();
+
+        sandbox.create_file("product/lib/index.js", "export default { tampered: true };");
+
+        assert_ne!(before, stamp_dir(&dir).unwrap());
+    }
+
+    #[test]
+    fn changes_when_file_added() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = stamp_dir(&dir).unwrap();
+
+        sandbox.create_file("product/lib/extra.js", "");
+
+        assert_ne!(before, stamp_dir(&dir).unwrap());
+    }
+
+    #[test]
+    fn ignores_last_used_file() {
+        let sandbox = create_empty_sandbox();
+        create_product(&sandbox);
+
+        let dir = sandbox.path().join("product");
+        let before = stamp_dir(&dir).unwrap();
+
+        sandbox.create_file("product/.last-used", "123");
+
+        assert_eq!(before, stamp_dir(&dir).unwrap());
+    }
+}
@@ -0,0 +1,71 @@
+use proto_core::flow::install::{InstallOptions, Installer, ProtoInstallError};
+use proto_core::{
+    ProtoConfig, ProtoEnvironment, Tool, ToolContext, ToolSpec, load_tool_from_locator,
+};
+use starbase_sandbox::create_empty_sandbox;
+use std::path::Path;
+
+async fn create_mocked_tool(root: &Path) -> Tool {
+    let mut proto = ProtoEnvironment::new_testing(root).unwrap();
+    proto.working_dir = root.to_path_buf();
+
+    load_tool_from_locator(
+        ToolContext::parse("protostar").unwrap(),
+        proto,
+        ProtoConfig::default()
+            .builtin_plugins()
+            .tools
+            .get("protostar")
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+mod installer {
+    use super::*;
+
+    mod require_verified {
+        use super::*;
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn errors_without_a_published_checksum() {
+            let sandbox = create_empty_sandbox();
+
+            // Nothing is listening, as the archive must never be downloaded
+            sandbox.create_file(
+                ".prototools",
+                r#"
+[tools.protostar]
+download-url = "http://127.0.0.1:9/dist/protostar"
+"#,
+            );
+
+            let tool = create_mocked_tool(sandbox.path()).await;
+            let spec = ToolSpec::parse("1.0.0").unwrap();
+            let installer = Installer::new(&tool, &spec);
+
+            let error = installer
+                .install(InstallOptions {
+                    require_verified: true,
+                    skip_prompts: true,
+                    skip_ui: true,
+                    ..Default::default()
+                })
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                error,
+                ProtoInstallError::UnverifiedDownload { .. }
+            ));
+            assert!(
+                !installer.product_dir.exists()
+                    || std::fs::read_dir(&installer.product_dir)
+                        .unwrap()
+                        .next()
+                        .is_none()
+            );
+        }
+    }
+}
@@ -181,4 +181,82 @@ mod inventory {
             );
         }
     }
+    mod system_store {
+        use super::*;
+        use proto_core::ToolManifest;
+
+        fn create_system_inventory(dir: &Path, versions: &[&str]) -> Inventory {
+            let mut manifest = ToolManifest::default();
+
+            for version in versions {
+                manifest
+                    .installed_versions
+                    .insert(VersionSpec::parse(version).unwrap());
+            }
+
+            Inventory {
+                dir: dir.join("user"),
+                system_dir: Some(dir.join("system")),
+                system_manifest: Some(manifest),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn returns_none_without_system_store() {
+            let sandbox = create_empty_sandbox();
+            let inventory = create_inventory(sandbox.path());
+
+            assert!(
+                inventory
+                    .get_system_product_dir(&VersionSpec::parse("1.0.0").unwrap())
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn returns_none_if_not_in_manifest() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file("system/1.0.0/file", "");
+
+            let inventory = create_system_inventory(sandbox.path(), &[]);
+
+            assert!(
+                inventory
+                    .get_system_product_dir(&VersionSpec::parse("1.0.0").unwrap())
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn returns_none_if_dir_missing() {
+            let sandbox = create_empty_sandbox();
+            let inventory = create_system_inventory(sandbox.path(), &["1.0.0"]);
+
+            assert!(
+                inventory
+                    .get_system_product_dir(&VersionSpec::parse("1.0.0").unwrap())
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn returns_dir_if_installed() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file("system/1.0.0/file", "");
+
+            let inventory = create_system_inventory(sandbox.path(), &["1.0.0"]);
+
+            assert_eq!(
+                inventory
+                    .get_system_product_dir(&VersionSpec::parse("1.0.0").unwrap())
+                    .unwrap(),
+                sandbox.path().join("system/1.0.0")
+            );
+            assert_eq!(
+                inventory.get_product_dir(&VersionSpec::parse("1.0.0").unwrap()),
+                sandbox.path().join("user/1.0.0")
+            );
+        }
+    }
 }
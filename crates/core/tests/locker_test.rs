@@ -1098,6 +1098,56 @@ mod locker {
             assert!(result.is_err());
         }
 
+        #[tokio::test(flavor = "multi_thread")]
+        async fn doesnt_fail_when_fingerprints_mismatch() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(".prototools", "");
+
+            let tool = create_tool_in_sandbox(sandbox.path()).await;
+            let locker = Locker::new(&tool);
+
+            let checksum = Checksum::sha256("abc123".into());
+
+            let mut spec = ToolSpec::parse("20.0.0").unwrap();
+            spec.version_locked = Some(LockRecord {
+                checksum: Some(checksum.clone()),
+                fingerprint: Some("expected_fingerprint".into()),
+                ..Default::default()
+            });
+
+            let install_record = LockRecord {
+                checksum: Some(checksum),
+                fingerprint: Some("actual_fingerprint".into()),
+                ..Default::default()
+            };
+
+            // Post-install scripts may modify files, so only a warning is logged
+            locker.verify_locked_record(&spec, &install_record).unwrap();
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn skips_fingerprints_without_checksum() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(".prototools", "");
+
+            let tool = create_tool_in_sandbox(sandbox.path()).await;
+            let locker = Locker::new(&tool);
+
+            let mut spec = ToolSpec::parse("20.0.0").unwrap();
+            spec.version_locked = Some(LockRecord {
+                fingerprint: Some("expected_fingerprint".into()),
+                ..Default::default()
+            });
+
+            let install_record = LockRecord {
+                fingerprint: Some("actual_fingerprint".into()),
+                ..Default::default()
+            };
+
+            // Built from source, so files differ between machines
+            locker.verify_locked_record(&spec, &install_record).unwrap();
+        }
+
         #[tokio::test(flavor = "multi_thread")]
         async fn skips_verification_when_different_backends() {
             let sandbox = create_empty_sandbox();
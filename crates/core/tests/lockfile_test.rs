@@ -1,5 +1,5 @@
 use proto_core::{Id, LockRecord, ProtoLock};
-use proto_pdk_api::ToolLockOptions;
+use proto_pdk_api::{Checksum, ToolLockOptions};
 use starbase_sandbox::create_empty_sandbox;
 use system_env::{SystemArch, SystemOS};
 use version_spec::{UnresolvedVersionSpec, VersionSpec};
@@ -195,6 +195,37 @@ mod lockfile {
             assert!(lockfile_record.spec.is_some());
             assert!(lockfile_record.version.is_some());
         }
+
+        #[test]
+        fn for_manifest_strips_fingerprint() {
+            let record = LockRecord {
+                fingerprint: Some("abc123".into()),
+                ..Default::default()
+            };
+
+            assert!(record.for_manifest().fingerprint.is_none());
+        }
+
+        #[test]
+        fn for_lockfile_keeps_fingerprint_with_checksum() {
+            let record = LockRecord {
+                checksum: Some(Checksum::sha256("hash".into())),
+                fingerprint: Some("abc123".into()),
+                ..Default::default()
+            };
+
+            assert_eq!(record.for_lockfile().fingerprint, Some("abc123".into()));
+        }
+
+        #[test]
+        fn for_lockfile_strips_fingerprint_without_checksum() {
+            let record = LockRecord {
+                fingerprint: Some("abc123".into()),
+                ..Default::default()
+            };
+
+            assert!(record.for_lockfile().fingerprint.is_none());
+        }
     }
 
     mod proto_lock_io {
@@ -0,0 +1,62 @@
+use proto_core::layout::ProjectRegistry;
+use starbase_sandbox::create_empty_sandbox;
+
+mod project_registry {
+    use super::*;
+
+    #[test]
+    fn registers_and_saves_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("project/.prototools", "node = \"20\"");
+
+        let project_dir = sandbox.path().join("project");
+        let mut registry = ProjectRegistry::load_from(sandbox.path()).unwrap();
+
+        assert!(registry.register(&project_dir, 1000));
+        registry.save().unwrap();
+
+        let registry = ProjectRegistry::load_from(sandbox.path()).unwrap();
+
+        assert_eq!(
+            registry.projects.get(&project_dir).unwrap().last_seen_at,
+            1000
+        );
+    }
+
+    #[test]
+    fn only_refreshes_once_a_day() {
+        let sandbox = create_empty_sandbox();
+        let project_dir = sandbox.path().join("project");
+        let mut registry = ProjectRegistry::load_from(sandbox.path()).unwrap();
+
+        assert!(registry.register(&project_dir, 1000));
+        assert!(!registry.register(&project_dir, 2000));
+        assert!(registry.register(&project_dir, 1000 + 24 * 60 * 60 * 1000));
+    }
+
+    #[test]
+    fn prunes_missing_projects() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a/.prototools", "node = \"20\"");
+        sandbox.create_file("b/.protolock", "");
+
+        let mut registry = ProjectRegistry::load_from(sandbox.path()).unwrap();
+
+        for dir in ["a", "b", "c"] {
+            registry.register(&sandbox.path().join(dir), 1000);
+        }
+
+        registry.save().unwrap();
+
+        assert_eq!(registry.prune(), vec![sandbox.path().join("c")]);
+
+        registry.save().unwrap();
+
+        let registry = ProjectRegistry::load_from(sandbox.path()).unwrap();
+
+        assert_eq!(
+            registry.projects.keys().collect::<Vec<_>>(),
+            [&sandbox.path().join("a"), &sandbox.path().join("b")]
+        );
+    }
+}
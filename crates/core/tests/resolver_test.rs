@@ -356,6 +356,7 @@ mod resolver {
             let result = resolver
                 .resolve_version_from_list(&candidate, false)
                 .await
+                .unwrap()
                 .unwrap();
 
             let resolved = result.to_string();
@@ -379,7 +380,8 @@ mod resolver {
             let bad_candidate = UnresolvedVersionSpec::parse("999.999.999").unwrap();
             let result = resolver
                 .resolve_version_from_list(&bad_candidate, false)
-                .await;
+                .await
+                .unwrap();
 
             assert!(result.is_none());
         }
@@ -399,7 +401,10 @@ mod resolver {
             resolver.load_versions(&initial).await.unwrap();
 
             // After loading, the data resolver should have versions
-            let result = resolver.resolve_version_from_list(&initial, false).await;
+            let result = resolver
+                .resolve_version_from_list(&initial, false)
+                .await
+                .unwrap();
 
             assert!(result.is_some());
         }
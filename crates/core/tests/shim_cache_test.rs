@@ -0,0 +1,130 @@
+use proto_core::layout::{ShimCache, ShimCacheEntry};
+use starbase_sandbox::create_empty_sandbox;
+use std::path::PathBuf;
+
+mod shim_cache {
+    use super::*;
+
+    #[test]
+    fn uses_a_file_per_directory() {
+        let sandbox = create_empty_sandbox();
+
+        let a = ShimCache::load_from(sandbox.path(), &sandbox.path().join("a"));
+        let b = ShimCache::load_from(sandbox.path(), &sandbox.path().join("b"));
+
+        assert_ne!(a.path, b.path);
+        assert!(a.path.starts_with(sandbox.path().join("shims")));
+    }
+
+    #[test]
+    fn saves_and_loads_entries() {
+        let sandbox = create_empty_sandbox();
+        let mut cache = ShimCache::load_from(sandbox.path(), sandbox.path());
+
+        cache.insert(
+            "node",
+            ShimCacheEntry {
+                exe: PathBuf::from("/bin/node"),
+                before_args: vec!["--a".into()],
+                ..Default::default()
+            },
+        );
+        cache.save().unwrap();
+
+        let cache = ShimCache::load_from(sandbox.path(), sandbox.path());
+
+        assert_eq!(cache.get("node").unwrap().exe, PathBuf::from("/bin/node"));
+        assert_eq!(cache.get("node").unwrap().before_args, vec!["--a"]);
+    }
+
+    #[test]
+    fn treats_malformed_files_as_empty() {
+        let sandbox = create_empty_sandbox();
+        let cache = ShimCache::load_from(sandbox.path(), sandbox.path());
+
+        std::fs::create_dir_all(cache.path.parent().unwrap()).unwrap();
+        std::fs::write(&cache.path, "{").unwrap();
+
+        let cache = ShimCache::load_from(sandbox.path(), sandbox.path());
+
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn is_stale_when_a_tracked_file_changes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(".prototools", "node = \"20\"");
+        sandbox.create_file("node", "");
+
+        let mut entry = ShimCacheEntry {
+            exe: sandbox.path().join("node"),
+            ..Default::default()
+        };
+        entry.track_file(sandbox.path().join(".prototools"));
+
+        assert!(entry.is_fresh());
+
+        entry
+            .files
+            .insert(sandbox.path().join(".prototools"), Some(0));
+
+        assert!(!entry.is_fresh());
+    }
+
+    #[test]
+    fn is_stale_when_a_tracked_file_is_created() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node", "");
+
+        let mut entry = ShimCacheEntry {
+            exe: sandbox.path().join("node"),
+            ..Default::default()
+        };
+        entry.track_file(sandbox.path().join(".nvmrc"));
+
+        assert!(entry.is_fresh());
+
+        sandbox.create_file(".nvmrc", "20");
+
+        assert!(!entry.is_fresh());
+    }
+
+    #[test]
+    fn is_stale_when_a_tracked_var_changes() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("node", "");
+
+        let mut entry = ShimCacheEntry {
+            exe: sandbox.path().join("node"),
+            ..Default::default()
+        };
+        entry.track_var("PROTO_SHIM_CACHE_TEST_VERSION");
+
+        assert!(entry.is_fresh());
+
+        entry
+            .vars
+            .insert("PROTO_SHIM_CACHE_TEST_VERSION".into(), Some("1.2.3".into()));
+
+        assert!(!entry.is_fresh());
+    }
+
+    #[test]
+    fn is_stale_when_exe_is_missing() {
+        let sandbox = create_empty_sandbox();
+
+        let entry = ShimCacheEntry {
+            exe: sandbox.path().join("node"),
+            ..Default::default()
+        };
+
+        assert!(!entry.is_fresh());
+
+        let entry = ShimCacheEntry {
+            exe: PathBuf::from("node"),
+            ..Default::default()
+        };
+
+        assert!(entry.is_fresh());
+    }
+}
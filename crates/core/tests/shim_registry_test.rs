@@ -169,6 +169,115 @@ mod registry {
         );
     }
 
+    #[test]
+    fn removes_entries_on_save() {
+        let sandbox = create_empty_sandbox();
+        let path = sandbox.path().join("shims/registry.json");
+
+        let mut reg = ShimRegistry::load(&path).unwrap();
+        reg.update("uv".into(), Shim::default()).unwrap();
+        reg.update("uvx".into(), secondary("uv")).unwrap();
+        reg.save().unwrap();
+
+        let mut reg = ShimRegistry::load(&path).unwrap();
+
+        assert!(reg.remove("uvx").is_some());
+        assert!(reg.remove("unknown").is_none());
+        assert!(reg.get("uvx").is_none());
+
+        reg.save().unwrap();
+
+        assert_eq!(registry_keys(&path), vec!["uv"]);
+    }
+
+    #[test]
+    fn doesnt_remove_entries_changed_since_load() {
+        let sandbox = create_empty_sandbox();
+        let path = sandbox.path().join("shims/registry.json");
+
+        let mut reg = ShimRegistry::load(&path).unwrap();
+        reg.update("uvx".into(), secondary("uv")).unwrap();
+        reg.save().unwrap();
+
+        let mut stale_reg = ShimRegistry::load(&path).unwrap();
+        let mut other_reg = ShimRegistry::load(&path).unwrap();
+
+        other_reg.update("uvx".into(), Shim::default()).unwrap();
+        other_reg.save().unwrap();
+
+        stale_reg.remove("uvx");
+        stale_reg.save().unwrap();
+
+        assert_eq!(registry_keys(&path), vec!["uvx"]);
+    }
+
+    #[test]
+    fn replaces_paths_in_args_and_env_vars() {
+        let sandbox = create_empty_sandbox();
+        let path = sandbox.path().join("shims/registry.json");
+        let from = sandbox.path().join("old");
+        let to = sandbox.path().join("new");
+
+        let mut registry = ShimRegistry::load(&path).unwrap();
+        registry
+            .update(
+                "tool".into(),
+                Shim {
+                    before_args: vec![format!("--config={}", from.join("config").display())],
+                    env_vars: [("TOOL_HOME".into(), from.display().to_string())]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        registry.update("other".into(), Shim::default()).unwrap();
+        registry.save().unwrap();
+
+        let mut registry = ShimRegistry::load(&path).unwrap();
+
+        assert_eq!(registry.replace_path(&from, &to).unwrap(), vec!["tool"]);
+
+        registry.save().unwrap();
+
+        let shim = ShimRegistry::load(&path)
+            .unwrap()
+            .shims
+            .remove("tool")
+            .unwrap();
+
+        assert_eq!(
+            shim.before_args,
+            vec![format!("--config={}", to.join("config").display())]
+        );
+        assert_eq!(shim.env_vars["TOOL_HOME"], to.display().to_string());
+    }
+
+    #[test]
+    fn doesnt_replace_partial_path_matches() {
+        let sandbox = create_empty_sandbox();
+        let path = sandbox.path().join("shims/registry.json");
+        let from = sandbox.path().join("old");
+
+        let mut registry = ShimRegistry::load(&path).unwrap();
+        registry
+            .update(
+                "tool".into(),
+                Shim {
+                    after_args: vec![format!("{}-backup", from.display())],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert!(
+            registry
+                .replace_path(&from, &sandbox.path().join("new"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn load_treats_empty_file_as_empty_registry() {
         let sandbox = create_empty_sandbox();
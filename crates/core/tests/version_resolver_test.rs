@@ -1,6 +1,9 @@
 use proto_core::{
-    ProtoToolConfig, ToolManifest, UnresolvedVersionSpec, Version, VersionSpec, resolve_version,
+    AliasExpression, AliasPinPart, Id, ProtoToolConfig, ToolManifest, UnresolvedVersionSpec,
+    Version, VersionFilter, VersionResolver, VersionSpec, resolve_version,
+    resolve_version_with_filter,
 };
+use proto_pdk_api::{LoadVersionsOutput, VersionMetadata};
 use std::collections::BTreeMap;
 
 mod version_resolver {
@@ -82,6 +85,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(10, 0, 0)
         );
@@ -94,6 +98,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(10, 0, 0)
         );
@@ -114,6 +119,7 @@ mod version_resolver {
                 Some(&manifest),
                 Some(&config),
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -126,6 +132,7 @@ mod version_resolver {
                 Some(&manifest),
                 Some(&config),
             )
+            .unwrap()
             .unwrap(),
             Version::new(10, 0, 0)
         );
@@ -144,6 +151,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -160,6 +168,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -176,6 +185,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -192,6 +202,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 10, 5)
         );
@@ -204,6 +215,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -224,6 +236,7 @@ mod version_resolver {
                 Some(&manifest),
                 Some(&config),
             )
+            .unwrap()
             .unwrap(),
             Version::new(3, 0, 0)
         );
@@ -242,6 +255,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 2, 3)
         );
@@ -254,6 +268,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 0, 0)
         );
@@ -266,6 +281,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 10, 5)
         );
@@ -286,6 +302,7 @@ mod version_resolver {
                 Some(&manifest),
                 Some(&config),
             )
+            .unwrap()
             .unwrap(),
             Version::new(3, 3, 3)
         );
@@ -298,6 +315,7 @@ mod version_resolver {
                 Some(&manifest),
                 Some(&config),
             )
+            .unwrap()
             .unwrap(),
             Version::new(3, 3, 3)
         );
@@ -316,6 +334,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -328,6 +347,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -346,6 +366,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -362,6 +383,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -374,6 +396,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 1, 1)
         );
@@ -386,6 +409,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -398,6 +422,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -411,6 +436,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(1, 10, 5)
         );
@@ -424,6 +450,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(10, 0, 0)
         );
@@ -442,6 +469,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -458,6 +486,7 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap(),
             Version::new(8, 0, 0)
         );
@@ -476,6 +505,7 @@ mod version_resolver {
             None,
             None,
         )
+        .unwrap()
         .unwrap();
     }
 
@@ -492,7 +522,653 @@ mod version_resolver {
                 None,
                 None,
             )
+            .unwrap()
             .unwrap();
         }
     }
+
+    fn create_yanked_metadata(versions: &[&str]) -> BTreeMap<VersionSpec, VersionMetadata> {
+        BTreeMap::from_iter(versions.iter().map(|version| {
+            (
+                VersionSpec::parse(version).unwrap(),
+                VersionMetadata {
+                    yanked: true,
+                    ..Default::default()
+                },
+            )
+        }))
+    }
+
+    #[test]
+    fn skips_yanked_versions_for_reqs() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let metadata = create_yanked_metadata(&["1.10.5", "10.0.0"]);
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse("1").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(1, 5, 9)
+        );
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse(">=8").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(8, 0, 0)
+        );
+    }
+
+    #[test]
+    fn skips_yanked_installed_versions() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let manifest = create_manifest();
+        let metadata = create_yanked_metadata(&["3.3.3"]);
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse("^3").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    ..Default::default()
+                },
+                Some(&manifest),
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(3, 0, 0)
+        );
+    }
+
+    #[test]
+    fn resolves_yanked_versions_only_when_pinned() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let metadata = create_yanked_metadata(&["10.0.0"]);
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse("10.0.0").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(10, 0, 0)
+        );
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::Alias("latest".into()),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap_err()
+            .to_string(),
+            "Alias latest resolved to version 10.0.0, which has been yanked."
+        );
+    }
+
+    #[test]
+    fn defaults_latest_to_newest_unyanked_version() {
+        let resolver = VersionResolver::from_output(LoadVersionsOutput {
+            versions: create_versions(),
+            metadata: create_yanked_metadata(&["10.0.0"]).into_iter().collect(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            resolver
+                .resolve(&UnresolvedVersionSpec::default())
+                .unwrap()
+                .unwrap(),
+            Version::new(8, 0, 0)
+        );
+    }
+
+    fn create_release_metadata() -> BTreeMap<VersionSpec, VersionMetadata> {
+        BTreeMap::from_iter(
+            [("8.0.0", "2024-01-01"), ("10.0.0", "2024-06-15T12:00:00Z")]
+                .into_iter()
+                .map(|(version, date)| {
+                    (
+                        VersionSpec::parse(version).unwrap(),
+                        VersionMetadata {
+                            release_date: Some(date.into()),
+                            ..Default::default()
+                        },
+                    )
+                }),
+        )
+    }
+
+    #[test]
+    fn holds_back_recent_versions_for_reqs() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let metadata = create_release_metadata();
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse(">=5").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    release_cutoff: Some("2024-06-01"),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(8, 0, 0)
+        );
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse(">=5").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    release_cutoff: Some("2024-06-15"),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(10, 0, 0)
+        );
+    }
+
+    #[test]
+    fn holds_back_recent_versions_for_aliases() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let metadata = create_release_metadata();
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::Alias("stable".into()),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    release_cutoff: Some("2023-12-01"),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(7, 8, 9)
+        );
+    }
+
+    #[test]
+    fn holds_back_recent_versions_for_aliases_within_lts_line() {
+        let versions =
+            ["20.10.0", "21.6.0", "22.1.0"].map(|version| VersionSpec::parse(version).unwrap());
+        let aliases = BTreeMap::from_iter([(
+            "lts".into(),
+            UnresolvedVersionSpec::parse("22.1.0").unwrap(),
+        )]);
+        let metadata = BTreeMap::from_iter(
+            [
+                ("20.10.0", "2023-10-24"),
+                ("21.6.0", "2024-01-14"),
+                ("22.1.0", "2024-10-29"),
+            ]
+            .map(|(version, date)| {
+                (
+                    VersionSpec::parse(version).unwrap(),
+                    VersionMetadata {
+                        lts: if version == "21.6.0" {
+                            None
+                        } else {
+                            Some("codename".into())
+                        },
+                        release_date: Some(date.into()),
+                        ..Default::default()
+                    },
+                )
+            }),
+        );
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::Alias("lts".into()),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    release_cutoff: Some("2024-06-01"),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(20, 10, 0)
+        );
+    }
+
+    #[test]
+    fn doesnt_hold_back_pinned_versions() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let metadata = create_release_metadata();
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse("10.0.0").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    metadata: Some(&metadata),
+                    release_cutoff: Some("2023-12-01"),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(10, 0, 0)
+        );
+    }
+
+    #[test]
+    fn clamps_minimum_release_age_to_epoch() {
+        let mut resolver = VersionResolver::default();
+
+        resolver.with_minimum_release_age(u32::MAX);
+
+        assert_eq!(resolver.release_cutoff.as_deref(), Some("1970-01-01"));
+
+        resolver.with_minimum_release_age(0);
+
+        assert_eq!(resolver.release_cutoff, None);
+    }
+
+    #[test]
+    fn returns_held_back_version() {
+        let mut resolver = VersionResolver::from_output(LoadVersionsOutput {
+            versions: create_versions(),
+            metadata: create_release_metadata().into_iter().collect(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            resolver
+                .resolve_with_held_back(&UnresolvedVersionSpec::default(), false)
+                .unwrap(),
+            (Some(VersionSpec::parse("10.0.0").unwrap()), None)
+        );
+
+        resolver.release_cutoff = Some("2024-03-01".into());
+
+        assert_eq!(
+            resolver
+                .resolve_with_held_back(&UnresolvedVersionSpec::default(), false)
+                .unwrap(),
+            (
+                Some(VersionSpec::parse("8.0.0").unwrap()),
+                Some(VersionSpec::parse("10.0.0").unwrap())
+            )
+        );
+        assert_eq!(
+            resolver
+                .resolve_with_held_back(&UnresolvedVersionSpec::parse(">=8").unwrap(), false)
+                .unwrap(),
+            (
+                Some(VersionSpec::parse("8.0.0").unwrap()),
+                Some(VersionSpec::parse("10.0.0").unwrap())
+            )
+        );
+        assert_eq!(
+            resolver
+                .resolve_with_held_back(&UnresolvedVersionSpec::parse("^7").unwrap(), false)
+                .unwrap(),
+            (Some(VersionSpec::parse("7.8.9").unwrap()), None)
+        );
+    }
+
+    fn create_policy() -> ProtoToolConfig {
+        let mut config = ProtoToolConfig::default();
+        config.allow = Some(UnresolvedVersionSpec::parse("<10").unwrap());
+        config
+            .exclude
+            .push(UnresolvedVersionSpec::parse("8.0.0").unwrap());
+        config
+    }
+
+    #[test]
+    fn skips_excluded_and_disallowed_versions() {
+        let versions = create_versions();
+        let aliases = create_aliases();
+        let policy = create_policy();
+
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::parse(">=4").unwrap(),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    policy: Some(&policy),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(7, 8, 9)
+        );
+        assert_eq!(
+            resolve_version_with_filter(
+                &UnresolvedVersionSpec::Alias("latest".into()),
+                &versions,
+                &aliases,
+                VersionFilter {
+                    policy: Some(&policy),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
+            .unwrap()
+            .unwrap(),
+            Version::new(7, 8, 9)
+        );
+    }
+
+    mod alias_expressions {
+        use super::*;
+
+        fn create_lts_resolver() -> VersionResolver<'static> {
+            let versions = [
+                "16.20.2", "18.19.0", "19.9.0", "20.10.0", "21.6.0", "22.1.0",
+            ]
+            .map(|version| VersionSpec::parse(version).unwrap());
+            let metadata = ["16.20.2", "18.19.0", "20.10.0"].map(|version| {
+                (
+                    VersionSpec::parse(version).unwrap(),
+                    VersionMetadata {
+                        lts: Some("codename".into()),
+                        ..Default::default()
+                    },
+                )
+            });
+
+            VersionResolver::from_output(LoadVersionsOutput {
+                aliases: [(
+                    "lts".into(),
+                    UnresolvedVersionSpec::parse("20.10.0").unwrap(),
+                )]
+                .into_iter()
+                .collect(),
+                metadata: metadata.into_iter().collect(),
+                versions: versions.into(),
+                ..Default::default()
+            })
+        }
+
+        #[test]
+        fn resolves_previous_lts_lines() {
+            let resolver = create_lts_resolver();
+
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::parse("lts-1").unwrap())
+                    .unwrap()
+                    .unwrap(),
+                Version::new(18, 19, 0)
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::parse("lts-2").unwrap())
+                    .unwrap()
+                    .unwrap(),
+                Version::new(16, 20, 2)
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::parse("lts-3").unwrap())
+                    .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn resolves_previous_major_lines() {
+            let resolver = create_lts_resolver();
+
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::parse("latest-1").unwrap())
+                    .unwrap()
+                    .unwrap(),
+                Version::new(21, 6, 0)
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::parse("latest-3").unwrap())
+                    .unwrap()
+                    .unwrap(),
+                Version::new(19, 9, 0)
+            );
+        }
+
+        #[test]
+        fn resolves_from_config_aliases() {
+            let mut resolver = create_lts_resolver();
+            let mut config = ProtoToolConfig::default();
+
+            config.aliases.insert(
+                "company-lts".into(),
+                UnresolvedVersionSpec::parse("lts-1").unwrap().into(),
+            );
+
+            resolver.with_config(&config);
+
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("company-lts".into()))
+                    .unwrap()
+                    .unwrap(),
+                Version::new(18, 19, 0)
+            );
+        }
+
+        #[test]
+        fn resolves_pins_of_other_tools() {
+            let mut resolver = create_lts_resolver();
+
+            resolver.with_pins([
+                (
+                    "deno".into(),
+                    UnresolvedVersionSpec::parse("18.2.0").unwrap(),
+                ),
+                (
+                    "bun".into(),
+                    UnresolvedVersionSpec::parse("20.10.0").unwrap(),
+                ),
+                ("go".into(), UnresolvedVersionSpec::parse("canary").unwrap()),
+            ]);
+
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/deno/major".into()))
+                    .unwrap()
+                    .unwrap(),
+                Version::new(18, 19, 0)
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/bun".into()))
+                    .unwrap()
+                    .unwrap(),
+                Version::new(20, 10, 0)
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/go/major".into()))
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/rust".into()))
+                    .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn errors_for_previous_lts_without_metadata() {
+            let resolver = VersionResolver::from_output(LoadVersionsOutput {
+                aliases: [(
+                    "lts".into(),
+                    UnresolvedVersionSpec::parse("20.10.0").unwrap(),
+                )]
+                .into_iter()
+                .collect(),
+                versions: ["18.19.0", "19.9.0", "20.10.0"]
+                    .map(|version| VersionSpec::parse(version).unwrap())
+                    .into(),
+                ..Default::default()
+            });
+
+            let error = resolver
+                .resolve(&UnresolvedVersionSpec::parse("lts-1").unwrap())
+                .unwrap_err();
+
+            assert!(
+                error
+                    .to_string()
+                    .contains("does not provide long-term support (LTS) metadata")
+            );
+        }
+
+        #[test]
+        fn finds_pinned_tools_through_aliases() {
+            let mut resolver = create_lts_resolver();
+            let mut config = ProtoToolConfig::default();
+
+            resolver.aliases.insert(
+                "company".into(),
+                UnresolvedVersionSpec::parse("pin/deno/major").unwrap(),
+            );
+            config.aliases.insert(
+                "company-previous".into(),
+                UnresolvedVersionSpec::parse("company-1").unwrap().into(),
+            );
+
+            resolver.with_config(&config);
+
+            assert_eq!(
+                resolver.get_pinned_tools(&UnresolvedVersionSpec::Alias("company-previous".into())),
+                vec![Id::raw("deno")]
+            );
+            assert_eq!(
+                resolver.get_pinned_tools(&UnresolvedVersionSpec::parse("pin/bun/minor").unwrap()),
+                vec![Id::raw("bun")]
+            );
+            assert!(
+                resolver
+                    .get_pinned_tools(&UnresolvedVersionSpec::Alias("lts".into()))
+                    .is_empty()
+            );
+        }
+
+        #[test]
+        fn doesnt_resolve_pins_of_pins() {
+            let mut resolver = create_lts_resolver();
+
+            resolver.with_pins([
+                ("a".into(), UnresolvedVersionSpec::parse("pin/b").unwrap()),
+                ("b".into(), UnresolvedVersionSpec::parse("pin/c").unwrap()),
+                ("c".into(), UnresolvedVersionSpec::parse("pin/b").unwrap()),
+            ]);
+
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/a".into()))
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                resolver
+                    .resolve(&UnresolvedVersionSpec::Alias("pin/b".into()))
+                    .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn parses_expressions() {
+            assert_eq!(
+                AliasExpression::from_spec(&UnresolvedVersionSpec::parse("lts-1").unwrap()),
+                Some(AliasExpression::Previous {
+                    alias: "lts".into(),
+                    offset: 1
+                })
+            );
+            assert_eq!(
+                AliasExpression::from_spec(
+                    &UnresolvedVersionSpec::parse("pin/node/minor").unwrap()
+                ),
+                Some(AliasExpression::Pin {
+                    tool: Id::raw("node"),
+                    part: AliasPinPart::Minor
+                })
+            );
+            assert_eq!(
+                AliasExpression::from_spec(&UnresolvedVersionSpec::parse("~lts-1.2").unwrap()),
+                None
+            );
+            assert_eq!(AliasExpression::parse("pin/node/patch"), None);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 mod build;
 mod checksum;
+mod policy;
 mod source;
 
 use crate::shapes::*;
@@ -12,6 +13,7 @@ use warpgate_api::*;
 
 pub use build::*;
 pub use checksum::*;
+pub use policy::*;
 pub use source::*;
 
 /// Enumeration of all available plugin functions that can be implemented by plugins.
@@ -165,6 +167,14 @@ pub enum PluginFunction {
     ///
     /// **Input:** [`ActivateEnvironmentInput`] | **Output:** [`ActivateEnvironmentOutput`]
     ActivateEnvironment,
+
+    /// Check whether an operation is allowed by a policy.
+    ///
+    /// Only implemented by policy plugins, and is called before a tool is
+    /// installed, pinned, or ran, allowing organizations to deny versions.
+    ///
+    /// **Input:** [`PolicyCheckInput`] | **Output:** [`PolicyCheckOutput`]
+    CheckPolicy,
 }
 
 impl PluginFunction {
@@ -194,6 +204,7 @@ impl PluginFunction {
             Self::SyncManifest => "sync_manifest",
             Self::SyncShellProfile => "sync_shell_profile",
             Self::ActivateEnvironment => "activate_environment",
+            Self::CheckPolicy => "check_policy",
         }
     }
 }
@@ -871,6 +882,49 @@ api_struct!(
     }
 );
 
+api_struct!(
+    /// Release metadata for a specific version.
+    #[serde(default)]
+    pub struct VersionMetadata {
+        /// Link to a security advisory that affects this version.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub advisory_url: Option<String>,
+
+        /// Date in which this version reaches end-of-life,
+        /// in ISO 8601 format (`YYYY-MM-DD`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub eol_date: Option<String>,
+
+        /// Codename of the long-term support line this version belongs to.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub lts: Option<String>,
+
+        /// Date in which this version was released,
+        /// in ISO 8601 format (`YYYY-MM-DD`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub release_date: Option<String>,
+
+        /// Link to the release notes or changelog for this version.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub release_notes_url: Option<String>,
+
+        /// Whether this version has been yanked or deprecated. Yanked
+        /// versions are skipped during resolution, unless pinned exactly.
+        #[serde(skip_serializing_if = "is_false")]
+        pub yanked: bool,
+    }
+);
+
+impl VersionMetadata {
+    /// Return true if this version has reached end-of-life
+    /// on or before the provided ISO 8601 date (`YYYY-MM-DD`).
+    pub fn is_eol_at(&self, date: &str) -> bool {
+        self.eol_date
+            .as_ref()
+            .is_some_and(|eol| eol.get(0..10).unwrap_or(eol) <= date)
+    }
+}
+
 api_struct!(
     /// Output returned by the `load_versions` function.
     #[serde(default)]
@@ -887,6 +941,10 @@ api_struct!(
         #[serde(skip_serializing_if = "FxHashMap::is_empty")]
         pub aliases: FxHashMap<String, UnresolvedVersionSpec>,
 
+        /// Mapping of versions to their release metadata (dates, EOL, LTS, etc).
+        #[serde(skip_serializing_if = "FxHashMap::is_empty")]
+        pub metadata: FxHashMap<VersionSpec, VersionMetadata>,
+
         /// List of available production versions to install.
         #[serde(skip_serializing_if = "Vec::is_empty")]
         pub versions: Vec<VersionSpec>,
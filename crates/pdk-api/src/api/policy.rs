@@ -0,0 +1,99 @@
+use super::{Checksum, PluginUnresolvedContext};
+use std::fmt;
+use version_spec::{UnresolvedVersionSpec, VersionSpec};
+use warpgate_api::{Id, api_struct, api_unit_enum};
+
+api_unit_enum!(
+    /// Operations that are checked against policy plugins.
+    pub enum PolicyOperation {
+        #[default]
+        Install,
+        Pin,
+    }
+);
+
+impl fmt::Display for PolicyOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Install => write!(f, "install"),
+            Self::Pin => write!(f, "pin"),
+        }
+    }
+}
+
+api_struct!(
+    /// A record from the lockfile for the version being checked.
+    #[serde(default)]
+    pub struct PolicyLockRecord {
+        /// Checksum of the downloaded archive.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub checksum: Option<Checksum>,
+
+        /// URL the archive was downloaded from.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub source: Option<String>,
+
+        /// The resolved version.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub version: Option<VersionSpec>,
+    }
+);
+
+api_struct!(
+    /// Input passed to the `check_policy` function.
+    pub struct PolicyCheckInput {
+        /// Current policy context.
+        pub context: PluginUnresolvedContext,
+
+        /// The operation being performed.
+        pub operation: PolicyOperation,
+
+        /// Identifier of the tool being checked.
+        pub id: Id,
+
+        /// Identifier of the backend, if the tool is using one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub backend: Option<Id>,
+
+        /// The version specification that was requested.
+        pub spec: UnresolvedVersionSpec,
+
+        /// The resolved version, if it has been resolved.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub version: Option<VersionSpec>,
+
+        /// The matching lockfile record, if one exists.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub record: Option<PolicyLockRecord>,
+    }
+);
+
+api_struct!(
+    /// Output returned by the `check_policy` function.
+    pub struct PolicyCheckOutput {
+        /// Whether the operation is allowed.
+        pub allowed: bool,
+
+        /// A message explaining why the operation was denied.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub message: Option<String>,
+    }
+);
+
+impl PolicyCheckOutput {
+    /// Allow the operation.
+    pub fn allow() -> Self {
+        Self {
+            allowed: true,
+            message: None,
+        }
+    }
+
+    /// Deny the operation with the provided message.
+    pub fn deny(message: impl Into<String>) -> Self {
+        Self {
+            allowed: false,
+            message: Some(message.into()),
+        }
+    }
+}
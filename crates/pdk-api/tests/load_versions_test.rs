@@ -1,4 +1,4 @@
-use proto_pdk_api::LoadVersionsOutput;
+use proto_pdk_api::{LoadVersionsOutput, VersionMetadata};
 
 mod load_versions_output {
     use super::*;
@@ -38,6 +38,24 @@ mod load_versions_output {
         assert_eq!(output.latest.unwrap().to_string(), "1.0.0");
     }
 
+    #[test]
+    fn latest_includes_scheme_releases() {
+        let output = create_output(&["5.2.0.RELEASE", "5.3.0.RELEASE"]);
+
+        assert_eq!(output.latest.unwrap().to_string(), "5.3.0.RELEASE");
+
+        let output = create_output(&["1.0.0", "1.0.post1"]);
+
+        assert_eq!(output.latest.unwrap().to_string(), "1.0.post1");
+    }
+
+    #[test]
+    fn latest_skips_scheme_prereleases() {
+        let output = create_output(&["3.12.0", "3.13.0rc1", "3.13.0.dev1", "3.12.0+local"]);
+
+        assert_eq!(output.latest.unwrap().to_string(), "3.12.0");
+    }
+
     #[test]
     fn latest_falls_back_to_zero() {
         let output = create_output(&[]);
@@ -45,3 +63,42 @@ mod load_versions_output {
         assert_eq!(output.latest.unwrap().to_string(), "0.0.0");
     }
 }
+
+mod version_metadata {
+    use super::*;
+
+    #[test]
+    fn checks_eol() {
+        let meta = VersionMetadata {
+            eol_date: Some("2025-04-30".into()),
+            ..Default::default()
+        };
+
+        assert!(!meta.is_eol_at("2025-04-29"));
+        assert!(meta.is_eol_at("2025-04-30"));
+        assert!(meta.is_eol_at("2026-01-01"));
+        assert!(!VersionMetadata::default().is_eol_at("2026-01-01"));
+    }
+
+    #[test]
+    fn serializes_with_versions_as_keys() {
+        let mut output = LoadVersionsOutput::from(vec!["20.0.0".into()]).unwrap();
+        output.metadata.insert(
+            output.versions[0].clone(),
+            VersionMetadata {
+                lts: Some("iron".into()),
+                yanked: true,
+                ..Default::default()
+            },
+        );
+
+        let json = serde_json::to_string(&output.metadata).unwrap();
+
+        assert_eq!(json, r#"{"20.0.0":{"lts":"iron","yanked":true}}"#);
+        assert_eq!(
+            serde_json::from_str::<LoadVersionsOutput>(&serde_json::to_string(&output).unwrap())
+                .unwrap(),
+            output
+        );
+    }
+}
@@ -9,9 +9,70 @@ pub use unix::*;
 pub use windows::*;
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
-pub const SHIM_VERSION: u8 = 20;
+pub const SHIM_VERSION: u8 = 21;
+
+/// Return the file name of the resolution cache for the provided working
+/// directory. Both proto and the shim must agree on this name, so we use
+/// FNV-1a instead of the std hasher, which is not stable across releases.
+pub fn get_cache_file_name(dir: &Path) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for byte in dir.as_os_str().as_encoded_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    format!("{hash:016x}.json")
+}
+
+/// Return the last modified time of the provided file in milliseconds,
+/// or `None` if the file does not exist.
+pub fn get_modified_time(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+
+    modified
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_millis() as u64)
+}
+
+/// Return a description of the first input of a shim cache entry that has
+/// changed since the entry was created, or `None` if the entry is fresh.
+/// Inputs are the executable (which must exist if absolute), tracked files
+/// and their modified times, and tracked environment variables and their
+/// values, where `None` means the file did not exist or the variable was
+/// not set. Both proto and the shim must agree on freshness.
+pub fn find_changed_cache_input<'a>(
+    exe: &Path,
+    files: impl IntoIterator<Item = (&'a Path, Option<u64>)>,
+    vars: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+) -> Option<String> {
+    if exe.is_absolute() && !exe.exists() {
+        return Some(format!("executable {exe:?} no longer exists"));
+    }
+
+    for (path, time) in files {
+        if get_modified_time(path) != time {
+            return Some(format!("{path:?} has changed"));
+        }
+    }
+
+    for (name, value) in vars {
+        if env::var(name)
+            .ok()
+            .filter(|value| !value.is_empty())
+            .as_deref()
+            != value
+        {
+            return Some(format!("`{name}` has changed"));
+        }
+    }
+
+    None
+}
 
 pub fn locate_proto_exe(exe_name: &str) -> Option<PathBuf> {
     let exe_name = get_exe_file_name(exe_name);
@@ -170,6 +231,19 @@ mod tests {
         path
     }
 
+    #[test]
+    fn cache_file_name_is_stable() {
+        assert_eq!(get_cache_file_name(Path::new("")), "cbf29ce484222325.json");
+        assert_eq!(
+            get_cache_file_name(Path::new("/a")),
+            get_cache_file_name(Path::new("/a"))
+        );
+        assert_ne!(
+            get_cache_file_name(Path::new("/a")),
+            get_cache_file_name(Path::new("/b"))
+        );
+    }
+
     #[test]
     fn ignores_directory_candidates_when_locating_proto_exe() {
         let _lock = env_lock().lock().unwrap();
@@ -43,6 +43,29 @@ impl System {
         }
     }
 
+    /// Create a new instance that targets the provided package manager.
+    /// The operating system is derived from the package manager, so that
+    /// commands can be generated for another system (for example, a Docker image).
+    pub fn for_manager_target(manager: SystemPackageManager) -> Self {
+        let os = match manager {
+            SystemPackageManager::Pkg => SystemOS::FreeBSD,
+            SystemPackageManager::Pkgin => SystemOS::NetBSD,
+            SystemPackageManager::Apk
+            | SystemPackageManager::Apt
+            | SystemPackageManager::Dnf
+            | SystemPackageManager::Pacman
+            | SystemPackageManager::Yum => SystemOS::Linux,
+            SystemPackageManager::Brew => SystemOS::MacOS,
+            SystemPackageManager::Choco | SystemPackageManager::Scoop => SystemOS::Windows,
+            SystemPackageManager::All => SystemOS::from_env(),
+        };
+
+        Self {
+            os,
+            ..Self::with_manager(manager)
+        }
+    }
+
     /// Return the command and arguments to "install a package" for the
     /// current package manager. Will replace `$` in an argument with the
     /// dependency name, derived from [`DependencyName`].
@@ -11,6 +11,30 @@ fn many_dep() -> DependencyConfig {
 mod pm {
     use super::*;
 
+    #[test]
+    fn keeps_host_os_with_manager() {
+        assert_eq!(
+            System::with_manager(SystemPackageManager::Brew).os,
+            SystemOS::from_env()
+        );
+    }
+
+    #[test]
+    fn derives_os_from_manager_target() {
+        assert_eq!(
+            System::for_manager_target(SystemPackageManager::Apt).os,
+            SystemOS::Linux
+        );
+        assert_eq!(
+            System::for_manager_target(SystemPackageManager::Brew).os,
+            SystemOS::MacOS
+        );
+        assert_eq!(
+            System::for_manager_target(SystemPackageManager::Scoop).os,
+            SystemOS::Windows
+        );
+    }
+
     mod apk {
         use super::*;
 
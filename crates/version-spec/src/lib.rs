@@ -1,15 +1,19 @@
 mod resolved_spec;
 mod spec_error;
 mod syntax;
+mod syntax_interval;
 mod syntax_parser;
+mod syntax_scheme;
 mod syntax_traits;
 mod unresolved_spec;
 
 pub use resolved_spec::*;
 pub use spec_error::*;
 pub use syntax::*;
+pub use syntax_interval::*;
 #[doc(hidden)]
 pub use syntax_parser::*;
+pub use syntax_scheme::VersionScheme;
 pub use syntax_traits::*;
 pub use unresolved_spec::*;
 
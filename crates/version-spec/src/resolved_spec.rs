@@ -27,7 +27,7 @@ impl VersionSpec {
     ///
     /// - If the value "canary", map as `Canary` variant.
     /// - If an alpha-numeric value that starts with a character, map as `Alias`.
-    /// - Else parse with [`Version`], and map as `Version`.
+    /// - Else parse with [`Version::parse_with_schemes`], and map as `Version`.
     pub fn parse<T: AsRef<str>>(value: T) -> Result<Self, SpecError> {
         Self::from_str(value.as_ref())
     }
@@ -118,8 +118,9 @@ impl FromStr for VersionSpec {
         }
 
         // A version takes priority, with an alias being the residual:
-        // whatever the grammar does not accept as a version
-        match Version::parse(value) {
+        // whatever the grammar does not accept as a version. Plugins return
+        // ecosystem specific versions as strings, so detect those as well
+        match Version::parse_with_schemes(value) {
             Ok(version) => Ok(Self::Version(version)),
             Err(error) => match parse_alias(value) {
                 Ok(alias) => Ok(Self::Alias(alias)),
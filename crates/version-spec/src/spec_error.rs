@@ -19,4 +19,10 @@ pub enum SpecError {
         #[source]
         error: Box<ParseError>,
     },
+
+    #[error("Failed to parse {value} as a {kind:?} version.")]
+    FailedSchemeParse {
+        kind: crate::syntax::VersionKind,
+        value: String,
+    },
 }
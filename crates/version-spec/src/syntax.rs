@@ -1,6 +1,10 @@
 use crate::is_calver_like;
 use crate::spec_error::SpecError;
 use crate::syntax_parser::*;
+use crate::syntax_scheme::{
+    VersionScheme, compare_scheme_line, compare_scheme_release, compare_schemes, detect_scheme,
+    parse_maven, parse_pep440,
+};
 use crate::syntax_traits::{FormatOptions, FormatsVersion};
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
@@ -8,7 +12,8 @@ use std::cmp::Ordering;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
-/// The kind of version, either calendar or semantic.
+/// The kind of version, either calendar, semantic, or an ecosystem
+/// specific scheme.
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[non_exhaustive]
 pub enum VersionKind {
@@ -18,6 +23,22 @@ pub enum VersionKind {
     /// A semantic version, typically in the form of `MAJOR.MINOR.PATCH`.
     #[default]
     Semantic,
+
+    /// A [PEP 440](https://peps.python.org/pep-0440/) version, as used by Python,
+    /// for example `3.12.0rc1`, `1.0.post1`, or `1!2.0`.
+    Pep440,
+
+    /// A Maven version, as used by the Java ecosystem, for example
+    /// `1.0-SNAPSHOT`, `5.3.0.RELEASE`, or `2.0-M1`.
+    Maven,
+}
+
+impl VersionKind {
+    /// Return true if the kind is an ecosystem specific scheme,
+    /// in which the original string is preserved.
+    pub fn is_scheme(&self) -> bool {
+        matches!(self, Self::Pep440 | Self::Maven)
+    }
 }
 
 /// A version in either calendar or semantic format, with support for
@@ -46,9 +67,13 @@ pub struct Version {
     /// in `1.2.3-alpha.1`. Does not include the leading `-`.
     pub prerelease: Option<CompactString>,
 
-    /// Optional build metadata, for example the "build.5" in `1.2.3+build.5`.
-    /// Does not include the leading `+`.
+    /// Optional build metadata, for example the "build.5" in `1.2.3+build.5`,
+    /// or the local version label for PEP 440. Does not include the leading `+`.
     pub build: Option<CompactString>,
+
+    /// The original string and parsed parts of an ecosystem specific scheme,
+    /// like PEP 440 or Maven, as they have no canonical semantic form.
+    pub scheme: Option<Box<VersionScheme>>,
 }
 
 impl Version {
@@ -83,7 +108,7 @@ impl Version {
         }
     }
 
-    /// Parses the provided value into a version.
+    /// Parses the provided value into a calendar or semantic version.
     pub fn parse<T: AsRef<str>>(value: T) -> Result<Self, SpecError> {
         let value = value.as_ref();
 
@@ -99,6 +124,35 @@ impl Version {
         })
     }
 
+    /// Parses the provided value into a version like [`Version::parse`], but
+    /// falls back to PEP 440 and Maven versions, which are only detected when
+    /// syntax that is specific to them is used.
+    pub fn parse_with_schemes<T: AsRef<str>>(value: T) -> Result<Self, SpecError> {
+        let value = value.as_ref();
+
+        Self::parse(value).or_else(|error| detect_scheme(value).ok_or(error))
+    }
+
+    /// Parses the provided value into a PEP 440 version.
+    pub fn parse_pep440<T: AsRef<str>>(value: T) -> Result<Self, SpecError> {
+        let value = value.as_ref();
+
+        parse_pep440(value).ok_or_else(|| SpecError::FailedSchemeParse {
+            kind: VersionKind::Pep440,
+            value: value.into(),
+        })
+    }
+
+    /// Parses the provided value into a Maven version.
+    pub fn parse_maven<T: AsRef<str>>(value: T) -> Result<Self, SpecError> {
+        let value = value.as_ref();
+
+        parse_maven(value).ok_or_else(|| SpecError::FailedSchemeParse {
+            kind: VersionKind::Maven,
+            value: value.into(),
+        })
+    }
+
     /// Return true if the version is a calendar version.
     pub fn is_calendar(&self) -> bool {
         self.kind == VersionKind::Calendar
@@ -109,6 +163,17 @@ impl Version {
         self.kind == VersionKind::Semantic
     }
 
+    /// Return true if the version is an ecosystem specific scheme,
+    /// like PEP 440 or Maven.
+    pub fn is_scheme(&self) -> bool {
+        self.kind.is_scheme()
+    }
+
+    /// Return the original string if the version is an ecosystem specific scheme.
+    pub fn get_raw(&self) -> Option<&str> {
+        self.scheme.as_ref().map(|scheme| scheme.as_str())
+    }
+
     /// Converts this version into a requirement with the provided operator.
     pub fn to_requirement(&self, op: Op) -> Requirement {
         Requirement {
@@ -137,7 +202,7 @@ impl Display for Version {
                     include_patch: self.patch > 0,
                     ..FormatOptions::calendar()
                 },
-                VersionKind::Semantic => FormatOptions::semantic(),
+                _ => FormatOptions::new(self.kind),
             })
         )
     }
@@ -145,6 +210,10 @@ impl Display for Version {
 
 impl Ord for Version {
     fn cmp(&self, other: &Self) -> Ordering {
+        if self.is_scheme() || other.is_scheme() {
+            return compare_schemes(self, other, compare_prerelease, compare_build);
+        }
+
         self.kind
             .cmp(&other.kind)
             .then_with(|| self.scope.cmp(&other.scope))
@@ -316,6 +385,10 @@ impl Requirement {
     /// parts of this requirement, including the pre-release. Omitted
     /// parts match any value.
     pub fn matches_exact(&self, version: &Version) -> bool {
+        if version.is_scheme() {
+            return self.matches_scheme(Op::Exact, version);
+        }
+
         if let Some(major) = self.major {
             if version.major != major {
                 return false;
@@ -341,6 +414,10 @@ impl Requirement {
     /// requirement. A partial requirement only matches versions beyond
     /// the defined parts, for example `>1` does not match `1.5.0`.
     pub fn matches_greater(&self, version: &Version) -> bool {
+        if version.is_scheme() {
+            return self.matches_scheme(Op::Greater, version);
+        }
+
         let Some(major) = self.major else {
             return false;
         };
@@ -372,6 +449,10 @@ impl Requirement {
     /// Returns true if the provided version is less (`<`) than this requirement.
     /// A partial requirement only matches versions below the defined parts.
     pub fn matches_less(&self, version: &Version) -> bool {
+        if version.is_scheme() {
+            return self.matches_scheme(Op::Less, version);
+        }
+
         let Some(major) = self.major else {
             return false;
         };
@@ -403,6 +484,10 @@ impl Requirement {
     /// Returns true for a patch-level (`~`) match: the defined major and
     /// minor parts must be equal, while the remaining parts may drift higher.
     pub fn matches_tilde(&self, version: &Version) -> bool {
+        if version.is_scheme() {
+            return self.matches_scheme(Op::Tilde, version);
+        }
+
         let Some(major) = self.major else {
             return true;
         };
@@ -431,6 +516,10 @@ impl Requirement {
     /// the next major version, or the next minor or patch version when
     /// the major or minor is 0.
     pub fn matches_caret(&self, version: &Version) -> bool {
+        if version.is_scheme() {
+            return self.matches_scheme(Op::Caret, version);
+        }
+
         let Some(major) = self.major else {
             return true;
         };
@@ -471,6 +560,75 @@ impl Requirement {
             != Ordering::Less
     }
 
+    // Ecosystem schemes have parts that a requirement cannot define, like
+    // epochs and post-releases, so compare against the requirement as a
+    // version instead, so that operators agree with the ordering of versions
+    fn matches_scheme(&self, op: Op, version: &Version) -> bool {
+        let line = [self.major, self.minor, self.patch]
+            .into_iter()
+            .map_while(|part| part)
+            .collect::<Vec<_>>();
+        let is_full = line.len() == 3;
+        let compare_line =
+            |size: usize| compare_scheme_line(version, &line[0..size.min(line.len())]);
+        let compare_bound = || {
+            compare_scheme_release(
+                version,
+                &Version {
+                    kind: self.kind,
+                    major: self.major.unwrap_or_default(),
+                    minor: self.minor.unwrap_or_default(),
+                    patch: self.patch.unwrap_or_default(),
+                    prerelease: self.prerelease.clone(),
+                    ..Default::default()
+                },
+                compare_prerelease,
+            )
+        };
+
+        match op {
+            Op::Exact | Op::Wildcard => {
+                if is_full {
+                    compare_bound() == Ordering::Equal
+                } else {
+                    compare_line(3) == Ordering::Equal && self.prerelease == version.prerelease
+                }
+            }
+            Op::Greater => {
+                !line.is_empty()
+                    && if is_full {
+                        compare_bound() == Ordering::Greater
+                    } else {
+                        compare_line(3) == Ordering::Greater
+                    }
+            }
+            Op::Less => {
+                !line.is_empty()
+                    && if is_full {
+                        compare_bound() == Ordering::Less
+                    } else {
+                        compare_line(3) == Ordering::Less
+                    }
+            }
+            Op::GreaterEq => {
+                self.matches_scheme(Op::Exact, version) || self.matches_scheme(Op::Greater, version)
+            }
+            Op::LessEq => {
+                self.matches_scheme(Op::Exact, version) || self.matches_scheme(Op::Less, version)
+            }
+            Op::Tilde => compare_line(2) == Ordering::Equal && compare_bound() != Ordering::Less,
+            Op::Caret => {
+                let size = match (self.major, self.minor) {
+                    (Some(major), _) if major > 0 => 1,
+                    (_, Some(minor)) if minor > 0 => 2,
+                    _ => 3,
+                };
+
+                compare_line(size) == Ordering::Equal && compare_bound() != Ordering::Less
+            }
+        }
+    }
+
     /// Returns true if this requirement has a pre-release on the same
     /// version numbers as the provided version, allowing a pre-release
     /// version to be matched.
@@ -487,10 +645,7 @@ impl Display for Requirement {
         write!(
             f,
             "{}",
-            self.to_formatted_string(&match self.kind {
-                VersionKind::Calendar => FormatOptions::calendar(),
-                VersionKind::Semantic => FormatOptions::semantic(),
-            })
+            self.to_formatted_string(&FormatOptions::new(self.kind))
         )
     }
 }
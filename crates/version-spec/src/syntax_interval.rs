@@ -0,0 +1,429 @@
+use crate::syntax::{Clause, Op, Range, Requirement, Version, VersionKind};
+use compact_str::CompactString;
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+/// A contiguous interval of versions, with optional lower and upper bounds.
+/// Intervals are used for computing the intersection of requirements and
+/// ranges, and do not take pre-release opt-in into account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionInterval {
+    /// An optional scope that all versions in the interval must have.
+    pub scope: Option<CompactString>,
+
+    /// The lower bound of the interval.
+    pub lower: Bound<Version>,
+
+    /// The upper bound of the interval.
+    pub upper: Bound<Version>,
+}
+
+impl VersionInterval {
+    /// Creates an interval that contains all versions.
+    pub fn unbounded() -> Self {
+        Self {
+            scope: None,
+            lower: Bound::Unbounded,
+            upper: Bound::Unbounded,
+        }
+    }
+
+    /// Return true if the interval contains no versions, for example
+    /// when the lower bound is greater than the upper bound.
+    pub fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Bound::Included(lower), Bound::Included(upper)) => lower > upper,
+            (Bound::Included(lower), Bound::Excluded(upper))
+            | (Bound::Excluded(lower), Bound::Included(upper))
+            | (Bound::Excluded(lower), Bound::Excluded(upper)) => lower >= upper,
+            _ => false,
+        }
+    }
+
+    /// Return true if the interval contains the provided version.
+    pub fn contains(&self, version: &Version) -> bool {
+        if self.scope.is_some() && self.scope != version.scope {
+            return false;
+        }
+
+        let above_lower = match &self.lower {
+            Bound::Included(lower) => version >= lower,
+            Bound::Excluded(lower) => version > lower,
+            Bound::Unbounded => true,
+        };
+
+        let below_upper = match &self.upper {
+            Bound::Included(upper) => version <= upper,
+            Bound::Excluded(upper) => version < upper,
+            Bound::Unbounded => true,
+        };
+
+        above_lower && below_upper
+    }
+
+    /// Returns the intersection of this and the provided interval,
+    /// or `None` if they do not overlap.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let scope = match (&self.scope, &other.scope) {
+            (Some(lhs), Some(rhs)) if lhs != rhs => return None,
+            (lhs, rhs) => lhs.clone().or_else(|| rhs.clone()),
+        };
+
+        let lower = if compare_lower(&self.lower, &other.lower).is_ge() {
+            self.lower.clone()
+        } else {
+            other.lower.clone()
+        };
+
+        let upper = if compare_upper(&self.upper, &other.upper).is_le() {
+            self.upper.clone()
+        } else {
+            other.upper.clone()
+        };
+
+        let interval = Self {
+            scope,
+            lower,
+            upper,
+        };
+
+        if interval.is_empty() {
+            None
+        } else {
+            Some(interval)
+        }
+    }
+
+    /// Converts the interval into an equivalent clause.
+    pub fn to_clause(&self) -> Clause {
+        let with_scope = |mut req: Requirement| {
+            req.scope = self.scope.clone();
+            req
+        };
+
+        let lower = match &self.lower {
+            Bound::Included(lower) => {
+                if matches!(&self.upper, Bound::Included(upper) if upper == lower) {
+                    return Clause::Only(with_scope(lower.to_requirement(Op::Exact)));
+                }
+
+                Some(with_scope(lower.to_requirement(Op::GreaterEq)))
+            }
+            Bound::Excluded(lower) => Some(with_scope(lower.to_requirement(Op::Greater))),
+            Bound::Unbounded => None,
+        };
+
+        let upper = match &self.upper {
+            Bound::Included(upper) => Some(with_scope(upper.to_requirement(Op::LessEq))),
+            Bound::Excluded(upper) => Some(with_scope(upper.to_requirement(Op::Less))),
+            Bound::Unbounded => None,
+        };
+
+        match (lower, upper) {
+            (Some(lower), Some(upper)) => Clause::All(vec![lower, upper]),
+            (Some(req), None) | (None, Some(req)) => Clause::Only(req),
+            (None, None) => Clause::Only(with_scope(Requirement {
+                op: Op::Wildcard,
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+// An unbounded lower bound is the lowest, and an excluded
+// bound is higher than an included bound of the same version
+fn compare_lower(lhs: &Bound<Version>, rhs: &Bound<Version>) -> Ordering {
+    match (lhs, rhs) {
+        (Bound::Unbounded, Bound::Unbounded) => Ordering::Equal,
+        (Bound::Unbounded, _) => Ordering::Less,
+        (_, Bound::Unbounded) => Ordering::Greater,
+        (Bound::Included(l), Bound::Included(r)) | (Bound::Excluded(l), Bound::Excluded(r)) => {
+            l.cmp(r)
+        }
+        (Bound::Included(l), Bound::Excluded(r)) => l.cmp(r).then(Ordering::Less),
+        (Bound::Excluded(l), Bound::Included(r)) => l.cmp(r).then(Ordering::Greater),
+    }
+}
+
+// An unbounded upper bound is the highest, and an excluded
+// bound is lower than an included bound of the same version
+fn compare_upper(lhs: &Bound<Version>, rhs: &Bound<Version>) -> Ordering {
+    match (lhs, rhs) {
+        (Bound::Unbounded, Bound::Unbounded) => Ordering::Equal,
+        (Bound::Unbounded, _) => Ordering::Greater,
+        (_, Bound::Unbounded) => Ordering::Less,
+        (Bound::Included(l), Bound::Included(r)) | (Bound::Excluded(l), Bound::Excluded(r)) => {
+            l.cmp(r)
+        }
+        (Bound::Included(l), Bound::Excluded(r)) => l.cmp(r).then(Ordering::Greater),
+        (Bound::Excluded(l), Bound::Included(r)) => l.cmp(r).then(Ordering::Less),
+    }
+}
+
+// Two sorted intervals can be merged when the first reaches the second
+fn is_connected(lhs: &VersionInterval, rhs: &VersionInterval) -> bool {
+    if lhs.scope != rhs.scope {
+        return false;
+    }
+
+    match (&lhs.upper, &rhs.lower) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => true,
+        (Bound::Excluded(upper), Bound::Excluded(lower)) => upper > lower,
+        (Bound::Included(upper), Bound::Included(lower))
+        | (Bound::Included(upper), Bound::Excluded(lower))
+        | (Bound::Excluded(upper), Bound::Included(lower)) => upper >= lower,
+    }
+}
+
+/// Sorts and merges overlapping intervals into their canonical form.
+fn merge_intervals(mut intervals: Vec<VersionInterval>) -> Vec<VersionInterval> {
+    intervals.retain(|interval| !interval.is_empty());
+    intervals.sort_by(|lhs, rhs| {
+        lhs.scope
+            .cmp(&rhs.scope)
+            .then_with(|| compare_lower(&lhs.lower, &rhs.lower))
+    });
+
+    let mut merged: Vec<VersionInterval> = vec![];
+
+    for interval in intervals {
+        if let Some(last) = merged.last_mut()
+            && is_connected(last, &interval)
+        {
+            if compare_upper(&interval.upper, &last.upper).is_gt() {
+                last.upper = interval.upper;
+            }
+
+            continue;
+        }
+
+        merged.push(interval);
+    }
+
+    merged
+}
+
+fn create_bound(kind: VersionKind, major: u32, minor: u32, patch: u32) -> Version {
+    Version {
+        kind,
+        major,
+        minor,
+        patch,
+        ..Default::default()
+    }
+}
+
+// The first version after all versions with the same major
+fn bump_major(kind: VersionKind, major: u32) -> Version {
+    if kind == VersionKind::Calendar {
+        create_bound(kind, major + 1, 1, 0)
+    } else {
+        create_bound(kind, major + 1, 0, 0)
+    }
+}
+
+// The first version after all versions with the same major and minor
+fn bump_minor(kind: VersionKind, major: u32, minor: u32) -> Version {
+    if kind == VersionKind::Calendar && minor >= 12 {
+        bump_major(kind, major)
+    } else {
+        create_bound(kind, major, minor + 1, 0)
+    }
+}
+
+impl Requirement {
+    /// Converts the requirement into an interval of matching versions.
+    pub fn to_interval(&self) -> VersionInterval {
+        let kind = self.kind;
+        let mut interval = VersionInterval {
+            scope: self.scope.clone(),
+            ..VersionInterval::unbounded()
+        };
+
+        let Some(major) = self.major else {
+            return interval;
+        };
+
+        // The lowest version that matches all defined parts
+        let floor = Version {
+            prerelease: self.prerelease.clone(),
+            ..create_bound(
+                kind,
+                major,
+                self.minor.unwrap_or_default(),
+                self.patch.unwrap_or_default(),
+            )
+        };
+
+        // The first version after all versions that match the defined parts
+        let ceiling = match (self.minor, self.patch) {
+            (None, _) => Some(bump_major(kind, major)),
+            (Some(minor), None) => Some(bump_minor(kind, major, minor)),
+            _ => None,
+        };
+
+        let (lower, upper) = match self.op {
+            Op::Exact | Op::Wildcard => match ceiling {
+                Some(ceiling) => (Bound::Included(floor), Bound::Excluded(ceiling)),
+                None => (Bound::Included(floor.clone()), Bound::Included(floor)),
+            },
+            Op::Greater => match ceiling {
+                Some(ceiling) => (Bound::Included(ceiling), Bound::Unbounded),
+                None => (Bound::Excluded(floor), Bound::Unbounded),
+            },
+            Op::GreaterEq => (Bound::Included(floor), Bound::Unbounded),
+            Op::Less => (Bound::Unbounded, Bound::Excluded(floor)),
+            Op::LessEq => match ceiling {
+                Some(ceiling) => (Bound::Unbounded, Bound::Excluded(ceiling)),
+                None => (Bound::Unbounded, Bound::Included(floor)),
+            },
+            Op::Tilde => {
+                let ceiling = match self.minor {
+                    Some(minor) => bump_minor(kind, major, minor),
+                    None => bump_major(kind, major),
+                };
+
+                (Bound::Included(floor), Bound::Excluded(ceiling))
+            }
+            Op::Caret => {
+                let minor = self.minor.unwrap_or_default();
+                let ceiling = if major > 0 || self.minor.is_none() {
+                    bump_major(kind, major)
+                } else if minor > 0 || self.patch.is_none() {
+                    bump_minor(kind, major, minor)
+                } else {
+                    create_bound(kind, major, minor, self.patch.unwrap_or_default() + 1)
+                };
+
+                (Bound::Included(floor), Bound::Excluded(ceiling))
+            }
+        };
+
+        interval.lower = lower;
+        interval.upper = upper;
+        interval
+    }
+
+    /// Returns the intersection of this and the provided requirement
+    /// as a range, or `None` if they cannot be satisfied together.
+    pub fn intersect(&self, other: &Requirement) -> Option<Range> {
+        self.to_interval()
+            .intersect(&other.to_interval())
+            .map(|interval| Range::from_intervals(vec![interval]))
+    }
+}
+
+impl Clause {
+    /// Converts the clause into an interval of matching versions,
+    /// or `None` if the clause cannot be satisfied.
+    pub fn to_interval(&self) -> Option<VersionInterval> {
+        match self {
+            Clause::All(reqs) => reqs
+                .iter()
+                .try_fold(VersionInterval::unbounded(), |interval, req| {
+                    interval.intersect(&req.to_interval())
+                }),
+            Clause::Between(lower, upper) => {
+                let interval = VersionInterval {
+                    scope: lower.scope.clone(),
+                    lower: Bound::Included(Version {
+                        scope: None,
+                        ..lower.as_ref().clone()
+                    }),
+                    upper: Bound::Included(Version {
+                        scope: None,
+                        ..upper.as_ref().clone()
+                    }),
+                };
+
+                if interval.is_empty() {
+                    None
+                } else {
+                    Some(interval)
+                }
+            }
+            Clause::Only(req) => Some(req.to_interval()),
+        }
+    }
+}
+
+impl Range {
+    /// Creates a range from the provided intervals, in their canonical form.
+    /// An empty list of intervals results in a wildcard range.
+    pub fn from_intervals(intervals: Vec<VersionInterval>) -> Self {
+        let intervals = merge_intervals(intervals);
+
+        // A wildcard is represented by an empty list of clauses
+        if intervals.len() == 1 && intervals[0] == VersionInterval::unbounded() {
+            return Self::default();
+        }
+
+        Self {
+            clauses: intervals.iter().map(VersionInterval::to_clause).collect(),
+        }
+    }
+
+    /// Converts the range into a sorted list of non-overlapping intervals.
+    /// An empty list means that the range cannot be satisfied.
+    pub fn to_intervals(&self) -> Vec<VersionInterval> {
+        if self.clauses.is_empty() {
+            return vec![VersionInterval::unbounded()];
+        }
+
+        merge_intervals(
+            self.clauses
+                .iter()
+                .filter_map(Clause::to_interval)
+                .collect(),
+        )
+    }
+
+    /// Returns the intersection of this and the provided range, or `None`
+    /// if the ranges cannot be satisfied together. The result is simplified
+    /// into its canonical form, for example `^20` and `>=20.10` results in
+    /// `>=20.10.0 && <21.0.0`.
+    pub fn intersect(&self, other: &Range) -> Option<Range> {
+        let rhs = other.to_intervals();
+        let mut intervals = vec![];
+
+        for lhs in self.to_intervals() {
+            for rhs in &rhs {
+                if let Some(interval) = lhs.intersect(rhs) {
+                    intervals.push(interval);
+                }
+            }
+        }
+
+        if intervals.is_empty() {
+            None
+        } else {
+            Some(Range::from_intervals(intervals))
+        }
+    }
+
+    /// Return true if at least one version can satisfy the range.
+    pub fn is_satisfiable(&self) -> bool {
+        !self.to_intervals().is_empty()
+    }
+
+    /// Simplifies the range into its canonical form, by merging overlapping
+    /// clauses, and removing clauses that cannot be satisfied. Returns `None`
+    /// if the range cannot be satisfied at all.
+    pub fn simplify(&self) -> Option<Range> {
+        let intervals = self.to_intervals();
+
+        if intervals.is_empty() {
+            None
+        } else {
+            Some(Range::from_intervals(intervals))
+        }
+    }
+}
+
+impl From<Requirement> for Range {
+    fn from(req: Requirement) -> Self {
+        Self {
+            clauses: vec![Clause::Only(req)],
+        }
+    }
+}
@@ -0,0 +1,500 @@
+use crate::syntax::{Version, VersionKind};
+use compact_str::{CompactString, format_compact};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+/// The release phase of a version, in order of precedence.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum Phase {
+    /// A development release without a pre-release, for example `1.0.dev1`.
+    Dev,
+    /// A pre-release, for example `1.0rc1` or `1.0-SNAPSHOT`.
+    Pre,
+    /// A final release.
+    #[default]
+    Release,
+    /// A post-release, for example `1.0.post1` or `1.0-sp1`.
+    Post,
+}
+
+/// The original string of an ecosystem specific version, with the parts that are
+/// required for ordering, which are parsed once when the version is created.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VersionScheme {
+    raw: CompactString,
+    parts: SchemeParts,
+}
+
+impl VersionScheme {
+    /// Return the original string of the version.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// The components of an ecosystem version that are required for
+/// ordering, but that do not map onto the fields of [`Version`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub(crate) struct SchemeParts {
+    pub epoch: u32,
+    pub release: Vec<u32>,
+    pub phase: Phase,
+    pub pre: Option<CompactString>,
+    pub post: Option<u32>,
+    pub dev: Option<u32>,
+    pub local: Option<CompactString>,
+}
+
+impl SchemeParts {
+    fn from_version(version: &Version) -> Self {
+        Self {
+            release: vec![version.major, version.minor, version.patch],
+            phase: if version.prerelease.is_some() {
+                Phase::Pre
+            } else {
+                Phase::Release
+            },
+            pre: version.prerelease.clone(),
+            local: version.build.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn into_version(self, kind: VersionKind, raw: &str) -> Version {
+        let part = |index: usize| self.release.get(index).copied().unwrap_or_default();
+
+        // Pre-releases and development releases are both excluded from
+        // requirements by default, so map them onto the pre-release field
+        let mut prerelease = match (&self.pre, self.post, self.phase) {
+            (Some(pre), Some(post), _) => Some(format_compact!("{pre}.post.{post}")),
+            (Some(pre), None, _) => Some(pre.clone()),
+            (None, Some(post), _) if self.dev.is_some() => Some(format_compact!("post.{post}")),
+            _ => None,
+        };
+
+        if let Some(dev) = self.dev {
+            prerelease = Some(match prerelease {
+                Some(pre) => format_compact!("{pre}.dev.{dev}"),
+                None => format_compact!("dev.{dev}"),
+            });
+        }
+
+        Version {
+            kind,
+            major: part(0),
+            minor: part(1),
+            patch: part(2),
+            prerelease,
+            build: self.local.clone(),
+            scheme: Some(Box::new(VersionScheme {
+                raw: raw.into(),
+                parts: self,
+            })),
+            ..Default::default()
+        }
+    }
+}
+
+// Versions without a scheme, like semantic versions, are normalized on demand
+fn get_parts(version: &Version) -> Cow<'_, SchemeParts> {
+    match &version.scheme {
+        Some(scheme) => Cow::Borrowed(&scheme.parts),
+        None => Cow::Owned(SchemeParts::from_version(version)),
+    }
+}
+
+// Missing release segments are treated as 0, so that 1.0 == 1.0.0
+fn compare_release(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    for index in 0..lhs.len().max(rhs.len()) {
+        let ordering = lhs
+            .get(index)
+            .unwrap_or(&0)
+            .cmp(rhs.get(index).unwrap_or(&0));
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Compares versions when at least one of them is an ecosystem scheme,
+/// by normalizing both sides into the same shape. Calendar versions are
+/// always grouped first, to match the ordering of the version kinds.
+pub(crate) fn compare_schemes(
+    lhs: &Version,
+    rhs: &Version,
+    compare_pre: impl Fn(Option<&str>, Option<&str>) -> Ordering,
+    compare_local: impl Fn(Option<&str>, Option<&str>) -> Ordering,
+) -> Ordering {
+    let is_not_calendar = |version: &Version| version.kind != VersionKind::Calendar;
+    let lhs_parts = get_parts(lhs);
+    let rhs_parts = get_parts(rhs);
+
+    is_not_calendar(lhs)
+        .cmp(&is_not_calendar(rhs))
+        .then_with(|| lhs.scope.cmp(&rhs.scope))
+        .then_with(|| compare_parts(&lhs_parts, &rhs_parts, compare_pre))
+        .then_with(|| compare_local(lhs_parts.local.as_deref(), rhs_parts.local.as_deref()))
+        .then_with(|| lhs.kind.cmp(&rhs.kind))
+        .then_with(|| lhs.get_raw().cmp(&rhs.get_raw()))
+}
+
+/// Compares the epoch, release, and pre, post, and development parts of
+/// versions when at least one of them is an ecosystem scheme. The scope
+/// and local label are ignored, as requirements cannot define them.
+pub(crate) fn compare_scheme_release(
+    lhs: &Version,
+    rhs: &Version,
+    compare_pre: impl Fn(Option<&str>, Option<&str>) -> Ordering,
+) -> Ordering {
+    compare_parts(&get_parts(lhs), &get_parts(rhs), compare_pre)
+}
+
+/// Compares the epoch and leading release segments of an ecosystem scheme
+/// against the provided segments only, so that a partial requirement like
+/// `3.12` covers every version in that release line.
+pub(crate) fn compare_scheme_line(version: &Version, line: &[u32]) -> Ordering {
+    if line.is_empty() {
+        return Ordering::Equal;
+    }
+
+    let parts = get_parts(version);
+    let release = parts
+        .release
+        .iter()
+        .take(line.len())
+        .copied()
+        .collect::<Vec<_>>();
+
+    parts
+        .epoch
+        .cmp(&0)
+        .then_with(|| compare_release(&release, line))
+}
+
+fn compare_parts(
+    lhs: &SchemeParts,
+    rhs: &SchemeParts,
+    compare_pre: impl Fn(Option<&str>, Option<&str>) -> Ordering,
+) -> Ordering {
+    lhs.epoch
+        .cmp(&rhs.epoch)
+        .then_with(|| compare_release(&lhs.release, &rhs.release))
+        .then_with(|| lhs.phase.cmp(&rhs.phase))
+        .then_with(|| match (&lhs.pre, &rhs.pre) {
+            (Some(l), Some(r)) => compare_pre(Some(l), Some(r)),
+            (l, r) => l.is_some().cmp(&r.is_some()),
+        })
+        .then_with(|| lhs.post.cmp(&rhs.post))
+        // A development release comes before the release it belongs to
+        .then_with(|| match (lhs.dev, rhs.dev) {
+            (Some(l), Some(r)) => l.cmp(&r),
+            (l, r) => r.is_some().cmp(&l.is_some()),
+        })
+}
+
+/// Detects an ecosystem scheme from the provided input, but only when syntax
+/// that is specific to the scheme is used. A plain release of up to 3 parts
+/// is a semantic version (or partial requirement), and must not match.
+pub(crate) fn detect_scheme(input: &str) -> Option<Version> {
+    let input = input.trim();
+
+    // Python releases always have a minor segment, so require it
+    // to avoid detecting typos (like `1.a.2`) as pre-releases
+    if let Some(parts) = parse_pep440_parts(input)
+        && parts.release.len() > 1
+        && (parts.epoch > 0
+            || parts.release.len() > 3
+            || parts.pre.is_some()
+            || parts.post.is_some()
+            || parts.dev.is_some()
+            || parts.local.is_some())
+    {
+        return Some(parts.into_version(VersionKind::Pep440, input));
+    }
+
+    detect_maven_qualifier(input)
+}
+
+/// Detects a Maven version with a qualifier that has no semantic equivalent,
+/// like `SNAPSHOT` or `RELEASE`, and is therefore never a requirement.
+pub(crate) fn detect_maven_qualifier(input: &str) -> Option<Version> {
+    let input = input.trim();
+
+    if !input.bytes().any(|byte| byte.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let version = parse_maven(input)?;
+
+    if version.prerelease.as_deref().is_some_and(|pre| {
+        pre.starts_with("alpha") || pre.starts_with("beta") || pre.starts_with("rc")
+    }) {
+        return None;
+    }
+
+    Some(version)
+}
+
+/// Parses a [PEP 440](https://peps.python.org/pep-0440/) version, as used by Python.
+pub(crate) fn parse_pep440(input: &str) -> Option<Version> {
+    let input = input.trim();
+
+    parse_pep440_parts(input).map(|parts| parts.into_version(VersionKind::Pep440, input))
+}
+
+/// Parses a Maven version, as used by the Java ecosystem.
+pub(crate) fn parse_maven(input: &str) -> Option<Version> {
+    let input = input.trim();
+
+    parse_maven_parts(input).map(|parts| parts.into_version(VersionKind::Maven, input))
+}
+
+/// A minimal cursor over the characters of a version string.
+struct Scanner<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_separator(&mut self) -> bool {
+        self.eat(b'.') || self.eat(b'-') || self.eat(b'_')
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let len = self.rest().bytes().take_while(u8::is_ascii_digit).count();
+
+        if len == 0 {
+            return None;
+        }
+
+        let value = self.rest()[..len].parse().ok()?;
+        self.pos += len;
+
+        Some(value)
+    }
+
+    fn word(&mut self) -> &'a str {
+        let len = self
+            .rest()
+            .bytes()
+            .take_while(u8::is_ascii_alphabetic)
+            .count();
+        let value = &self.rest()[..len];
+        self.pos += len;
+
+        value
+    }
+
+    // Attempt to parse with the closure, and rewind if it fails
+    fn attempt<T>(&mut self, op: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let pos = self.pos;
+        let result = op(self);
+
+        if result.is_none() {
+            self.pos = pos;
+        }
+
+        result
+    }
+}
+
+fn parse_release(scanner: &mut Scanner) -> Option<Vec<u32>> {
+    let mut release = vec![scanner.number()?];
+
+    while let Some(number) = scanner.attempt(|s| if s.eat(b'.') { s.number() } else { None }) {
+        release.push(number);
+    }
+
+    Some(release)
+}
+
+fn parse_pep440_parts(input: &str) -> Option<SchemeParts> {
+    let lower = input.to_ascii_lowercase();
+    let (public, local) = match lower.split_once('+') {
+        Some((public, local)) => (public, Some(local)),
+        None => (lower.as_str(), None),
+    };
+
+    let mut scanner = Scanner::new(public);
+    let mut parts = SchemeParts::default();
+
+    scanner.eat(b'v');
+
+    if let Some(epoch) = scanner.attempt(|s| {
+        let epoch = s.number()?;
+        if s.eat(b'!') { Some(epoch) } else { None }
+    }) {
+        parts.epoch = epoch;
+    }
+
+    parts.release = parse_release(&mut scanner)?;
+
+    // Pre-release: a1, b2, rc3, with optional separators
+    if let Some((label, number)) = scanner.attempt(|s| {
+        s.eat_separator();
+
+        let label = match s.word() {
+            "a" | "alpha" => "a",
+            "b" | "beta" => "b",
+            "rc" | "c" | "pre" | "preview" => "rc",
+            _ => return None,
+        };
+
+        s.eat_separator();
+
+        Some((label, s.number().unwrap_or_default()))
+    }) {
+        parts.phase = Phase::Pre;
+        parts.pre = Some(format_compact!("{label}.{number}"));
+    }
+
+    // Post-release: post1, rev1, r1, or an implicit -1
+    if let Some(number) = scanner.attempt(|s| {
+        if s.eat(b'-')
+            && let Some(number) = s.number()
+        {
+            return Some(number);
+        }
+
+        s.eat_separator();
+
+        if !matches!(s.word(), "post" | "rev" | "r") {
+            return None;
+        }
+
+        s.eat_separator();
+
+        Some(s.number().unwrap_or_default())
+    }) {
+        if parts.phase == Phase::Release {
+            parts.phase = Phase::Post;
+        }
+
+        parts.post = Some(number);
+    }
+
+    // Development release: dev1
+    if let Some(number) = scanner.attempt(|s| {
+        s.eat_separator();
+
+        if s.word() != "dev" {
+            return None;
+        }
+
+        s.eat_separator();
+
+        Some(s.number().unwrap_or_default())
+    }) {
+        if parts.phase == Phase::Release {
+            parts.phase = Phase::Dev;
+        }
+
+        parts.dev = Some(number);
+    }
+
+    if !scanner.is_done() {
+        return None;
+    }
+
+    if let Some(local) = local {
+        if local.is_empty()
+            || !local
+                .split(['.', '-', '_'])
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric()))
+        {
+            return None;
+        }
+
+        parts.local = Some(local.replace(['-', '_'], ".").into());
+    }
+
+    Some(parts)
+}
+
+fn parse_maven_parts(input: &str) -> Option<SchemeParts> {
+    let lower = input.to_ascii_lowercase();
+    let mut scanner = Scanner::new(&lower);
+    let mut parts = SchemeParts {
+        release: parse_release(&mut scanner)?,
+        ..Default::default()
+    };
+
+    if scanner.is_done() {
+        return Some(parts);
+    }
+
+    if !(scanner.eat(b'-') || scanner.eat(b'.')) {
+        return None;
+    }
+
+    let qualifier = scanner.word();
+
+    // Single letter qualifiers require a number, for example M1
+    let single = qualifier.len() == 1;
+    let separated = scanner.eat(b'-') || scanner.eat(b'.');
+    let number = scanner.number();
+
+    if !scanner.is_done() || (single && (separated || number.is_none())) {
+        return None;
+    }
+
+    let (phase, label) = match qualifier {
+        "a" | "alpha" => (Phase::Pre, "alpha"),
+        "b" | "beta" => (Phase::Pre, "beta"),
+        "m" | "milestone" => (Phase::Pre, "milestone"),
+        "cr" | "rc" => (Phase::Pre, "rc"),
+        "snapshot" => (Phase::Pre, "snapshot"),
+        "ga" | "final" | "release" => (Phase::Release, ""),
+        "sp" => (Phase::Post, ""),
+        _ => return None,
+    };
+
+    parts.phase = phase;
+
+    match phase {
+        Phase::Pre => {
+            parts.pre = Some(match number {
+                Some(number) => format_compact!("{label}.{number}"),
+                None => CompactString::new(label),
+            });
+        }
+        Phase::Post => {
+            parts.post = Some(number.unwrap_or_default());
+        }
+        _ => {
+            if number.is_some() {
+                return None;
+            }
+        }
+    };
+
+    Some(parts)
+}
@@ -86,7 +86,7 @@ impl FormatOptions {
     pub fn new(kind: VersionKind) -> Self {
         match kind {
             VersionKind::Calendar => Self::calendar(),
-            VersionKind::Semantic => Self::semantic(),
+            VersionKind::Semantic | VersionKind::Pep440 | VersionKind::Maven => Self::semantic(),
         }
     }
 
@@ -132,6 +132,17 @@ pub trait FormatsVersion {
 
 impl FormatsVersion for Version {
     fn to_formatted_string(&self, options: &FormatOptions) -> String {
+        // Ecosystem schemes have no canonical form, so render the
+        // original string unless parts are explicitly omitted
+        if let Some(raw) = self.get_raw()
+            && options.include_minor
+            && options.include_patch
+            && options.include_prerelease
+            && options.include_build
+        {
+            return raw.to_string();
+        }
+
         let mut out = String::new();
 
         if options.include_scope
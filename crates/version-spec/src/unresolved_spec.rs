@@ -2,6 +2,7 @@ use crate::resolved_spec::VersionSpec;
 use crate::spec_error::SpecError;
 use crate::syntax::*;
 use crate::syntax_parser::parse_alias;
+use crate::syntax_scheme::detect_maven_qualifier;
 use crate::syntax_traits::{FormatOptions, FormatsVersion};
 use compact_str::CompactString;
 use human_sort::compare;
@@ -105,6 +106,18 @@ impl UnresolvedVersionSpec {
         }
     }
 
+    /// Convert the current unresolved specification to a range, in which a
+    /// version is an exact match. Returns `None` for aliases and canary,
+    /// as they cannot be represented as a range.
+    pub fn to_range(&self) -> Option<Range> {
+        match self {
+            Self::Range(range) => Some(range.to_owned()),
+            Self::Requirement(req) => Some(Range::from(req.to_owned())),
+            Self::Version(version) => Some(Range::from(version.to_requirement(Op::Exact))),
+            _ => None,
+        }
+    }
+
     /// Convert the current unresolved specification to a partial string, where
     /// minor and patch versions are omitted if not defined, and the comparator
     /// operator and build metadata are also omitted. For example, "~1.2" would
@@ -170,6 +183,10 @@ impl FromStr for UnresolvedVersionSpec {
             return Ok(Self::Version(version));
         }
 
+        if let Some(version) = detect_maven_qualifier(value) {
+            return Ok(Self::Version(version));
+        }
+
         // Kept for the error below, as a failed requirement is the most
         // useful diagnostic for an input that was meant to be a version
         let error = match Requirement::parse(value) {
@@ -181,6 +198,12 @@ impl FromStr for UnresolvedVersionSpec {
             return Ok(Self::Range(range));
         }
 
+        // Other ecosystem schemes are attempted last, as they overlap with partial
+        // requirements, for example "1.2-alpha" is also a valid PEP 440 version
+        if let Ok(version) = Version::parse_with_schemes(value) {
+            return Ok(Self::Version(version));
+        }
+
         match parse_alias(value) {
             Ok(alias) => Ok(Self::Alias(alias)),
             Err(_) => Err(error),
@@ -0,0 +1,208 @@
+use std::ops::Bound;
+use version_spec::{
+    MatchesVersion, Range, Requirement, UnresolvedVersionSpec, Version, VersionInterval,
+};
+
+fn req(input: &str) -> Requirement {
+    Requirement::parse(input).unwrap()
+}
+
+fn range(input: &str) -> Range {
+    Range::parse(input).unwrap()
+}
+
+fn intersect(lhs: &str, rhs: &str) -> Option<String> {
+    range(lhs)
+        .intersect(&range(rhs))
+        .map(|range| range.to_string())
+}
+
+mod interval {
+    use super::*;
+
+    #[test]
+    fn converts_requirements() {
+        for (input, expected) in [
+            ("^1.2.3", ">=1.2.3 && <2.0.0"),
+            ("^0.2.3", ">=0.2.3 && <0.3.0"),
+            ("^0.0.3", ">=0.0.3 && <0.0.4"),
+            ("^0", ">=0.0.0 && <1.0.0"),
+            ("~1.2.3", ">=1.2.3 && <1.3.0"),
+            ("~1", ">=1.0.0 && <2.0.0"),
+            ("1.2", ">=1.2.0 && <1.3.0"),
+            ("=1.2.3", "=1.2.3"),
+            (">1.2", ">=1.3.0"),
+            (">1.2.3", ">1.2.3"),
+            ("<=1", "<2.0.0"),
+            ("<=1.2.3", "<=1.2.3"),
+            ("*", "*"),
+        ] {
+            assert_eq!(
+                req(input).to_interval().to_clause().to_string(),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn contains_versions() {
+        let interval = req("^20").to_interval();
+
+        assert!(interval.contains(&Version::new(20, 10, 0)));
+        assert!(!interval.contains(&Version::new(21, 0, 0)));
+        assert!(!interval.contains(&Version::new(19, 9, 9)));
+    }
+
+    #[test]
+    fn detects_empty() {
+        let interval = VersionInterval {
+            scope: None,
+            lower: Bound::Included(Version::new(2, 0, 0)),
+            upper: Bound::Excluded(Version::new(2, 0, 0)),
+        };
+
+        assert!(interval.is_empty());
+        assert!(req(">=2").intersect(&req("<2")).is_none());
+    }
+
+    #[test]
+    fn respects_scopes() {
+        assert!(
+            req("^node-20")
+                .to_interval()
+                .intersect(&req("^deno-20").to_interval())
+                .is_none()
+        );
+        assert_eq!(
+            req("^node-20")
+                .to_interval()
+                .intersect(&req(">=20.5").to_interval())
+                .unwrap()
+                .scope
+                .as_deref(),
+            Some("node")
+        );
+    }
+}
+
+mod intersection {
+    use super::*;
+
+    #[test]
+    fn intersects_requirements() {
+        assert_eq!(
+            req("^20").intersect(&req(">=20.10")).unwrap().to_string(),
+            ">=20.10.0 && <21.0.0"
+        );
+        assert_eq!(
+            req("~1.2").intersect(&req("^1.2.5")).unwrap().to_string(),
+            ">=1.2.5 && <1.3.0"
+        );
+        assert_eq!(req("^20").intersect(&req("^22")), None);
+    }
+
+    #[test]
+    fn intersects_ranges() {
+        assert_eq!(
+            intersect("^18 || ^20", ">=19").as_deref(),
+            Some(">=20.0.0 && <21.0.0")
+        );
+        assert_eq!(
+            intersect("^18 || ^20", "1.2.3 - 20.5.0").as_deref(),
+            Some(">=18.0.0 && <19.0.0 || >=20.0.0 && <=20.5.0")
+        );
+        assert_eq!(intersect("*", "=1.2.3").as_deref(), Some("=1.2.3"));
+        assert_eq!(intersect("*", "*").as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn detects_unsatisfiable() {
+        assert_eq!(intersect("^18 || ^20", ">=22"), None);
+        assert_eq!(intersect(">=1.2.3", "<1.2.3"), None);
+
+        assert!(!range(">=2 && <1").is_satisfiable());
+        assert!(range(">=1 && <2").is_satisfiable());
+    }
+
+    #[test]
+    fn result_matches_same_versions() {
+        let lhs = range("^1.2 || ~2.1");
+        let rhs = range(">=1.5, <2.1.5");
+        let result = lhs.intersect(&rhs).unwrap();
+
+        for version in [
+            "1.2.0", "1.5.0", "1.9.9", "2.0.0", "2.1.0", "2.1.4", "2.1.5",
+        ] {
+            let version = Version::parse(version).unwrap();
+
+            assert_eq!(
+                result.matches(&version),
+                lhs.matches(&version) && rhs.matches(&version),
+                "version: {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn result_can_be_parsed() {
+        let result = range("^20").intersect(&range(">=20.10")).unwrap();
+
+        assert_eq!(Range::parse(result.to_string()).unwrap(), result);
+    }
+}
+
+mod simplify {
+    use super::*;
+
+    #[test]
+    fn merges_overlapping_clauses() {
+        assert_eq!(
+            range("^1.2 || ~1.5 || 1.3.0 - 1.4.0")
+                .simplify()
+                .unwrap()
+                .to_string(),
+            ">=1.2.0 && <2.0.0"
+        );
+        assert_eq!(
+            range(">=1 && <2 || >=2 && <3")
+                .simplify()
+                .unwrap()
+                .to_string(),
+            ">=1.0.0 && <3.0.0"
+        );
+    }
+
+    #[test]
+    fn removes_unsatisfiable_clauses() {
+        assert_eq!(
+            range(">=2 && <1 || ^3").simplify().unwrap().to_string(),
+            ">=3.0.0 && <4.0.0"
+        );
+        assert_eq!(range(">=2 && <1").simplify(), None);
+    }
+
+    #[test]
+    fn converts_unresolved_specs() {
+        assert_eq!(
+            UnresolvedVersionSpec::parse("1.2.3")
+                .unwrap()
+                .to_range()
+                .unwrap()
+                .to_string(),
+            "=1.2.3"
+        );
+        assert_eq!(
+            UnresolvedVersionSpec::parse("^1")
+                .unwrap()
+                .to_range()
+                .unwrap()
+                .to_string(),
+            "^1"
+        );
+        assert_eq!(
+            UnresolvedVersionSpec::parse("latest").unwrap().to_range(),
+            None
+        );
+    }
+}
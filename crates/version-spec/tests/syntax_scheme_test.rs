@@ -0,0 +1,402 @@
+use version_spec::{
+    FormatOptions, FormatsVersion, MatchesVersion, Range, Requirement, SpecError,
+    UnresolvedVersionSpec, Version, VersionKind, VersionSpec,
+};
+
+fn sorted<const N: usize>(
+    inputs: [&'static str; N],
+    parse: fn(&'static str) -> Result<Version, SpecError>,
+) -> Vec<String> {
+    let mut versions = inputs.map(|input| parse(input).unwrap());
+    versions.sort();
+    versions.iter().map(ToString::to_string).collect()
+}
+
+mod pep440 {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        let version = Version::parse_with_schemes("3.12.0rc1").unwrap();
+
+        assert_eq!(version.kind, VersionKind::Pep440);
+        assert_eq!(version.major, 3);
+        assert_eq!(version.minor, 12);
+        assert_eq!(version.patch, 0);
+        assert_eq!(version.prerelease.as_deref(), Some("rc.1"));
+
+        let version = Version::parse_with_schemes("1!2.0.post1+ubuntu-1").unwrap();
+
+        assert_eq!(version.kind, VersionKind::Pep440);
+        assert_eq!(version.major, 2);
+        assert_eq!(version.prerelease, None);
+        assert_eq!(version.get_raw(), Some("1!2.0.post1+ubuntu-1"));
+        assert_eq!(version.build.as_deref(), Some("ubuntu.1"));
+        assert_eq!(
+            version.to_formatted_string(&FormatOptions {
+                include_prerelease: false,
+                ..FormatOptions::new(VersionKind::Pep440)
+            }),
+            "2.0.0+ubuntu.1"
+        );
+
+        let version = Version::parse_with_schemes("1.0.dev3").unwrap();
+
+        assert_eq!(version.prerelease.as_deref(), Some("dev.3"));
+    }
+
+    #[test]
+    fn normalizes_prerelease_labels() {
+        for (input, pre) in [
+            ("1.0a1", "a.1"),
+            ("1.0.alpha.1", "a.1"),
+            ("1.0-beta2", "b.2"),
+            ("1.0c1", "rc.1"),
+            ("1.0_preview_3", "rc.3"),
+            ("1.0RC", "rc.0"),
+        ] {
+            assert_eq!(
+                Version::parse_pep440(input).unwrap().prerelease.as_deref(),
+                Some(pre),
+                "input: {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn prefers_semantic_and_calendar() {
+        for input in ["1.2.3", "1.2.3-rc.1", "2024-02-26"] {
+            assert!(
+                !Version::parse_with_schemes(input).unwrap().is_scheme(),
+                "input: {input}"
+            );
+        }
+
+        assert_eq!(
+            Version::parse_with_schemes("1.2.3.4").unwrap().kind,
+            VersionKind::Pep440
+        );
+    }
+
+    #[test]
+    fn only_detected_when_parsing_with_schemes() {
+        for input in ["1.2.3.4", "3.12.0rc1", "1!2.0", "1.0.post1"] {
+            assert!(Version::parse(input).is_err(), "input: {input}");
+            assert!(Version::parse_with_schemes(input).is_ok(), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn errors_for_invalid() {
+        for input in ["1.0rc1+", "1.0foo1", "a1.0", "1.0+loc@l"] {
+            assert!(Version::parse_pep440(input).is_err(), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn only_detects_specific_syntax() {
+        for input in ["1.2", "v1.2", "1.a.2", "1rc1"] {
+            assert!(
+                Version::parse_with_schemes(input).is_err(),
+                "input: {input}"
+            );
+            assert!(Version::parse_pep440(input).is_ok(), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn orders_versions() {
+        // The example ordering from the PEP 440 spec
+        assert_eq!(
+            sorted(
+                [
+                    "1.1.dev1",
+                    "1.0.post456",
+                    "1.0",
+                    "1.0a12",
+                    "1.0b2.post345",
+                    "1.0rc1.dev456",
+                    "1.0.dev456",
+                    "1.0a2.dev456",
+                    "1.0.post456.dev34",
+                    "1.0a1",
+                    "1.0b1.dev456",
+                    "1.0c1",
+                    "1.0rc1",
+                    "1.0b2",
+                    "1.0b2.post345.dev456",
+                    "1.0a12.dev456",
+                ],
+                Version::parse_pep440::<&str>
+            ),
+            [
+                "1.0.dev456",
+                "1.0a1",
+                "1.0a2.dev456",
+                "1.0a12.dev456",
+                "1.0a12",
+                "1.0b1.dev456",
+                "1.0b2",
+                "1.0b2.post345.dev456",
+                "1.0b2.post345",
+                "1.0rc1.dev456",
+                "1.0c1",
+                "1.0rc1",
+                "1.0",
+                "1.0.post456.dev34",
+                "1.0.post456",
+                "1.1.dev1",
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_with_epochs_and_local() {
+        assert_eq!(
+            sorted(
+                ["1!1.0", "2.0", "1.0+local.2", "1.0+local.10", "1.0.0.1"],
+                Version::parse_pep440::<&str>
+            ),
+            ["1.0+local.2", "1.0+local.10", "1.0.0.1", "2.0", "1!1.0"]
+        );
+    }
+
+    #[test]
+    fn orders_with_semantic_versions() {
+        assert_eq!(
+            sorted(
+                ["3.13.0", "3.12.1", "3.13.0rc1", "3.12.1.post1", "3.13.0b2"],
+                Version::parse_with_schemes::<&str>
+            ),
+            ["3.12.1", "3.12.1.post1", "3.13.0b2", "3.13.0rc1", "3.13.0"]
+        );
+    }
+
+    #[test]
+    fn matches_requirements() {
+        let rc = Version::parse_with_schemes("3.13.0rc1").unwrap();
+        let post = Version::parse_with_schemes("3.12.1.post1").unwrap();
+
+        assert!(!Requirement::parse("^3").unwrap().matches(&rc));
+        assert!(Requirement::parse(">=3.13.0-rc.1").unwrap().matches(&rc));
+        assert!(Requirement::parse("~3.12").unwrap().matches(&post));
+        assert!(!Range::parse(">=3.12.2 || <3").unwrap().matches(&post));
+    }
+
+    #[test]
+    fn matches_epochs_against_each_operator() {
+        let version = Version::parse_with_schemes("1!2.0").unwrap();
+
+        for req in [
+            "=2.0.0", "=2", ">3", ">=3.0.0", "<3", "<=2.0.0", "~2.0", "^2",
+        ] {
+            assert_eq!(
+                Requirement::parse(req).unwrap().matches(&version),
+                req.starts_with('>'),
+                "req: {req}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_post_releases_against_each_operator() {
+        let version = Version::parse_with_schemes("3.12.0.post1").unwrap();
+
+        for (req, expected) in [
+            ("=3.12.0", false),
+            ("=3.12", true),
+            (">3.12.0", true),
+            (">3.12", false),
+            (">=3.12.0", true),
+            ("<3.12.0", false),
+            ("<=3.12.0", false),
+            ("<3.12.1", true),
+            ("~3.12.0", true),
+            ("^3.12.0", true),
+            ("^3.12.1", false),
+        ] {
+            assert_eq!(
+                Requirement::parse(req).unwrap().matches(&version),
+                expected,
+                "req: {req}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_dev_releases_against_each_operator() {
+        let version = Version::parse_with_schemes("3.12.0.dev1").unwrap();
+
+        for (req, expected) in [
+            ("=3.12.0", false),
+            (">3.11.0", true),
+            (">=3.12.0", false),
+            ("<3.12.0", true),
+            ("<=3.12.0", true),
+            ("~3.12.0", false),
+            ("^3.11.0", true),
+        ] {
+            assert_eq!(
+                Requirement::parse(req).unwrap().matches_op(&version),
+                expected,
+                "req: {req}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_fourth_release_segment() {
+        let version = Version::parse_with_schemes("1.2.3.4").unwrap();
+
+        assert!(!Requirement::parse("=1.2.3").unwrap().matches(&version));
+        assert!(Requirement::parse(">1.2.3").unwrap().matches(&version));
+        assert!(Requirement::parse("<1.2.4").unwrap().matches(&version));
+        assert!(Requirement::parse("~1.2").unwrap().matches(&version));
+    }
+
+    #[test]
+    fn matches_operators_consistently_with_ordering() {
+        let req = Version::parse_with_schemes("3.12.0").unwrap();
+
+        for input in [
+            "1!2.0",
+            "3.12.0.post1",
+            "3.12.0.dev1",
+            "3.12.0rc1",
+            "3.12.0.0.1",
+        ] {
+            let version = Version::parse_with_schemes(input).unwrap();
+            let ordering = version.cmp(&req);
+
+            assert_eq!(
+                Requirement::parse(">3.12.0").unwrap().matches_op(&version),
+                ordering.is_gt(),
+                "input: {input}"
+            );
+            assert_eq!(
+                Requirement::parse("<3.12.0").unwrap().matches_op(&version),
+                ordering.is_lt(),
+                "input: {input}"
+            );
+            assert_eq!(
+                Requirement::parse("=3.12.0").unwrap().matches_op(&version),
+                ordering.is_eq(),
+                "input: {input}"
+            );
+        }
+    }
+}
+
+mod maven {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        let version = Version::parse_with_schemes("1.0-SNAPSHOT").unwrap();
+
+        assert_eq!(version.kind, VersionKind::Maven);
+        assert_eq!(version.major, 1);
+        assert_eq!(version.prerelease.as_deref(), Some("snapshot"));
+
+        let version = Version::parse_with_schemes("5.3.0.RELEASE").unwrap();
+
+        assert_eq!(version.kind, VersionKind::Maven);
+        assert_eq!(version.prerelease, None);
+
+        let version = Version::parse_maven("2.0-M1").unwrap();
+
+        assert_eq!(version.prerelease.as_deref(), Some("milestone.1"));
+    }
+
+    #[test]
+    fn errors_for_invalid() {
+        for input in ["1.0-foo", "1.0-M", "1.0.Final1", "1.0--SNAPSHOT"] {
+            assert!(Version::parse_maven(input).is_err(), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn orders_versions() {
+        let mut versions = [
+            "1.0-sp1",
+            "1.0",
+            "1.0.Final",
+            "1.0-SNAPSHOT",
+            "1.0-rc2",
+            "1.0-M2",
+            "1.0-beta-1",
+            "1.0-alpha1",
+            "0.9.GA",
+        ]
+        .map(|input| Version::parse_maven(input).unwrap());
+        versions.sort();
+
+        assert_eq!(
+            versions.map(|version| version.to_string()),
+            [
+                "0.9.GA",
+                "1.0-alpha1",
+                "1.0-beta-1",
+                "1.0-M2",
+                "1.0-rc2",
+                "1.0-SNAPSHOT",
+                "1.0",
+                "1.0.Final",
+                "1.0-sp1",
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_after_semantic_release() {
+        let maven = Version::parse_with_schemes("1.0.0.Final").unwrap();
+        let semver = Version::parse_with_schemes("1.0.0").unwrap();
+
+        assert_ne!(maven, semver);
+        assert!(maven > semver);
+        assert!(Requirement::parse("=1.0.0").unwrap().matches(&maven));
+    }
+}
+
+mod specs {
+    use super::*;
+
+    #[test]
+    fn round_trips_original_string() {
+        for input in [
+            "3.12.0rc1",
+            "1.0.post1",
+            "1!2.0",
+            "1.0-SNAPSHOT",
+            "5.3.0.RELEASE",
+        ] {
+            let spec = VersionSpec::parse(input).unwrap();
+
+            assert_eq!(spec.to_string(), input);
+            assert_eq!(
+                serde_json::to_string(&spec).unwrap(),
+                format!("\"{input}\"")
+            );
+            assert_eq!(
+                serde_json::from_str::<VersionSpec>(&format!("\"{input}\"")).unwrap(),
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn prefers_requirements_when_unresolved() {
+        assert!(matches!(
+            UnresolvedVersionSpec::parse("1.2-alpha").unwrap(),
+            UnresolvedVersionSpec::Requirement(_)
+        ));
+        assert_eq!(
+            UnresolvedVersionSpec::parse("3.12.0rc1").unwrap(),
+            UnresolvedVersionSpec::Version(Version::parse_with_schemes("3.12.0rc1").unwrap())
+        );
+        assert_eq!(
+            UnresolvedVersionSpec::parse("1.0-SNAPSHOT").unwrap(),
+            UnresolvedVersionSpec::Version(Version::parse_with_schemes("1.0-SNAPSHOT").unwrap())
+        );
+    }
+}
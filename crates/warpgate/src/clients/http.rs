@@ -57,6 +57,7 @@ impl Downloader for HttpDownloader {
 pub struct HttpClient {
     client: Client,
     middleware: ClientWithMiddleware,
+    pub(crate) options: HttpOptions,
 }
 
 impl HttpClient {
@@ -122,6 +123,13 @@ pub struct HttpOptions {
     /// Absolute path to a directory in which to cache GET and HEAD requests.
     pub cache_dir: Option<PathBuf>,
 
+    /// Number of segments to split large file downloads into, which are then
+    /// downloaded in parallel. Requires the server to support range requests.
+    pub download_segments: Option<u16>,
+
+    /// Maximum bandwidth, in bytes per second, to use when downloading files.
+    pub max_bandwidth: Option<u64>,
+
     /// A list of proxy URLs that all requests should pass through. URLs that start with
     /// `http:` will handle insecure requests, while `https:` will handle secure requests.
     pub proxies: Vec<String>,
@@ -299,7 +307,11 @@ pub fn create_http_client_with_options(
 
     debug!("Created HTTP client");
 
-    Ok(HttpClient { client, middleware })
+    Ok(HttpClient {
+        client,
+        middleware,
+        options: options.to_owned(),
+    })
 }
 
 /// HTTP client middleware that applies credentials from
@@ -0,0 +1,558 @@
+use super::{HttpClient, WarpgateHttpClientError};
+use reqwest::header::{
+    ACCEPT_RANGES, CONTENT_LENGTH, ETAG, HeaderMap, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::{Response, StatusCode};
+use rustc_hash::FxHashMap;
+use starbase_utils::fs::{self, FsError};
+use starbase_utils::net::OnChunkFn;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tracing::{debug, instrument, trace};
+
+/// Files smaller than this size will not be split into segments.
+pub const SEGMENTED_DOWNLOAD_THRESHOLD: u64 = 32 * 1024 * 1024;
+
+/// Options for [`HttpClient::download_file`].
+#[derive(Clone, Default)]
+pub struct DownloadFileOptions {
+    /// Headers to inject into each request.
+    pub headers: FxHashMap<String, String>,
+
+    /// A callback that is called with the current and total size of the
+    /// download for each chunk received. Is called with a current size
+    /// of 0 when the download starts.
+    pub on_chunk: Option<OnChunkFn>,
+}
+
+/// Return the path to the file that a download is written to,
+/// before being moved to the destination path on completion.
+pub fn get_partial_download_file(dest_file: &Path, segment: Option<usize>) -> PathBuf {
+    let mut name = dest_file
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+
+    name.push(".part");
+
+    if let Some(segment) = segment {
+        name.push(format!("{segment}"));
+    }
+
+    dest_file.with_file_name(name)
+}
+
+/// Return the path to the file that stores the validator (the `ETag` or
+/// `Last-Modified` header) of the remote file that a partial download
+/// belongs to. Partial downloads are only resumed when this validator
+/// still matches, which is checked by the server with `If-Range`.
+pub fn get_partial_validator_file(dest_file: &Path) -> PathBuf {
+    let mut name = dest_file
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+
+    name.push(".part.etag");
+
+    dest_file.with_file_name(name)
+}
+
+/// Split the total size into a list of inclusive byte ranges.
+pub fn split_into_segments(total_size: u64, count: u64) -> Vec<(u64, u64)> {
+    let count = count.clamp(1, total_size.max(1));
+    let size = total_size.div_ceil(count);
+    let mut segments = vec![];
+    let mut start = 0;
+
+    while start < total_size {
+        let end = (start + size).min(total_size) - 1;
+
+        segments.push((start, end));
+        start = end + 1;
+    }
+
+    segments
+}
+
+// Tracks progress across all segments of a download, and throttles
+// the download when a maximum bandwidth has been configured.
+struct DownloadProgress {
+    current_size: AtomicU64,
+    max_bandwidth: Option<u64>,
+    on_chunk: Option<OnChunkFn>,
+    started: Instant,
+    total_size: AtomicU64,
+    transferred: AtomicU64,
+}
+
+impl DownloadProgress {
+    fn new(on_chunk: Option<OnChunkFn>, max_bandwidth: Option<u64>) -> Self {
+        Self {
+            current_size: AtomicU64::new(0),
+            max_bandwidth: max_bandwidth.filter(|limit| *limit > 0),
+            on_chunk,
+            started: Instant::now(),
+            total_size: AtomicU64::new(0),
+            transferred: AtomicU64::new(0),
+        }
+    }
+
+    fn start(&self, resumed_size: u64, total_size: u64) {
+        self.current_size.store(resumed_size, Ordering::Relaxed);
+        self.total_size.store(total_size, Ordering::Relaxed);
+
+        if let Some(on_chunk) = &self.on_chunk {
+            on_chunk(0, total_size);
+
+            if resumed_size > 0 {
+                on_chunk(resumed_size, total_size);
+            }
+        }
+    }
+
+    async fn advance(&self, size: u64) {
+        let current_size = self.current_size.fetch_add(size, Ordering::Relaxed) + size;
+        let total_size = self.total_size.load(Ordering::Relaxed);
+
+        if let Some(on_chunk) = &self.on_chunk {
+            on_chunk(
+                if total_size > 0 {
+                    current_size.min(total_size)
+                } else {
+                    current_size
+                },
+                total_size,
+            );
+        }
+
+        // Sleep until the transfer rate drops below the limit
+        if let Some(limit) = self.max_bandwidth {
+            let transferred = self.transferred.fetch_add(size, Ordering::Relaxed) + size;
+            let expected = Duration::from_secs_f64(transferred as f64 / limit as f64);
+            let elapsed = self.started.elapsed();
+
+            if expected > elapsed {
+                tokio::time::sleep(expected - elapsed).await;
+            }
+        }
+    }
+}
+
+impl HttpClient {
+    /// Download a file from the provided URL to the destination file.
+    ///
+    /// The file is first written to a partial file (`<name>.part`), and if a
+    /// partial file already exists from an interrupted download, the download
+    /// is resumed with a `Range` request. Large files are split into segments
+    /// that are downloaded in parallel, when enabled and supported by the
+    /// server. Downloads are throttled to the configured maximum bandwidth.
+    #[instrument(skip(self, options))]
+    pub async fn download_file(
+        &self,
+        url: &str,
+        dest_file: &Path,
+        options: DownloadFileOptions,
+    ) -> Result<(), WarpgateHttpClientError> {
+        let progress = Arc::new(DownloadProgress::new(
+            options.on_chunk.clone(),
+            self.options.max_bandwidth,
+        ));
+        let segment_count = self.options.download_segments.unwrap_or(1);
+
+        if let Some(parent) = dest_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if segment_count > 1
+            && let Some((total_size, validator)) =
+                self.get_ranged_content_length(url, &options).await?
+            && total_size >= SEGMENTED_DOWNLOAD_THRESHOLD
+        {
+            return self
+                .download_file_in_segments(
+                    url,
+                    dest_file,
+                    &options,
+                    split_into_segments(total_size, segment_count as u64),
+                    validator,
+                    progress,
+                )
+                .await;
+        }
+
+        let part_file = get_partial_download_file(dest_file, None);
+        let validator_file = get_partial_validator_file(dest_file);
+        let validator = read_validator(&validator_file);
+
+        self.download_partial_file(
+            url,
+            &part_file,
+            None,
+            PartialValidator {
+                value: validator.as_deref(),
+                file: Some(&validator_file),
+            },
+            &options,
+            &progress,
+        )
+        .await?;
+
+        fs::rename(&part_file, dest_file)?;
+        fs::remove_file(&validator_file)?;
+
+        Ok(())
+    }
+
+    /// Make a `HEAD` request to determine the size (and validator) of
+    /// the file, but only if the server supports range requests.
+    async fn get_ranged_content_length(
+        &self,
+        url: &str,
+        options: &DownloadFileOptions,
+    ) -> Result<Option<(u64, Option<String>)>, WarpgateHttpClientError> {
+        let mut request = self.head(url);
+
+        for (key, value) in &options.headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| HttpClient::map_error(url.to_owned(), error))?;
+
+        if !response.status().is_success()
+            || response
+                .headers()
+                .get(ACCEPT_RANGES)
+                .is_none_or(|value| value != "bytes")
+        {
+            return Ok(None);
+        }
+
+        // The body of a HEAD response is empty, so we can't use
+        // `Response::content_length`, and must parse the header
+        Ok(response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|size| *size > 0)
+            .map(|size| (size, get_validator(response.headers()))))
+    }
+
+    async fn download_file_in_segments(
+        &self,
+        url: &str,
+        dest_file: &Path,
+        options: &DownloadFileOptions,
+        segments: Vec<(u64, u64)>,
+        validator: Option<String>,
+        progress: Arc<DownloadProgress>,
+    ) -> Result<(), WarpgateHttpClientError> {
+        let total_size = segments.last().map(|(_, end)| end + 1).unwrap_or_default();
+        let validator_file = get_partial_validator_file(dest_file);
+        let mut resumed_size = 0;
+        let mut part_files = vec![];
+
+        debug!(
+            url,
+            segments = segments.len(),
+            "Downloading file in parallel segments"
+        );
+
+        // Existing segments can only be resumed if they belong to
+        // the same version of the remote file, so start over if not
+        let can_resume =
+            validator.is_some() && read_validator(&validator_file).as_ref() == validator.as_ref();
+
+        for index in 0..segments.len() {
+            let part_file = get_partial_download_file(dest_file, Some(index));
+
+            if !can_resume && part_file.exists() {
+                fs::remove_file(&part_file)?;
+            }
+
+            part_files.push(part_file);
+        }
+
+        write_validator(&validator_file, validator.as_deref())?;
+
+        for (part_file, (start, end)) in part_files.iter().zip(&segments) {
+            resumed_size += get_file_size(part_file).min(end - start + 1);
+        }
+
+        progress.start(resumed_size, total_size);
+
+        let mut set = JoinSet::new();
+
+        for (part_file, range) in part_files.iter().zip(segments) {
+            let client = self.clone();
+            let url = url.to_owned();
+            let part_file = part_file.to_owned();
+            let options = options.clone();
+            let progress = Arc::clone(&progress);
+            let validator = validator.clone();
+
+            set.spawn(async move {
+                client
+                    .download_partial_file(
+                        &url,
+                        &part_file,
+                        Some(range),
+                        PartialValidator {
+                            value: validator.as_deref(),
+                            file: None,
+                        },
+                        &options,
+                        &progress,
+                    )
+                    .await
+            });
+        }
+
+        while let Some(result) = set.join_next().await {
+            match result {
+                Ok(inner) => inner?,
+                Err(error) => std::panic::resume_unwind(error.into_panic()),
+            };
+        }
+
+        // Join the segments into the destination file
+        let mut file = fs::create_file(dest_file)?;
+
+        for part_file in &part_files {
+            let mut part = fs::open_file(part_file)?;
+
+            io::copy(&mut part, &mut file).map_err(|error| FsError::Write {
+                path: dest_file.to_path_buf(),
+                error: Box::new(error),
+            })?;
+        }
+
+        for part_file in &part_files {
+            fs::remove_file(part_file)?;
+        }
+
+        fs::remove_file(&validator_file)?;
+
+        Ok(())
+    }
+
+    /// Download the URL (or an inclusive byte range of it) into the partial file,
+    /// resuming from the end of the partial file if it already exists, and the
+    /// remote file has not changed since (validated with `If-Range`).
+    async fn download_partial_file(
+        &self,
+        url: &str,
+        part_file: &Path,
+        range: Option<(u64, u64)>,
+        validator: PartialValidator<'_>,
+        options: &DownloadFileOptions,
+        progress: &DownloadProgress,
+    ) -> Result<(), WarpgateHttpClientError> {
+        let mut offset = get_file_size(part_file);
+
+        // We can't verify what the partial file belongs to, so start over
+        if offset > 0 && validator.value.is_none() {
+            trace!(part_file = ?part_file, "Partial download can't be validated, starting over");
+
+            offset = 0;
+        }
+
+        let request_range = match range {
+            Some((start, end)) => {
+                if start + offset > end {
+                    trace!(part_file = ?part_file, "Segment already downloaded, skipping");
+
+                    return Ok(());
+                }
+
+                Some((start + offset, Some(end)))
+            }
+            None if offset > 0 => {
+                trace!(url, offset, "Attempting to resume a partial download");
+
+                Some((offset, None))
+            }
+            None => None,
+        };
+
+        let mut response = self
+            .send_range_request(url, request_range, validator.value, options)
+            .await?;
+
+        // The partial file is complete or stale, so start over
+        if range.is_none() && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            offset = 0;
+            response = self.send_range_request(url, None, None, options).await?;
+        }
+
+        let status = response.status();
+
+        if status == StatusCode::NOT_FOUND {
+            return Err(WarpgateHttpClientError::NotFound {
+                url: url.to_owned(),
+            });
+        }
+
+        if !status.is_success() {
+            return Err(WarpgateHttpClientError::DownloadFailed {
+                url: url.to_owned(),
+                status: status.to_string(),
+            });
+        }
+
+        if status != StatusCode::PARTIAL_CONTENT {
+            // A segment must only contain its own range, otherwise the server
+            // ignored the range, or the file changed since the download began
+            if range.is_some() {
+                return Err(WarpgateHttpClientError::RangeNotSatisfied {
+                    url: url.to_owned(),
+                    status: status.to_string(),
+                });
+            }
+
+            if offset > 0 {
+                trace!(
+                    url,
+                    "Server does not support resuming, or the file has changed, starting over"
+                );
+
+                offset = 0;
+            }
+        }
+
+        // Record which version of the remote file we're downloading,
+        // before writing, so that an interrupted download can be resumed
+        if offset == 0
+            && let Some(validator_file) = validator.file
+        {
+            write_validator(validator_file, get_validator(response.headers()).as_deref())?;
+        }
+
+        let mut file = if offset > 0 {
+            fs::open_file_for_appending(part_file)?
+        } else {
+            fs::create_file(part_file)?
+        };
+
+        if range.is_none() {
+            progress.start(
+                offset,
+                response
+                    .content_length()
+                    .map(|size| size + offset)
+                    .unwrap_or_default(),
+            );
+        }
+
+        write_response_to_file(url, part_file, &mut response, &mut file, progress).await
+    }
+
+    async fn send_range_request(
+        &self,
+        url: &str,
+        range: Option<(u64, Option<u64>)>,
+        validator: Option<&str>,
+        options: &DownloadFileOptions,
+    ) -> Result<Response, WarpgateHttpClientError> {
+        let mut request = self.get(url);
+
+        for (key, value) in &options.headers {
+            request = request.header(key, value);
+        }
+
+        if let Some((start, end)) = range {
+            request = request.header(
+                RANGE,
+                match end {
+                    Some(end) => format!("bytes={start}-{end}"),
+                    None => format!("bytes={start}-"),
+                },
+            );
+
+            if let Some(validator) = validator {
+                request = request.header(IF_RANGE, validator);
+            }
+        }
+
+        request
+            .send()
+            .await
+            .map_err(|error| HttpClient::map_error(url.to_owned(), error))
+    }
+}
+
+// The validator of a partial download, and the file it's persisted to
+// (when not downloading in segments, which manage their own file).
+struct PartialValidator<'a> {
+    value: Option<&'a str>,
+    file: Option<&'a Path>,
+}
+
+/// Return a validator for `If-Range`, which must be a strong `ETag`,
+/// or a `Last-Modified` date.
+fn get_validator(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.starts_with("W/"))
+        .or_else(|| {
+            headers
+                .get(LAST_MODIFIED)
+                .and_then(|value| value.to_str().ok())
+        })
+        .map(|value| value.to_owned())
+}
+
+fn read_validator(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+fn write_validator(path: &Path, validator: Option<&str>) -> Result<(), FsError> {
+    match validator {
+        Some(validator) => fs::write_file(path, validator),
+        None => fs::remove_file(path),
+    }
+}
+
+fn get_file_size(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .map(|meta| meta.len())
+        .unwrap_or_default()
+}
+
+async fn write_response_to_file(
+    url: &str,
+    path: &Path,
+    response: &mut Response,
+    file: &mut File,
+    progress: &DownloadProgress,
+) -> Result<(), WarpgateHttpClientError> {
+    while let Some(chunk) =
+        response
+            .chunk()
+            .await
+            .map_err(|error| WarpgateHttpClientError::Http {
+                url: url.to_owned(),
+                error: Box::new(error),
+            })?
+    {
+        file.write_all(&chunk).map_err(|error| FsError::Write {
+            path: path.to_path_buf(),
+            error: Box::new(error),
+        })?;
+
+        progress.advance(chunk.len() as u64).await;
+    }
+
+    Ok(())
+}
@@ -21,6 +21,13 @@ pub enum WarpgateHttpClientError {
         error: Box<reqwest::Error>,
     },
 
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(code(plugin::http_client::download_failed))
+    )]
+    #[error("Failed to download file from {} ({status}).", .url.style(Style::Url))]
+    DownloadFailed { url: String, status: String },
+
     #[cfg_attr(
         feature = "miette",
         diagnostic(code(plugin::http_client::request_failed))
@@ -64,6 +71,20 @@ pub enum WarpgateHttpClientError {
         #[source]
         error: Box<reqwest::Error>,
     },
+
+    #[cfg_attr(feature = "miette", diagnostic(code(plugin::http_client::not_found)))]
+    #[error("Download file does not exist at {}.", .url.style(Style::Url))]
+    NotFound { url: String },
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(code(plugin::http_client::range_not_satisfied))
+    )]
+    #[error(
+        "Failed to download a byte range of {} ({status}). The server did not return partial content, or the file has changed.",
+        .url.style(Style::Url)
+    )]
+    RangeNotSatisfied { url: String, status: String },
 }
 
 impl From<FsError> for WarpgateHttpClientError {
@@ -1,7 +1,9 @@
 mod http;
+mod http_download;
 mod http_error;
 mod oci;
 
 pub use http::*;
+pub use http_download::*;
 pub use http_error::*;
 pub use oci::*;
@@ -1,11 +1,14 @@
-//! Utilities for locating and testing WASM plugins.
+//! Utilities for locating and testing WASM plugins, and for testing downloads.
 
 use serde::Serialize;
 use starbase_utils::envx::{is_ci, path_var};
 use starbase_utils::fs;
 use std::collections::HashMap;
 use std::env;
+use std::net::{Shutdown, TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use warpgate_api::{HostArch, HostEnvironment, HostLibc, HostOS, TestEnvironment, VirtualPath};
 
 fn traverse_target_dir<T: AsRef<Path>, F: AsRef<str>>(
@@ -219,3 +222,241 @@ impl ConfigBuilder {
         self
     }
 }
+
+/// A request received by a [`TestHttpServer`].
+#[derive(Clone, Debug)]
+pub struct TestHttpRequest {
+    pub method: String,
+    pub path: String,
+    /// Headers with lowercased names.
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl TestHttpRequest {
+    /// Return the value of the header with the provided (lowercased) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|value| value.as_str())
+    }
+}
+
+/// A response returned from the handler of a [`TestHttpServer`].
+#[derive(Clone, Debug)]
+pub struct TestHttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Close the connection after writing this many bytes of the body,
+    /// to simulate an interrupted transfer.
+    pub truncate_body: Option<usize>,
+}
+
+impl TestHttpResponse {
+    /// Create a response with the provided status and body.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.into(),
+            truncate_body: None,
+        }
+    }
+
+    /// Create a `200` response with the provided body.
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body)
+    }
+
+    /// Create an empty `404` response.
+    pub fn not_found() -> Self {
+        Self::new(404, vec![])
+    }
+
+    /// Add a header to the response.
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.headers
+            .push((name.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    /// Close the connection after writing the provided number of bytes of the body.
+    pub fn truncate(mut self, size: usize) -> Self {
+        self.truncate_body = Some(size);
+        self
+    }
+}
+
+type TestHttpHandler = dyn Fn(&TestHttpRequest) -> TestHttpResponse + Send + Sync;
+
+/// A minimal HTTP/1.1 server for testing downloads, that responds to each
+/// request with the provided handler, and records every request it receives.
+/// Each connection is handled on its own thread, and is closed after a
+/// single response.
+pub struct TestHttpServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<TestHttpRequest>>>,
+}
+
+impl TestHttpServer {
+    /// Start a server on a random local port.
+    pub fn start(
+        handler: impl Fn(&TestHttpRequest) -> TestHttpResponse + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let requests_clone = Arc::clone(&requests);
+        let handler: Arc<TestHttpHandler> = Arc::new(handler);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let handler = Arc::clone(&handler);
+                let requests = Arc::clone(&requests_clone);
+
+                thread::spawn(move || {
+                    let Some(request) = read_test_http_request(&mut stream) else {
+                        return;
+                    };
+
+                    requests.lock().unwrap().push(request.clone());
+
+                    write_test_http_response(&mut stream, &request, handler(&request));
+                });
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Start a server that serves the provided files by path,
+    /// and responds with a `404` for all other paths.
+    pub fn serve_files<K: AsRef<str>, V: Into<Vec<u8>>>(
+        files: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        let files = files
+            .into_iter()
+            .map(|(path, body)| (path.as_ref().to_owned(), body.into()))
+            .collect::<HashMap<String, Vec<u8>>>();
+
+        Self::start(move |request| match files.get(&request.path) {
+            Some(body) => TestHttpResponse::ok(body.clone()),
+            None => TestHttpResponse::not_found(),
+        })
+    }
+
+    /// Return all requests received so far, in the order they were received.
+    pub fn requests(&self) -> Vec<TestHttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Return the paths of all requests received so far.
+    pub fn request_paths(&self) -> Vec<String> {
+        self.requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect()
+    }
+}
+
+fn read_test_http_request(stream: &mut TcpStream) -> Option<TestHttpRequest> {
+    use std::io::Read;
+
+    let mut buffer = vec![];
+    let mut chunk = vec![0; 8192];
+
+    // Read the head, and then the body based on its length
+    let index = loop {
+        let size = stream.read(&mut chunk).ok()?;
+
+        if size == 0 {
+            return None;
+        }
+
+        buffer.extend_from_slice(&chunk[0..size]);
+
+        if let Some(index) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[0..index]).to_string();
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split(' ');
+    let method = parts.next()?.to_owned();
+    let path = parts.next()?.to_owned();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_owned()))
+        .collect::<HashMap<_, _>>();
+    let length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or_default();
+
+    while buffer.len() < index + 4 + length {
+        let size = stream.read(&mut chunk).ok()?;
+
+        if size == 0 {
+            break;
+        }
+
+        buffer.extend_from_slice(&chunk[0..size]);
+    }
+
+    let body = buffer[index + 4..].iter().take(length).copied().collect();
+
+    Some(TestHttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn write_test_http_response(
+    stream: &mut TcpStream,
+    request: &TestHttpRequest,
+    response: TestHttpResponse,
+) {
+    use std::io::Write;
+
+    let reason = match response.status {
+        200 => "OK",
+        206 => "Partial Content",
+        403 => "Forbidden",
+        404 => "Not Found",
+        416 => "Range Not Satisfiable",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {} {reason}\r\ncontent-length: {}\r\nconnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+
+    head.push_str("\r\n");
+
+    // The client may have closed the connection early, which is expected
+    let _ = stream.write_all(head.as_bytes());
+
+    if request.method != "HEAD" {
+        let size = response
+            .truncate_body
+            .unwrap_or(response.body.len())
+            .min(response.body.len());
+
+        let _ = stream.write_all(&response.body[0..size]);
+    }
+
+    let _ = stream.flush();
+    let _ = stream.shutdown(Shutdown::Both);
+}
@@ -0,0 +1,451 @@
+use starbase_sandbox::create_empty_sandbox;
+use starbase_utils::fs;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use warpgate::test_utils::{TestHttpResponse, TestHttpServer};
+use warpgate::{
+    DownloadFileOptions, HttpOptions, create_http_client_with_options, get_partial_download_file,
+    get_partial_validator_file, split_into_segments,
+};
+
+fn create_body(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i % 251) as u8).collect()
+}
+
+const ETAG: &str = "\"abc\"";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Ranges {
+    // Doesn't advertise or support `Range` requests
+    Unsupported,
+    // Advertises and supports `Range` requests
+    Supported,
+    // Advertises `Range` requests, but always returns the full body
+    Ignored,
+}
+
+// Serve the body with support for `Range` and `If-Range` requests (when enabled).
+fn start_server(body: Vec<u8>, ranges: Ranges) -> (String, TestHttpServer) {
+    let server = TestHttpServer::start(move |request| {
+        let range = request
+            .header("range")
+            .and_then(|value| value.strip_prefix("bytes="))
+            .filter(|_| ranges == Ranges::Supported)
+            .filter(|_| request.header("if-range").is_none_or(|value| value == ETAG));
+
+        let mut response = match range {
+            Some(range) => {
+                let (start, end) = range.split_once('-').unwrap();
+                let start = start.parse::<usize>().unwrap();
+                let end = if end.is_empty() {
+                    body.len() - 1
+                } else {
+                    end.parse::<usize>().unwrap()
+                };
+
+                if start >= body.len() {
+                    TestHttpResponse::new(416, vec![])
+                } else {
+                    TestHttpResponse::new(206, &body[start..=end])
+                }
+            }
+            None => TestHttpResponse::ok(body.clone()),
+        }
+        .header("etag", ETAG);
+
+        if ranges != Ranges::Unsupported {
+            response = response.header("accept-ranges", "bytes");
+        }
+
+        response
+    });
+
+    (format!("{}/file.tar.gz", server.url), server)
+}
+
+// Return the `Range` header of each request received by the server.
+fn get_ranges(server: &TestHttpServer) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .map(|request| {
+            request
+                .header("range")
+                .and_then(|value| value.strip_prefix("bytes="))
+                .unwrap_or_default()
+                .to_owned()
+        })
+        .collect()
+}
+
+mod http_download {
+    use super::*;
+
+    #[test]
+    fn splits_into_segments() {
+        assert_eq!(split_into_segments(10, 1), vec![(0, 9)]);
+        assert_eq!(split_into_segments(10, 2), vec![(0, 4), (5, 9)]);
+        assert_eq!(split_into_segments(10, 3), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(split_into_segments(2, 4), vec![(0, 0), (1, 1)]);
+        assert_eq!(split_into_segments(0, 4), vec![]);
+    }
+
+    #[test]
+    fn returns_partial_file_path() {
+        let sandbox = create_empty_sandbox();
+        let file = sandbox.path().join("file.tar.gz");
+
+        assert_eq!(
+            get_partial_download_file(&file, None),
+            sandbox.path().join("file.tar.gz.part")
+        );
+        assert_eq!(
+            get_partial_download_file(&file, Some(2)),
+            sandbox.path().join("file.tar.gz.part2")
+        );
+    }
+
+    #[tokio::test]
+    async fn downloads_file() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, _) = start_server(body.clone(), Ranges::Unsupported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert!(!get_partial_download_file(&dest, None).exists());
+    }
+
+    #[tokio::test]
+    async fn resumes_from_partial_file() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, None), &body[0..4000]).unwrap();
+        fs::write_file(get_partial_validator_file(&dest), ETAG).unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert_eq!(get_ranges(&server), vec!["4000-"]);
+        assert!(!get_partial_validator_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn resumes_an_interrupted_download() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let body_clone = body.clone();
+        let count = AtomicU64::new(0);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        let server = TestHttpServer::start(move |_| {
+            // Close the connection before the body has been fully written,
+            // and then resume from where it was interrupted
+            if count.fetch_add(1, Ordering::Relaxed) == 0 {
+                TestHttpResponse::ok(body_clone.clone())
+                    .header("etag", ETAG)
+                    .truncate(4000)
+            } else {
+                TestHttpResponse::new(206, &body_clone[4000..])
+            }
+        });
+        let url = format!("{}/file.tar.gz", server.url);
+
+        let client = create_http_client_with_options(&HttpOptions::default()).unwrap();
+
+        assert!(
+            client
+                .download_file(&url, &dest, DownloadFileOptions::default())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            fs::read_file(get_partial_validator_file(&dest)).unwrap(),
+            ETAG
+        );
+
+        client
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+
+        let requests = server.requests();
+
+        assert_eq!(requests[1].header("range"), Some("bytes=4000-"));
+        assert_eq!(requests[1].header("if-range"), Some(ETAG));
+    }
+
+    #[tokio::test]
+    async fn starts_over_if_partial_file_has_no_validator() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, None), b"invalid").unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert_eq!(get_ranges(&server), vec![""]);
+    }
+
+    #[tokio::test]
+    async fn starts_over_if_file_has_changed() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, None), b"invalid").unwrap();
+        fs::write_file(get_partial_validator_file(&dest), "\"old\"").unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert_eq!(get_ranges(&server), vec!["7-"]);
+    }
+
+    #[tokio::test]
+    async fn starts_over_if_server_doesnt_support_ranges() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, _) = start_server(body.clone(), Ranges::Unsupported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, None), b"invalid").unwrap();
+        fs::write_file(get_partial_validator_file(&dest), ETAG).unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn starts_over_if_range_not_satisfiable() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, None), create_body(20_000)).unwrap();
+        fs::write_file(get_partial_validator_file(&dest), ETAG).unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(&url, &dest, DownloadFileOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert_eq!(get_ranges(&server), vec!["20000-", ""]);
+    }
+
+    #[tokio::test]
+    async fn downloads_large_files_in_segments() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(33 * 1024 * 1024);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        create_http_client_with_options(&HttpOptions {
+            download_segments: Some(3),
+            ..Default::default()
+        })
+        .unwrap()
+        .download_file(&url, &dest, DownloadFileOptions::default())
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+
+        let mut requests = get_ranges(&server);
+        requests.sort();
+
+        // HEAD request first, then each segment
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0], "");
+        assert!(requests[1].starts_with("0-"));
+
+        for index in 0..3 {
+            assert!(!get_partial_download_file(&dest, Some(index)).exists());
+        }
+
+        assert!(!get_partial_validator_file(&dest).exists());
+    }
+
+    #[tokio::test]
+    async fn resumes_segments_of_the_same_file() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(33 * 1024 * 1024);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, Some(0)), &body[0..1000]).unwrap();
+        fs::write_file(get_partial_validator_file(&dest), ETAG).unwrap();
+
+        create_http_client_with_options(&HttpOptions {
+            download_segments: Some(3),
+            ..Default::default()
+        })
+        .unwrap()
+        .download_file(&url, &dest, DownloadFileOptions::default())
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert!(
+            get_ranges(&server)
+                .iter()
+                .any(|range| range.starts_with("1000-"))
+        );
+    }
+
+    #[tokio::test]
+    async fn discards_segments_of_a_changed_file() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(33 * 1024 * 1024);
+        let (url, server) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        fs::write_file(get_partial_download_file(&dest, Some(0)), b"invalid").unwrap();
+        fs::write_file(get_partial_validator_file(&dest), "\"old\"").unwrap();
+
+        create_http_client_with_options(&HttpOptions {
+            download_segments: Some(3),
+            ..Default::default()
+        })
+        .unwrap()
+        .download_file(&url, &dest, DownloadFileOptions::default())
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert!(
+            get_ranges(&server)
+                .iter()
+                .any(|range| range.starts_with("0-"))
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_if_segment_is_not_partial_content() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(33 * 1024 * 1024);
+        let (url, _) = start_server(body.clone(), Ranges::Ignored);
+        let dest = sandbox.path().join("file.tar.gz");
+
+        let error = create_http_client_with_options(&HttpOptions {
+            download_segments: Some(3),
+            ..Default::default()
+        })
+        .unwrap()
+        .download_file(&url, &dest, DownloadFileOptions::default())
+        .await
+        .unwrap_err();
+
+        assert!(error.to_string().contains("did not return partial content"));
+        assert!(!dest.exists());
+    }
+
+    #[tokio::test]
+    async fn throttles_to_max_bandwidth() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(20_000);
+        let (url, _) = start_server(body.clone(), Ranges::Unsupported);
+        let dest = sandbox.path().join("file.tar.gz");
+        let started = std::time::Instant::now();
+
+        create_http_client_with_options(&HttpOptions {
+            max_bandwidth: Some(40_000),
+            ..Default::default()
+        })
+        .unwrap()
+        .download_file(&url, &dest, DownloadFileOptions::default())
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read_file_bytes(&dest).unwrap(), body);
+        assert!(started.elapsed().as_millis() >= 450);
+    }
+
+    #[tokio::test]
+    async fn reports_progress() {
+        let sandbox = create_empty_sandbox();
+        let body = create_body(10_000);
+        let (url, _) = start_server(body.clone(), Ranges::Supported);
+        let dest = sandbox.path().join("file.tar.gz");
+        let current = Arc::new(AtomicU64::new(0));
+        let total = Arc::new(AtomicU64::new(0));
+        let current_clone = Arc::clone(&current);
+        let total_clone = Arc::clone(&total);
+
+        fs::write_file(get_partial_download_file(&dest, None), &body[0..4000]).unwrap();
+        fs::write_file(get_partial_validator_file(&dest), ETAG).unwrap();
+
+        create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(
+                &url,
+                &dest,
+                DownloadFileOptions {
+                    on_chunk: Some(Arc::new(move |cur, tot| {
+                        current_clone.store(cur, Ordering::Relaxed);
+                        total_clone.store(tot, Ordering::Relaxed);
+                    })),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(current.load(Ordering::Relaxed), 10_000);
+        assert_eq!(total.load(Ordering::Relaxed), 10_000);
+    }
+
+    #[tokio::test]
+    async fn errors_if_not_found() {
+        let sandbox = create_empty_sandbox();
+        let server = TestHttpServer::start(|_| TestHttpResponse::not_found());
+        let url = format!("{}/missing.tar.gz", server.url);
+
+        let error = create_http_client_with_options(&HttpOptions::default())
+            .unwrap()
+            .download_file(
+                &url,
+                &sandbox.path().join("missing.tar.gz"),
+                DownloadFileOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("does not exist"));
+    }
+}
@@ -513,6 +513,31 @@ export interface LoadVersionsInput {
 	initial: UnresolvedVersionSpec;
 }
 
+/** Release metadata for a specific version. */
+export interface VersionMetadata {
+	/** Link to a security advisory that affects this version. */
+	advisoryUrl?: string | null;
+	/**
+	 * Date in which this version reaches end-of-life,
+	 * in ISO 8601 format (`YYYY-MM-DD`).
+	 */
+	eolDate?: string | null;
+	/** Codename of the long-term support line this version belongs to. */
+	lts?: string | null;
+	/**
+	 * Date in which this version was released,
+	 * in ISO 8601 format (`YYYY-MM-DD`).
+	 */
+	releaseDate?: string | null;
+	/** Link to the release notes or changelog for this version. */
+	releaseNotesUrl?: string | null;
+	/**
+	 * Whether this version has been yanked or deprecated. Yanked
+	 * versions are skipped during resolution, unless pinned exactly.
+	 */
+	yanked?: boolean;
+}
+
 /** Output returned by the `load_versions` function. */
 export interface LoadVersionsOutput {
 	/** Mapping of aliases (channels, etc) to a version. */
@@ -521,6 +546,8 @@ export interface LoadVersionsOutput {
 	canary?: UnresolvedVersionSpec | null;
 	/** Latest stable version. */
 	latest?: UnresolvedVersionSpec | null;
+	/** Mapping of versions to their release metadata (dates, EOL, LTS, etc). */
+	metadata?: Record<VersionSpec, VersionMetadata>;
 	/** List of available production versions to install. */
 	versions?: VersionSpec[];
 }
@@ -762,3 +789,46 @@ export interface BuildInstructionsOutput {
 	 */
 	systemDependencies?: SystemDependency[];
 }
+
+/** Operations that are checked against policy plugins. */
+export type PolicyOperation = 'install' | 'pin';
+
+/** A record from the lockfile for the version being checked. */
+export interface PolicyLockRecord {
+	/** Checksum of the downloaded archive. */
+	checksum?: Checksum | null;
+	/** URL the archive was downloaded from. */
+	source?: string | null;
+	/** The resolved version. */
+	version?: VersionSpec | null;
+}
+
+/** Input passed to the `check_policy` function. */
+export interface PolicyCheckInput {
+	/** Identifier of the backend, if the tool is using one. */
+	backend?: Id | null;
+	/** Current policy context. */
+	context: PluginUnresolvedContext;
+	/** Identifier of the tool being checked. */
+	id: Id;
+	/**
+	 * The operation being performed.
+	 *
+	 * @type {'install' | 'pin' | 'run'}
+	 */
+	operation: PolicyOperation;
+	/** The matching lockfile record, if one exists. */
+	record?: PolicyLockRecord | null;
+	/** The version specification that was requested. */
+	spec: UnresolvedVersionSpec;
+	/** The resolved version, if it has been resolved. */
+	version?: VersionSpec | null;
+}
+
+/** Output returned by the `check_policy` function. */
+export interface PolicyCheckOutput {
+	/** Whether the operation is allowed. */
+	allowed: boolean;
+	/** A message explaining why the operation was denied. */
+	message?: string | null;
+}
@@ -135,7 +135,21 @@ pub fn load_versions(Json(_): Json<LoadVersionsInput>) -> FnResult<Json<LoadVers
     tags.push("6.0.0-rc.1".into());
     tags.push("canary".into());
 
-    Ok(Json(LoadVersionsOutput::from(tags)?))
+    let mut output = LoadVersionsOutput::from(tags)?;
+
+    for version in &output.versions {
+        output.metadata.insert(
+            version.clone(),
+            VersionMetadata {
+                release_notes_url: Some(format!(
+                    "https://github.com/moonrepo/protostar/releases/tag/v{version}"
+                )),
+                ..Default::default()
+            },
+        );
+    }
+
+    Ok(Json(output))
 }
 
 #[plugin_fn]
@@ -161,10 +175,72 @@ pub fn resolve_version(
     Ok(Json(output))
 }
 
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct MockedConfig {
+    build_dependencies: Option<Vec<String>>,
+    build_instructions: Vec<String>,
+    checksum_url: Option<String>,
+    download_url: Option<String>,
+}
+
+#[plugin_fn]
+pub fn build_instructions(
+    Json(_): Json<BuildInstructionsInput>,
+) -> FnResult<Json<BuildInstructionsOutput>> {
+    let config = get_tool_config::<MockedConfig>()?;
+
+    // Only support building when configured
+    let Some(deps) = config.build_dependencies else {
+        return Err(plugin_err!(
+            "Building from source is not supported for {}",
+            get_plugin_id()?
+        ));
+    };
+
+    Ok(Json(BuildInstructionsOutput {
+        system_dependencies: vec![SystemDependency::names(deps)],
+        instructions: config
+            .build_instructions
+            .into_iter()
+            .map(|command| {
+                let mut args = command.split_whitespace();
+                let exe = args.next().unwrap_or_default();
+
+                BuildInstruction::RunCommand(Box::new(CommandInstruction::new(exe, args)))
+            })
+            .collect(),
+        ..Default::default()
+    }))
+}
+
+#[plugin_fn]
+pub fn download_prebuilt(
+    Json(_): Json<DownloadPrebuiltInput>,
+) -> FnResult<Json<DownloadPrebuiltOutput>> {
+    let config = get_tool_config::<MockedConfig>()?;
+
+    Ok(Json(DownloadPrebuiltOutput {
+        checksum_url: config.checksum_url,
+        download_url: config.download_url.unwrap_or_default(),
+        ..Default::default()
+    }))
+}
+
 #[plugin_fn]
 pub fn native_install(
     Json(input): Json<NativeInstallInput>,
 ) -> FnResult<Json<NativeInstallOutput>> {
+    let config = get_tool_config::<MockedConfig>()?;
+
+    // Install from a prebuilt or build from source instead, when configured
+    if config.download_url.is_some() || !config.build_instructions.is_empty() {
+        return Ok(Json(NativeInstallOutput {
+            skip_install: true,
+            ..Default::default()
+        }));
+    }
+
     let id = get_plugin_id()?;
     let env = get_host_environment()?;
     let version = &input.context.version;
@@ -240,3 +316,21 @@ pub fn locate_executables(
 
     Ok(Json(output))
 }
+
+#[plugin_fn]
+pub fn check_policy(Json(input): Json<PolicyCheckInput>) -> FnResult<Json<PolicyCheckOutput>> {
+    // Deny the oldest major when used as a policy
+    if input
+        .version
+        .as_ref()
+        .and_then(|version| version.as_version())
+        .is_some_and(|version| version.major == 1)
+    {
+        return Ok(Json(PolicyCheckOutput::deny(format!(
+            "{} v1 is no longer supported",
+            input.id
+        ))));
+    }
+
+    Ok(Json(PolicyCheckOutput::allow()))
+}